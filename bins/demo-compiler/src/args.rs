@@ -2,7 +2,7 @@ use anyhow::Result;
 use camino::Utf8PathBuf;
 use clap::Parser;
 use scarb::compiler::Profile;
-use scarb_ui::Verbosity;
+use scarb_ui::{OutputFormat, Verbosity};
 use smol_str::SmolStr;
 use tracing::level_filters::LevelFilter;
 use tracing_log::{AsTrace, LogTracer};
@@ -29,6 +29,14 @@ pub struct CompilerArgs {
     #[command(flatten)]
     pub verbose: clap_verbosity_flag::Verbosity,
 
+    /// Print the messages as JSON lines, for editors and CI annotators.
+    #[arg(
+        long,
+        global = true,
+        help = "Print diagnostics, artifacts and test results as JSON lines."
+    )]
+    pub json: bool,
+
     /// Run without accessing the network for scarb dependencies.
     #[arg(short, long, help = "Run without accessing the network.")]
     pub offline: bool,
@@ -49,6 +57,14 @@ impl CompilerArgs {
         }
     }
 
+    pub fn ui_output_format(&self) -> OutputFormat {
+        if self.json {
+            OutputFormat::Json
+        } else {
+            OutputFormat::Text
+        }
+    }
+
     pub fn init_logging(&self) -> Result<(), Box<dyn std::error::Error>> {
         const DEFAULT_LOG_FILTER: &str = "info,hyper=off,scarb=off,salsa=off";
 
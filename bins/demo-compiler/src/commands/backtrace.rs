@@ -0,0 +1,49 @@
+use std::io::Read;
+
+use anyhow::{Context, Result};
+use camino::Utf8PathBuf;
+use clap::Args;
+use dojo_compiler::compiler::backtrace::{parse_statement_indices, read_debug_info, symbolize};
+use scarb_ui::Ui;
+
+#[derive(Debug, Args)]
+pub struct BacktraceArgs {
+    #[arg(
+        help = "The debug info of the contract (`<name>.debug.json`), written by `build \
+                  --output-debug-info`."
+    )]
+    pub debug_info: Utf8PathBuf,
+
+    #[arg(
+        help = "The Sierra statement indices to symbolize, the innermost first. Read from \
+                  the standard input if omitted."
+    )]
+    #[arg(value_delimiter = ',')]
+    pub statements: Vec<String>,
+}
+
+impl BacktraceArgs {
+    pub fn run(self, ui: &Ui) -> Result<()> {
+        let debug_info = read_debug_info(&self.debug_info)?;
+
+        let input = if self.statements.is_empty() {
+            let mut input = String::new();
+            std::io::stdin()
+                .read_to_string(&mut input)
+                .context("Failed to read the Sierra statement indices from stdin.")?;
+            input
+        } else {
+            self.statements.join(" ")
+        };
+
+        let statement_indices = parse_statement_indices(&input)?;
+
+        if statement_indices.is_empty() {
+            anyhow::bail!("No Sierra statement index to symbolize.");
+        }
+
+        ui.print(symbolize(&debug_info, &statement_indices).to_string());
+
+        Ok(())
+    }
+}
@@ -1,8 +1,14 @@
 use anyhow::Result;
-use clap::{Args, Parser};
+use clap::{Args, Parser, ValueEnum};
+use dojo_compiler::compiler::annotation::DojoAnnotation;
+use dojo_compiler::compiler::config::select_dojo_packages;
+use dojo_compiler::compiler::messages;
+use dojo_compiler::compiler::size_report::{ContractSize, SizeReport};
 use dojo_compiler::compiler::DojoCompiler;
 use scarb::core::Config;
 use scarb_ui::args::{FeaturesSpec, PackagesFilter};
+use scarb_ui::Message;
+use serde::{Serialize, Serializer};
 
 #[derive(Debug, Args)]
 pub struct BuildArgs {
@@ -15,13 +21,100 @@ pub struct BuildArgs {
     pub packages: Option<PackagesFilter>,
 
     #[arg(long)]
-    #[arg(help = "Output the Sierra debug information for the compiled contracts.")]
+    #[arg(
+        help = "Output the Sierra debug information for the compiled contracts, used by the `backtrace` command."
+    )]
     pub output_debug_info: bool,
+
+    #[arg(long)]
+    #[arg(help = "Rebuild all the contracts, ignoring the artifacts of the previous build.")]
+    pub no_cache: bool,
+
+    #[arg(long)]
+    #[arg(
+        help = "Fail if the class hashes differ from the lock file, instead of updating it. \
+                  Implies `--no-cache`, the target directory being left untouched on failure."
+    )]
+    pub locked: bool,
+
+    #[arg(long)]
+    #[arg(
+        help = "Build packages depending on a dojo version not supported by the compiler, \
+                  only warning about the mismatch."
+    )]
+    pub allow_incompatible_dojo: bool,
+
+    #[arg(long, value_enum, num_args = 0..=1, default_missing_value = "text")]
+    #[arg(
+        help = "Print the size of the compiled contracts after the build, as a table (`text`) \
+                  or as JSON (`json`). Compiles the CASM of the contracts to compute their size."
+    )]
+    pub size_report: Option<SizeReportFormat>,
+
+    #[arg(long)]
+    #[arg(help = "Print the namespace mapping rule applied to each resource after the build.")]
+    pub namespace_report: bool,
+}
+
+/// The format of the size report printed after the build.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SizeReportFormat {
+    Text,
+    Json,
 }
 
 impl BuildArgs {
     pub fn run(self, config: &Config) -> Result<()> {
-        DojoCompiler::compile_workspace(config, self.packages, self.features)
+        DojoCompiler::compile_workspace(
+            config,
+            self.packages.clone(),
+            self.features,
+            // With `--locked`, the previous artifacts are kept until the classes are verified.
+            !self.no_cache || self.locked,
+            self.allow_incompatible_dojo,
+        )?;
+
+        if self.size_report.is_none() && !self.namespace_report {
+            return Ok(());
+        }
+
+        let ws = scarb::ops::read_workspace(config.manifest_path(), config)?;
+        let packages = select_dojo_packages(&ws, self.packages.as_ref())?;
+
+        // The JSON output only contains JSON messages, the size report included.
+        let size_report = self.size_report.map(|format| {
+            if messages::is_json(&config.ui()) {
+                SizeReportFormat::Json
+            } else {
+                format
+            }
+        });
+
+        for package in &packages {
+            if packages.len() > 1 && size_report != Some(SizeReportFormat::Json) {
+                config.ui().print(format!("package `{}`", package.id.name));
+            }
+
+            match size_report {
+                Some(SizeReportFormat::Text) => {
+                    print_size_report(&SizeReport::read(&ws, package)?, config)
+                }
+                Some(SizeReportFormat::Json) => {
+                    // One JSON line per package.
+                    config.ui().print(SizeReportMessage {
+                        package: package.id.name.to_string(),
+                        contracts: SizeReport::read(&ws, package)?.contracts,
+                    });
+                }
+                None => {}
+            }
+
+            if self.namespace_report {
+                print_namespace_report(&DojoAnnotation::read(&ws, package)?, config);
+            }
+        }
+
+        Ok(())
     }
 }
 
@@ -34,6 +127,99 @@ impl Default for BuildArgs {
             features,
             packages: None,
             output_debug_info: false,
+            no_cache: false,
+            locked: false,
+            allow_incompatible_dojo: false,
+            size_report: None,
+            namespace_report: false,
         }
     }
 }
+
+/// The size report of a package, printed as a JSON line.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename = "size_report")]
+struct SizeReportMessage {
+    package: String,
+    contracts: Vec<ContractSize>,
+}
+
+impl Message for SizeReportMessage {
+    fn text(self) -> String {
+        serde_json::to_string(&self).expect("size report should serialize")
+    }
+
+    fn structured<S: Serializer>(self, ser: S) -> Result<S::Ok, S::Error> {
+        self.serialize(ser)
+    }
+}
+
+/// Prints the size report as a table, one contract per line.
+fn print_size_report(report: &SizeReport, config: &Config) {
+    let ui = config.ui();
+
+    ui.print(format!(
+        "{:<50} {:>8} {:>8} {:>11} {:>8} {:>8} {:>9} {:>13} {:>8}",
+        "contract",
+        "sierra",
+        "casm",
+        "entrypoints",
+        "user",
+        "dojo",
+        "starknet",
+        "dependencies",
+        "unknown"
+    ));
+
+    for c in &report.contracts {
+        let casm = c
+            .casm_bytecode_size
+            .map_or("-".to_string(), |size| size.to_string());
+
+        ui.print(format!(
+            "{:<50} {:>8} {:>8} {:>11} {:>8} {:>8} {:>9} {:>13} {:>8}",
+            c.qualified_path,
+            c.sierra_program_length,
+            casm,
+            c.entrypoints,
+            c.statements.user,
+            c.statements.dojo,
+            c.statements.starknet,
+            c.statements.dependencies,
+            c.statements.unknown
+        ));
+    }
+}
+
+/// Prints the namespace mapping rule of each resource, one resource per line.
+fn print_namespace_report(annotation: &DojoAnnotation, config: &Config) {
+    let ui = config.ui();
+
+    let resources = annotation
+        .contracts
+        .iter()
+        .map(|c| ("contract", &c.tag, &c.namespace_rule))
+        .chain(
+            annotation
+                .models
+                .iter()
+                .map(|m| ("model", &m.tag, &m.namespace_rule)),
+        )
+        .chain(
+            annotation
+                .events
+                .iter()
+                .map(|e| ("event", &e.tag, &e.namespace_rule)),
+        )
+        .chain(annotation.sn_contracts.iter().filter_map(|c| {
+            c.tag
+                .as_ref()
+                .map(|tag| ("starknet", tag, &c.namespace_rule))
+        }));
+
+    ui.print(format!("{:<10} {:<50} {}", "kind", "tag", "rule"));
+
+    for (kind, tag, rule) in resources {
+        ui.print(format!("{:<10} {:<50} {}", kind, tag, rule));
+    }
+}
@@ -10,6 +10,14 @@ pub struct CleanArgs {
     #[arg(long)]
     #[arg(help = "Clean all profiles.")]
     pub all_profiles: bool,
+
+    #[arg(long)]
+    #[arg(conflicts_with = "all_profiles")]
+    #[arg(
+        help = "Only remove the artifacts of the resources which no longer exist, without \
+                  rebuilding."
+    )]
+    pub stale: bool,
 }
 
 impl CleanArgs {
@@ -17,6 +25,10 @@ impl CleanArgs {
         let ws = scarb::ops::read_workspace(config.manifest_path(), config)?;
         trace!(ws=?ws, "Workspace read successfully.");
 
+        if self.stale {
+            return DojoCompiler::clean_stale(config);
+        }
+
         let profile_spec = if self.all_profiles {
             ProfileSpec::All
         } else {
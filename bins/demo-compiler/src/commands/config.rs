@@ -0,0 +1,99 @@
+use anyhow::{Context, Result};
+use clap::Args;
+use dojo_compiler::compiler::config::{
+    config_paths, display_paths, interpolate_sections, load_merged_config, select_dojo_packages,
+};
+use dojo_compiler::compiler::profile_config::ProfileConfig;
+use scarb::core::{Config, Package, Workspace};
+use scarb_ui::args::PackagesFilter;
+use serde::Deserialize;
+use toml::Value;
+use tracing::trace;
+
+/// Keys of the `[env]` section holding secrets, redacted unless requested.
+const SECRET_ENV_KEYS: [&str; 2] = ["private_key", "keystore_password"];
+
+#[derive(Debug, Args)]
+pub struct ConfigArgs {
+    #[arg(long)]
+    #[arg(help = "Print the secrets of the [env] section instead of redacting them.")]
+    pub show_secrets: bool,
+
+    /// Specify the packages to print the configuration of.
+    #[command(flatten)]
+    pub packages: Option<PackagesFilter>,
+}
+
+impl ConfigArgs {
+    pub fn run(self, config: &Config) -> Result<()> {
+        let ws = scarb::ops::read_workspace(config.manifest_path(), config)?;
+        trace!(ws=?ws, "Workspace read successfully.");
+
+        let packages = select_dojo_packages(&ws, self.packages.as_ref())?;
+
+        if packages.is_empty() {
+            anyhow::bail!("No package with [[target.dojo]] found in workspace.");
+        }
+
+        for package in &packages {
+            self.print_package_config(&ws, package, config)?;
+        }
+
+        Ok(())
+    }
+
+    /// Prints the merged configuration of a dojo package.
+    fn print_package_config(
+        &self,
+        ws: &Workspace<'_>,
+        package: &Package,
+        config: &Config,
+    ) -> Result<()> {
+        let paths = config_paths(package, ws)?;
+        let profile = ws.current_profile()?;
+        let name = &package.id.name;
+
+        let Some(mut merged) = load_merged_config(&paths)? else {
+            config.ui().warn(format!(
+                "No Dojo configuration file found for package `{name}` and profile `{profile}`."
+            ));
+            return Ok(());
+        };
+
+        interpolate_sections(&mut merged)?;
+
+        // Only print a configuration the compiler would accept.
+        ProfileConfig::deserialize(merged.clone()).with_context(|| {
+            format!(
+                "Invalid Dojo configuration (merged from {}).",
+                display_paths(&paths)
+            )
+        })?;
+
+        if !self.show_secrets {
+            redact_secrets(&mut merged);
+        }
+
+        config.ui().print(format!(
+            "# Effective Dojo configuration of package `{name}` for profile `{profile}`, merged \
+             from {}.\n{}",
+            display_paths(&paths),
+            toml::to_string_pretty(&merged)?
+        ));
+
+        Ok(())
+    }
+}
+
+/// Redacts the secrets of the `[env]` section.
+fn redact_secrets(config: &mut Value) {
+    let Some(env) = config.get_mut("env").and_then(Value::as_table_mut) else {
+        return;
+    };
+
+    for key in SECRET_ENV_KEYS {
+        if let Some(value) = env.get_mut(key) {
+            *value = Value::from("<redacted>");
+        }
+    }
+}
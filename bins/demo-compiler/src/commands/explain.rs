@@ -0,0 +1,34 @@
+use anyhow::Result;
+use clap::Args;
+use dojo_compiler::diagnostic_codes::{self, REGISTRY};
+use scarb_ui::Ui;
+
+#[derive(Debug, Args)]
+pub struct ExplainArgs {
+    #[arg(
+        help = "The diagnostic code to explain, like `DOJO012`. Lists all the codes if omitted."
+    )]
+    pub code: Option<String>,
+}
+
+impl ExplainArgs {
+    pub fn run(self, ui: &Ui) -> Result<()> {
+        let Some(code) = self.code else {
+            for code in REGISTRY {
+                ui.print(format!("{}: {}", code.code, code.title));
+            }
+
+            return Ok(());
+        };
+
+        let Some(code) = diagnostic_codes::find(&code) else {
+            anyhow::bail!(
+                "Unknown diagnostic code `{code}`. Run `explain` without code to list the codes."
+            );
+        };
+
+        ui.print(code.to_string());
+
+        Ok(())
+    }
+}
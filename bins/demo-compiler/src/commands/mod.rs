@@ -4,26 +4,38 @@ use anyhow::Result;
 use clap::Subcommand;
 use scarb::core::Config;
 
+pub(crate) mod backtrace;
 pub(crate) mod build;
 pub(crate) mod clean;
+pub(crate) mod config;
 pub(crate) mod dev;
+pub(crate) mod explain;
 pub(crate) mod test;
 
+use backtrace::BacktraceArgs;
 use build::BuildArgs;
 use clean::CleanArgs;
+use config::ConfigArgs;
 use dev::DevArgs;
+use explain::ExplainArgs;
 use test::TestArgs;
 
 use tracing::info_span;
 
 #[derive(Debug, Subcommand)]
 pub enum Commands {
+    #[command(about = "Map Sierra statements of a failure back to the Cairo source")]
+    Backtrace(BacktraceArgs),
     #[command(about = "Build the world, generating the necessary artifacts for deployment")]
     Build(BuildArgs),
     #[command(about = "Remove generated artifacts, manifests and abis")]
     Clean(CleanArgs),
+    #[command(about = "Print the effective Dojo configuration of the current profile")]
+    Config(ConfigArgs),
     #[command(about = "Developer mode: watcher for building and migration")]
     Dev(DevArgs),
+    #[command(about = "Explain a diagnostic code, with a wrong and a right example")]
+    Explain(ExplainArgs),
     #[command(about = "Test the project's smart contracts")]
     Test(TestArgs),
 }
@@ -31,9 +43,12 @@ pub enum Commands {
 impl fmt::Display for Commands {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
+            Commands::Backtrace(_) => write!(f, "Backtrace"),
             Commands::Build(_) => write!(f, "Build"),
             Commands::Clean(_) => write!(f, "Clean"),
+            Commands::Config(_) => write!(f, "Config"),
             Commands::Dev(_) => write!(f, "Dev"),
+            Commands::Explain(_) => write!(f, "Explain"),
             Commands::Test(_) => write!(f, "Test"),
         }
     }
@@ -48,9 +63,12 @@ pub fn run(command: Commands, config: &Config) -> Result<()> {
     // useful to write tests for each command.
 
     match command {
+        Commands::Backtrace(args) => args.run(&config.ui()),
         Commands::Clean(args) => args.run(config),
+        Commands::Config(args) => args.run(config),
         Commands::Test(args) => args.run(config),
         Commands::Build(args) => args.run(config),
         Commands::Dev(args) => args.run(config),
+        Commands::Explain(args) => args.run(&config.ui()),
     }
 }
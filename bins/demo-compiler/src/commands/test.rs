@@ -1,5 +1,7 @@
 //! Compiles and runs tests for a Dojo project.
-use anyhow::{bail, Result};
+use std::collections::BTreeMap;
+
+use anyhow::{bail, Context, Result};
 use cairo_lang_compiler::db::RootDatabase;
 use cairo_lang_compiler::diagnostics::DiagnosticsReporter;
 use cairo_lang_compiler::project::{ProjectConfig, ProjectConfigContent};
@@ -8,15 +10,19 @@ use cairo_lang_filesystem::ids::Directory;
 use cairo_lang_starknet::starknet_plugin_suite;
 use cairo_lang_test_plugin::{test_plugin_suite, TestsCompilationConfig};
 use cairo_lang_test_runner::{CompiledTestRunner, RunProfilerConfig, TestCompiler, TestRunConfig};
+use camino::Utf8PathBuf;
 use clap::Args;
 use dojo_compiler::compiler::cairo_compiler::{
     collect_all_crate_ids, collect_crates_ids_from_selectors, collect_main_crate_ids, Props,
 };
 use dojo_compiler::compiler::config::{CompilerConfig, DojoConfigLoader};
+use dojo_compiler::compiler::messages::{self, DiagnosticMessage, StatusMessage};
+use dojo_compiler::compiler::scarb_internal::debug::get_generated_code_locations;
 use dojo_compiler::compiler::scarb_internal::{
     cfg_set_from_component, crates_config_for_compilation_unit,
 };
 use dojo_compiler::compiler::version::check_package_dojo_version;
+use dojo_compiler::diagnostic_codes;
 use dojo_compiler::plugin::dojo_plugin_suite;
 use scarb::compiler::{CairoCompilationUnit, CompilationUnit, CompilationUnitAttributes};
 use scarb::core::{Config, Package, TargetKind};
@@ -24,6 +30,11 @@ use scarb::ops::{self, CompileOpts};
 use scarb_ui::args::{FeaturesSpec, PackagesFilter};
 use tracing::trace;
 
+use crate::coverage::{Coverage, StatementLines, COVERAGE_ANNOTATION};
+use crate::gas_snapshot::{parse_tolerance, GasSnapshot};
+use crate::test_report::{TestReport, TestSuite};
+use crate::test_runner::{list_tests, run_tests, Shard, TestRunOptions, TestStatus};
+
 #[derive(Debug, Clone, PartialEq, clap::ValueEnum)]
 pub enum ProfilerMode {
     None,
@@ -47,6 +58,15 @@ pub struct TestArgs {
     /// The filter for the tests, running only tests containing the filter string.
     #[arg(short, long, default_value_t = String::default())]
     filter: String,
+    /// Run only the test whose full name is the filter.
+    #[arg(long, default_value_t = false, requires = "filter")]
+    exact: bool,
+    /// Run only the `i`-th of `n` shards of the tests, the tests being sorted by name.
+    #[arg(long, value_name = "i/n")]
+    shard: Option<String>,
+    /// List the tests with their attributes, without running them.
+    #[arg(long, default_value_t = false)]
+    list: bool,
     /// Should we run ignored tests as well.
     #[arg(long, default_value_t = false)]
     include_ignored: bool,
@@ -62,6 +82,25 @@ pub struct TestArgs {
     /// Should we print the resource usage.
     #[arg(long, default_value_t = false)]
     print_resource_usage: bool,
+    /// Test packages depending on a dojo version not supported by the compiler, only
+    /// warning about the mismatch.
+    #[arg(long, default_value_t = false)]
+    allow_incompatible_dojo: bool,
+    /// Write a report of the test results, in the `junit` or `json` format, to the given path.
+    #[arg(long, num_args = 2, value_names = ["FORMAT", "PATH"])]
+    report: Option<Vec<String>>,
+    /// Write the gas and the resources used by each test to the gas snapshot of the package.
+    #[arg(long, default_value_t = false)]
+    gas_snapshot: bool,
+    /// Compare the gas used by each test with the gas snapshot of the package, failing if it
+    /// increased by more than the tolerance (in percent, 0 by default).
+    #[arg(long, num_args = 0..=1, default_missing_value = "0", value_name = "TOLERANCE%")]
+    #[arg(conflicts_with = "gas_snapshot")]
+    check_gas: Option<String>,
+    /// Write the coverage of the package sources by the tests, in the LCOV format, to the given
+    /// path (`target/coverage/lcov.info` by default).
+    #[arg(long, value_name = "PATH")]
+    coverage: Option<Option<Utf8PathBuf>>,
     /// Specify the features to activate.
     #[command(flatten)]
     features: FeaturesSpec,
@@ -84,12 +123,53 @@ impl TestArgs {
             ws.members().collect()
         };
 
-        for p in &packages {
-            check_package_dojo_version(&ws, p)?;
+        let report = self
+            .report
+            .as_deref()
+            .map(TestReport::from_args)
+            .transpose()?;
+
+        if report.is_some() && self.profiler_mode != ProfilerMode::None {
+            bail!("The profiler can't be used with a test report.");
+        }
+
+        let shard = self.shard.as_deref().map(Shard::parse).transpose()?;
+
+        if (self.exact || shard.is_some()) && self.profiler_mode != ProfilerMode::None {
+            bail!("The profiler can't be used with `--exact` or `--shard`.");
+        }
+
+        let gas_tolerance = self.check_gas.as_deref().map(parse_tolerance).transpose()?;
+
+        if self.gas_snapshot || gas_tolerance.is_some() {
+            if !self.gas_enabled {
+                bail!("Gas snapshots require the tests to run with gas.");
+            }
+
+            if self.profiler_mode != ProfilerMode::None {
+                bail!("The profiler can't be used with gas snapshots.");
+            }
+        }
+
+        let coverage_path = self.coverage.clone().map(|path| {
+            path.unwrap_or_else(|| {
+                ws.target_dir()
+                    .path_unchecked()
+                    .join("coverage")
+                    .join("lcov.info")
+            })
+        });
+
+        if coverage_path.is_some() && self.profiler_mode != ProfilerMode::None {
+            bail!("The profiler can't be used with the coverage.");
         }
 
         let resolve = ops::resolve_workspace(&ws)?;
 
+        for p in &packages {
+            check_package_dojo_version(&ws, p, &resolve, self.allow_incompatible_dojo)?;
+        }
+
         let opts = CompileOpts {
             include_target_kinds: vec![TargetKind::TEST],
             exclude_target_kinds: vec![],
@@ -122,6 +202,11 @@ impl TestArgs {
             })
             .collect::<Vec<_>>();
 
+        let mut suites = vec![];
+        let mut gas_regressions = 0;
+        let mut gas_snapshots: BTreeMap<Utf8PathBuf, GasSnapshot> = BTreeMap::new();
+        let mut coverage = Coverage::default();
+
         for unit in compilation_units {
             let mut unit = if let CompilationUnit::Cairo(unit) = unit {
                 unit
@@ -129,10 +214,14 @@ impl TestArgs {
                 continue;
             };
 
-            config.ui().print(format!("testing {}", unit.name()));
+            config.ui().print(StatusMessage::new(
+                "testing",
+                format!("testing {}", unit.name()),
+            ));
 
+            let root_package = unit.components[0].package.clone();
             let compiler_config: CompilerConfig =
-                DojoConfigLoader::from_package(&unit.components[0].package, &ws)?;
+                DojoConfigLoader::from_package(&root_package, &ws)?;
 
             // For each component in the compilation unit (namely, the dependencies being
             // compiled) we inject into the `CfgSet` the component name and
@@ -142,6 +231,7 @@ impl TestArgs {
             for c in unit.components.iter_mut() {
                 c.cfg_set = Some(cfg_set_from_component(
                     c,
+                    &root_package,
                     &compiler_config.namespace,
                     &config.ui(),
                     &ws,
@@ -159,27 +249,32 @@ impl TestArgs {
             let props: Props = unit.main_component().target_props()?;
             let db = build_root_database(&unit)?;
 
-            if DiagnosticsReporter::stderr().allow_warnings().check(&db) {
+            let diagnostics_reporter = if messages::is_json(&config.ui()) {
+                messages::diagnostics_reporter(config.ui(), &[], true)
+            } else {
+                DiagnosticsReporter::stderr().allow_warnings()
+            };
+
+            if diagnostics_reporter.check(&db) {
                 bail!("failed to compile");
             }
 
             let test_crate_ids = collect_main_crate_ids(&unit, &db, false);
 
+            // The locations of the generated code are looked up in the compiled crates, before
+            // the database is moved to the test compiler.
+            let generated_code_locations = if coverage_path.is_some() {
+                get_generated_code_locations(&db, &test_crate_ids)
+            } else {
+                Default::default()
+            };
+
             let mut main_crate_ids = collect_all_crate_ids(&unit, &db);
 
             if let Some(external_contracts) = props.build_external_contracts {
                 main_crate_ids.extend(collect_crates_ids_from_selectors(&db, &external_contracts));
             }
 
-            let config = TestRunConfig {
-                filter: self.filter.clone(),
-                ignored: self.ignored,
-                include_ignored: self.include_ignored,
-                run_profiler: self.profiler_mode.clone().into(),
-                gas_enabled: self.gas_enabled,
-                print_resource_usage: self.print_resource_usage,
-            };
-
             let compiler = TestCompiler {
                 db: db.snapshot(),
                 main_crate_ids,
@@ -188,17 +283,194 @@ impl TestArgs {
                 config: TestsCompilationConfig {
                     starknet: true,
                     add_statements_functions: false,
-                    add_statements_code_locations: false,
+                    add_statements_code_locations: coverage_path.is_some(),
                 },
             };
 
             let compiled = compiler.build()?;
-            let runner = CompiledTestRunner { compiled, config };
 
-            // Database is required here for the profiler to work.
-            runner.run(Some(&db))?;
+            let options = TestRunOptions {
+                filter: self.filter.clone(),
+                exact: self.exact,
+                shard,
+                ignored: self.ignored,
+                include_ignored: self.include_ignored,
+                gas_enabled: self.gas_enabled,
+                print_resource_usage: self.print_resource_usage,
+                coverage: coverage_path.is_some(),
+            };
+
+            if self.list {
+                list_tests(&compiled.metadata, &options, &config.ui());
+                continue;
+            }
+
+            // The results of each test are only collected when required, the Cairo test
+            // runner being used otherwise.
+            let collect_results = messages::is_json(&config.ui())
+                || report.is_some()
+                || self.gas_snapshot
+                || gas_tolerance.is_some()
+                || self.exact
+                || shard.is_some()
+                || coverage_path.is_some();
+
+            if self.profiler_mode != ProfilerMode::None || !collect_results {
+                if messages::is_json(&config.ui()) {
+                    bail!("The profiler can't be used with the JSON output.");
+                }
+
+                let test_config = TestRunConfig {
+                    filter: self.filter.clone(),
+                    ignored: self.ignored,
+                    include_ignored: self.include_ignored,
+                    run_profiler: self.profiler_mode.clone().into(),
+                    gas_enabled: self.gas_enabled,
+                    print_resource_usage: self.print_resource_usage,
+                };
 
-            println!();
+                let runner = CompiledTestRunner {
+                    compiled,
+                    config: test_config,
+                };
+
+                // Database is required here for the profiler to work.
+                runner.run(Some(&db))?;
+
+                println!();
+                continue;
+            }
+
+            let statement_lines = if options.coverage {
+                let annotation = compiled
+                    .sierra_program
+                    .debug_info
+                    .as_ref()
+                    .and_then(|info| info.annotations.get(COVERAGE_ANNOTATION));
+
+                Some(StatementLines::from_annotation(
+                    annotation,
+                    unit.main_component().package.root(),
+                    &generated_code_locations,
+                )?)
+            } else {
+                None
+            };
+
+            let summary = run_tests(compiled, &options, &config.ui())?;
+            summary.report(&config.ui());
+
+            if let Some(lines) = &statement_lines {
+                let mut unit_coverage = Coverage::new(lines);
+
+                for result in &summary.results {
+                    unit_coverage.record(lines, &result.executed_statements);
+                }
+
+                coverage.merge(unit_coverage);
+
+                let calling_tests = summary
+                    .results
+                    .iter()
+                    .filter(|result| result.calls_contracts())
+                    .count();
+
+                if calling_tests > 0 {
+                    messages::emit(
+                        &config.ui(),
+                        DiagnosticMessage::warning(
+                            diagnostic_codes::NESTED_CALLS_NOT_COVERED.code,
+                            format!(
+                                "{calling_tests} tests of `{}` call or deploy contracts, the \
+                                 code run by these contracts is not covered.",
+                                unit.name()
+                            ),
+                        ),
+                    );
+                }
+            }
+
+            let snapshot_path = GasSnapshot::path(unit.main_component().package.root());
+            let snapshot = GasSnapshot::from_results(&summary.results);
+
+            if let Some(tolerance) = gas_tolerance {
+                let baseline = GasSnapshot::read(&snapshot_path)?.with_context(|| {
+                    format!(
+                        "Gas snapshot `{snapshot_path}` not found, run the tests with \
+                         `--gas-snapshot` to write it."
+                    )
+                })?;
+
+                let diff = baseline.compare(&snapshot, tolerance);
+                diff.report(&config.ui());
+                gas_regressions += diff.regressions.len();
+            }
+
+            if self.gas_snapshot {
+                // The units of a package share its snapshot, written once all of them run.
+                gas_snapshots
+                    .entry(snapshot_path)
+                    .or_default()
+                    .merge(snapshot);
+            }
+
+            suites.push(TestSuite {
+                name: unit.name(),
+                summary,
+            });
+        }
+
+        if let Some(report) = report {
+            report.write(&suites)?;
+        }
+
+        let is_partial_run =
+            !self.filter.is_empty() || self.exact || self.ignored || shard.is_some();
+
+        for (snapshot_path, snapshot) in gas_snapshots {
+            // Only the tests run are updated, the others may have been filtered out.
+            let mut updated = if is_partial_run {
+                GasSnapshot::read(&snapshot_path)?.unwrap_or_default()
+            } else {
+                GasSnapshot::default()
+            };
+
+            updated.merge(snapshot);
+            updated.write(&snapshot_path)?;
+
+            config.ui().print(
+                StatusMessage::new(
+                    "written",
+                    format!("gas snapshot written to {snapshot_path}"),
+                )
+                .with_path(&snapshot_path),
+            );
+        }
+
+        if let Some(path) = coverage_path.filter(|_| !self.list) {
+            coverage.write(&path)?;
+
+            let (hit, total) = coverage.line_counts();
+            config.ui().print(
+                StatusMessage::new(
+                    "written",
+                    format!("coverage written to {path} ({hit}/{total} lines)"),
+                )
+                .with_path(&path),
+            );
+        }
+
+        let failed = suites
+            .iter()
+            .map(|suite| suite.summary.count(TestStatus::Failed))
+            .sum::<usize>();
+
+        if failed > 0 {
+            bail!("{failed} tests failed.");
+        }
+
+        if gas_regressions > 0 {
+            bail!("{gas_regressions} tests use more gas than the gas snapshot.");
         }
 
         Ok(())
@@ -256,3 +528,127 @@ fn build_project_config(unit: &CairoCompilationUnit) -> Result<ProjectConfig> {
 
     Ok(project_config)
 }
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use assert_fs::TempDir;
+    use camino::Utf8Path;
+    use clap::Parser;
+    use dojo_compiler::plugin::CairoPluginRepository;
+    use scarb::compiler::CompilerRepository;
+
+    use super::*;
+
+    const CODE: &str = include_str!("test_data/coverage.cairo");
+
+    #[derive(Parser)]
+    struct Cli {
+        #[command(flatten)]
+        test: TestArgs,
+    }
+
+    #[test]
+    fn test_coverage() {
+        let cache_dir = TempDir::new().unwrap();
+        let output_dir = TempDir::new().unwrap();
+        let project_dir = TempDir::new().unwrap();
+
+        let crates_dir = Utf8Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("../../crates")
+            .canonicalize_utf8()
+            .unwrap();
+
+        let manifest_path = Utf8Path::from_path(project_dir.path())
+            .unwrap()
+            .join("Scarb.toml");
+
+        fs::write(
+            &manifest_path,
+            format!(
+                r#"
+[package]
+cairo-version = "=2.8.4"
+name = "coverage_test"
+version = "0.1.0"
+edition = "2024_07"
+
+[[target.starknet-contract]]
+sierra = true
+build-external-contracts = ["dojo::world::world_contract::world"]
+
+[dependencies]
+dojo = {{ path = "{crates_dir}/contracts" }}
+
+[dev-dependencies]
+dojo_cairo_test = {{ path = "{crates_dir}/core-cairo-test" }}
+cairo_test = "=2.8.4"
+"#
+            ),
+        )
+        .unwrap();
+        fs::write(
+            project_dir.path().join("dojo_dev.toml"),
+            r#"
+[world]
+name = "coverage_test"
+seed = "coverage_test"
+
+[namespace]
+default = "ns"
+"#,
+        )
+        .unwrap();
+        fs::create_dir(project_dir.path().join("src")).unwrap();
+        fs::write(project_dir.path().join("src/lib.cairo"), CODE).unwrap();
+
+        let config = Config::builder(manifest_path)
+            .global_cache_dir_override(Some(Utf8Path::from_path(cache_dir.path()).unwrap()))
+            .compilers(CompilerRepository::std())
+            .cairo_plugins(CairoPluginRepository::default().into())
+            .build()
+            .unwrap();
+
+        let lcov_path = Utf8Path::from_path(output_dir.path())
+            .unwrap()
+            .join("lcov.info");
+
+        Cli::parse_from(["test", "--coverage", lcov_path.as_str()])
+            .test
+            .run(&config)
+            .unwrap();
+
+        let lcov = fs::read_to_string(&lcov_path).unwrap();
+
+        let lib_lines = lcov
+            .split("end_of_record\n")
+            .find(|record| record.contains("src/lib.cairo"))
+            .expect("lib.cairo should be covered");
+
+        // The lcov lines are 1 based.
+        let line = |pattern: &str| CODE.lines().position(|l| l.contains(pattern)).unwrap() + 1;
+        let count = |pattern: &str| {
+            let line = line(pattern);
+
+            lib_lines
+                .lines()
+                .find_map(|l| l.strip_prefix(&format!("DA:{line},")))
+                .map(|count| count.parse::<usize>().unwrap())
+        };
+
+        // The code generated for the `#[dojo::model]`, `#[dojo::event]` and
+        // `#[dojo::contract]` items is not attributed to their lines.
+        assert_eq!(count("#[dojo::model]"), None);
+        assert_eq!(count("#[dojo::event]"), None);
+        assert_eq!(count("#[dojo::contract]"), None);
+
+        // The statements of the test are covered.
+        assert_eq!(count("spawn_test_world([ndef]"), Some(1));
+        assert_eq!(count("world.read_model(0)"), Some(1));
+
+        // `dojo_init` is called by the world through a dispatcher, in a nested entry point
+        // whose statements the runner doesn't report (DOJO107).
+        assert_eq!(count("world.write_model(@M"), Some(0));
+    }
+}
@@ -0,0 +1,57 @@
+#[derive(Copy, Drop, Serde)]
+#[dojo::model]
+pub struct M {
+    #[key]
+    pub a: felt252,
+    pub b: felt252,
+}
+
+#[derive(Copy, Drop, Serde)]
+#[dojo::event]
+pub struct E {
+    #[key]
+    pub a: felt252,
+    pub b: u32,
+}
+
+#[dojo::contract]
+pub mod c1 {
+    use dojo::model::ModelStorage;
+    use dojo::world::WorldStorageTrait;
+    use super::M;
+
+    fn dojo_init(ref self: ContractState, arg1: felt252) {
+        let mut world = WorldStorageTrait::new(self.world_dispatcher(), @"ns");
+        world.write_model(@M { a: 0, b: arg1 });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dojo::model::ModelStorage;
+    use dojo::world::WorldStorageTrait;
+    use dojo_cairo_test::{spawn_test_world, NamespaceDef, TestResource, ContractDefTrait};
+    use super::{c1, m, M};
+
+    #[test]
+    fn test_init() {
+        let ndef = NamespaceDef {
+            namespace: "ns",
+            resources: [
+                TestResource::Model(m::TEST_CLASS_HASH.try_into().unwrap()),
+                TestResource::Contract(
+                    ContractDefTrait::new(c1::TEST_CLASS_HASH, "c1")
+                        .with_init_calldata([0xff].span())
+                        .with_writer_of([dojo::utils::bytearray_hash(@"ns")].span())
+                )
+            ]
+                .span()
+        };
+
+        let world = spawn_test_world([ndef].span());
+        let mut world = WorldStorageTrait::new(world, @"ns");
+
+        let m: M = world.read_model(0);
+        assert!(m.b == 0xff, "invalid b");
+    }
+}
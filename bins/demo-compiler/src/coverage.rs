@@ -0,0 +1,337 @@
+//! Code coverage of the tests, written in the LCOV format.
+//!
+//! The Sierra statements executed by each test are mapped back to the Cairo source lines with
+//! the code locations the compiler adds to the debug info of the test program. Only the lines
+//! of the package sources are covered: the code generated by the plugins (Dojo models,
+//! contracts, derives...) is excluded, either located in a virtual file or attributed to the
+//! whole item it is generated from, like a `#[dojo::model]` struct.
+//!
+//! The runner only reports which statements were executed, so the count of a line is the
+//! number of tests executing it. The statements executed by the contracts called or deployed
+//! by a test (through a dispatcher or the world) run in a nested entry point, whose executed
+//! statements the runner doesn't report: they are not covered.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt::Write;
+use std::fs;
+
+use anyhow::{Context, Result};
+use camino::{Utf8Path, Utf8PathBuf};
+use dojo_compiler::compiler::scarb_internal::debug::{Location, TextPosition};
+use serde::Deserialize;
+
+/// The key of the statements code locations in the annotations of the debug info.
+pub const COVERAGE_ANNOTATION: &str = "github.com/software-mansion/cairo-coverage";
+
+#[derive(Debug, Deserialize)]
+struct CoverageAnnotation {
+    statements_code_locations: HashMap<usize, Vec<(String, CodeSpan)>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CodeSpan {
+    start: CodePosition,
+    end: CodePosition,
+}
+
+#[derive(Debug, Deserialize)]
+struct CodePosition {
+    line: usize,
+    col: usize,
+}
+
+/// The Cairo source lines of the Sierra statements of a test program.
+#[derive(Debug, Clone, Default)]
+pub struct StatementLines {
+    /// The source lines (1-based) of each statement.
+    lines: HashMap<usize, Vec<(Utf8PathBuf, usize)>>,
+}
+
+impl StatementLines {
+    /// Extracts the source lines of the statements from the coverage annotation of the debug
+    /// info of a test program.
+    ///
+    /// The locations outside of the package root, the ones in virtual files and the ones of
+    /// the generated code are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `annotation` - The [`COVERAGE_ANNOTATION`] of the test program, compiled with the
+    ///   statements code locations.
+    /// * `package_root` - The root of the package whose sources are covered.
+    /// * `generated` - The locations the generated code of the package is attributed to.
+    pub fn from_annotation(
+        annotation: Option<&serde_json::Value>,
+        package_root: &Utf8Path,
+        generated: &HashSet<Location>,
+    ) -> Result<Self> {
+        let annotation =
+            annotation.context("The test program has no statements code locations.")?;
+
+        let annotation: CoverageAnnotation = serde_json::from_value(annotation.clone())
+            .context("Failed to parse the statements code locations.")?;
+
+        let mut lines = HashMap::new();
+
+        for (statement, locations) in annotation.statements_code_locations {
+            let statement_lines = locations
+                .into_iter()
+                .filter(|(path, _)| !is_virtual_file(path))
+                .filter(|(path, span)| !is_generated(generated, path, span))
+                .map(|(path, span)| (Utf8PathBuf::from(path), span.start.line + 1))
+                .filter(|(path, _)| path.starts_with(package_root))
+                .collect::<Vec<_>>();
+
+            if !statement_lines.is_empty() {
+                lines.insert(statement, statement_lines);
+            }
+        }
+
+        Ok(Self { lines })
+    }
+}
+
+/// Returns true if the path is the full path of a virtual file, formatted by the compiler as
+/// `<parent full path>[<name>]`, like `src/models.cairo[position_model]`.
+fn is_virtual_file(path: &str) -> bool {
+    path.ends_with(']')
+}
+
+/// Returns true if the location is the one some generated code is attributed to.
+fn is_generated(generated: &HashSet<Location>, path: &str, span: &CodeSpan) -> bool {
+    generated.contains(&Location {
+        start: TextPosition {
+            line: span.start.line,
+            col: span.start.col,
+        },
+        end: TextPosition {
+            line: span.end.line,
+            col: span.end.col,
+        },
+        file_path: path.to_string(),
+        function: None,
+    })
+}
+
+/// The number of tests executing each covered source line.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Coverage {
+    pub files: BTreeMap<Utf8PathBuf, BTreeMap<usize, usize>>,
+}
+
+impl Coverage {
+    /// Creates the coverage of a test program, with all its lines not executed yet.
+    pub fn new(lines: &StatementLines) -> Self {
+        let mut coverage = Self::default();
+
+        for (path, line) in lines.lines.values().flatten() {
+            coverage
+                .files
+                .entry(path.clone())
+                .or_default()
+                .entry(*line)
+                .or_default();
+        }
+
+        coverage
+    }
+
+    /// Records the statements executed by a test, counting each of their lines once.
+    ///
+    /// # Arguments
+    ///
+    /// * `lines` - The source lines of the statements of the test program.
+    /// * `statements` - The executed statements.
+    pub fn record(&mut self, lines: &StatementLines, statements: &BTreeSet<usize>) {
+        let executed_lines = statements
+            .iter()
+            .filter_map(|statement| lines.lines.get(statement))
+            .flatten()
+            .collect::<BTreeSet<_>>();
+
+        for (path, line) in executed_lines {
+            *self
+                .files
+                .entry(path.clone())
+                .or_default()
+                .entry(*line)
+                .or_default() += 1;
+        }
+    }
+
+    /// Merges the coverage of another test program.
+    pub fn merge(&mut self, other: Coverage) {
+        for (path, lines) in other.files {
+            let file = self.files.entry(path).or_default();
+
+            for (line, count) in lines {
+                *file.entry(line).or_default() += count;
+            }
+        }
+    }
+
+    /// Returns the coverage in the LCOV format.
+    pub fn to_lcov(&self) -> String {
+        let mut lcov = String::new();
+
+        for (path, lines) in &self.files {
+            let _ = writeln!(lcov, "TN:");
+            let _ = writeln!(lcov, "SF:{path}");
+
+            for (line, count) in lines {
+                let _ = writeln!(lcov, "DA:{line},{count}");
+            }
+
+            let hit = lines.values().filter(|count| **count > 0).count();
+
+            let _ = writeln!(lcov, "LF:{}", lines.len());
+            let _ = writeln!(lcov, "LH:{hit}");
+            lcov.push_str("end_of_record\n");
+        }
+
+        lcov
+    }
+
+    /// Writes the coverage in the LCOV format to the given path.
+    pub fn write(&self, path: &Utf8Path) -> Result<()> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_str().is_empty()) {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create coverage directory `{parent}`."))?;
+        }
+
+        fs::write(path, self.to_lcov())
+            .with_context(|| format!("Failed to write coverage `{path}`."))
+    }
+
+    /// Returns the number of covered lines and the number of lines.
+    pub fn line_counts(&self) -> (usize, usize) {
+        self.files
+            .values()
+            .flat_map(|lines| lines.values())
+            .fold((0, 0), |(hit, total), count| {
+                (hit + usize::from(*count > 0), total + 1)
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn statement_lines() -> StatementLines {
+        let annotation = json!({
+            "statements_code_locations": {
+                "0": [["/pkg/src/lib.cairo", span(2, 4, 2, 12)]],
+                "1": [
+                    ["/pkg/src/lib.cairo", span(3, 4, 3, 10)],
+                    ["/pkg/src/lib.cairo", span(2, 8, 2, 12)]
+                ],
+                "2": [["/pkg/src/models.cairo[position_model]", span(0, 0, 0, 4)]],
+                "3": [["/core/src/lib.cairo", span(10, 0, 10, 4)]],
+                "4": [["/pkg/src/models.cairo", span(5, 0, 9, 1)]]
+            }
+        });
+
+        // The `#[dojo::model]` struct of `models.cairo` the model code is generated from.
+        let generated = HashSet::from([Location {
+            start: TextPosition { line: 5, col: 0 },
+            end: TextPosition { line: 9, col: 1 },
+            file_path: "/pkg/src/models.cairo".to_string(),
+            function: None,
+        }]);
+
+        StatementLines::from_annotation(Some(&annotation), Utf8Path::new("/pkg"), &generated)
+            .unwrap()
+    }
+
+    fn span(
+        start_line: usize,
+        start_col: usize,
+        end_line: usize,
+        end_col: usize,
+    ) -> serde_json::Value {
+        json!({
+            "start": { "line": start_line, "col": start_col },
+            "end": { "line": end_line, "col": end_col }
+        })
+    }
+
+    #[test]
+    fn test_statement_lines() {
+        let lines = statement_lines();
+
+        assert_eq!(
+            lines.lines[&0],
+            vec![(Utf8PathBuf::from("/pkg/src/lib.cairo"), 3)]
+        );
+        assert_eq!(
+            lines.lines[&1],
+            vec![
+                (Utf8PathBuf::from("/pkg/src/lib.cairo"), 4),
+                (Utf8PathBuf::from("/pkg/src/lib.cairo"), 3)
+            ]
+        );
+
+        // The virtual files, the files outside of the package and the generated code are
+        // skipped.
+        assert!(!lines.lines.contains_key(&2));
+        assert!(!lines.lines.contains_key(&3));
+        assert!(!lines.lines.contains_key(&4));
+    }
+
+    #[test]
+    fn test_statement_lines_no_annotation() {
+        assert!(
+            StatementLines::from_annotation(None, Utf8Path::new("/pkg"), &HashSet::new()).is_err()
+        );
+    }
+
+    #[test]
+    fn test_record_counts_tests() {
+        let lines = statement_lines();
+        let mut coverage = Coverage::new(&lines);
+
+        coverage.record(&lines, &BTreeSet::from([0, 1]));
+        coverage.record(&lines, &BTreeSet::from([0, 2, 3]));
+
+        let file = &coverage.files[Utf8Path::new("/pkg/src/lib.cairo")];
+
+        assert_eq!(file[&3], 2);
+        assert_eq!(file[&4], 1);
+        assert_eq!(coverage.line_counts(), (2, 2));
+    }
+
+    #[test]
+    fn test_to_lcov() {
+        let lines = statement_lines();
+        let mut coverage = Coverage::new(&lines);
+
+        coverage.record(&lines, &BTreeSet::from([0]));
+
+        assert_eq!(coverage.line_counts(), (1, 2));
+        assert_eq!(
+            coverage.to_lcov(),
+            "TN:\nSF:/pkg/src/lib.cairo\nDA:3,1\nDA:4,0\nLF:2\nLH:1\nend_of_record\n"
+        );
+    }
+
+    #[test]
+    fn test_merge() {
+        let lines = statement_lines();
+
+        let mut coverage = Coverage::new(&lines);
+        coverage.record(&lines, &BTreeSet::from([0]));
+
+        let mut other = Coverage::new(&lines);
+        other.record(&lines, &BTreeSet::from([1]));
+
+        coverage.merge(other);
+
+        let file = &coverage.files[Utf8Path::new("/pkg/src/lib.cairo")];
+
+        assert_eq!(file[&3], 2);
+        assert_eq!(file[&4], 1);
+    }
+}
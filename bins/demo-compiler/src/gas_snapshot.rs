@@ -0,0 +1,330 @@
+//! Gas snapshots of the tests, to catch the gas regressions in review.
+//!
+//! The snapshot of a package is a TOML file at the root of the package, committed with the
+//! code, which contains the gas and the resources used by each passing test, sorted by name:
+//!
+//! ```toml
+//! ["dojo_examples::tests::test_move"]
+//! gas_used = 1202140
+//! memory_holes = 72
+//! steps = 10343
+//! ```
+
+use std::collections::BTreeMap;
+use std::{fmt, fs};
+
+use anyhow::{Context, Result};
+use camino::{Utf8Path, Utf8PathBuf};
+use dojo_compiler::compiler::messages::{self, DiagnosticMessage, StatusMessage};
+use dojo_compiler::diagnostic_codes;
+use scarb_ui::Ui;
+use serde::{Deserialize, Serialize};
+
+use crate::test_runner::{TestResult, TestStatus};
+
+/// The name of the gas snapshot file, at the root of the package.
+pub const GAS_SNAPSHOT_FILE: &str = "gas_snapshot.toml";
+/// The key of the gas used by a test in the snapshot.
+const GAS_USED_KEY: &str = "gas_used";
+
+/// The gas and the resources used by each test.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct GasSnapshot {
+    pub tests: BTreeMap<String, BTreeMap<String, u64>>,
+}
+
+/// A change of the gas used by a test.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GasChange {
+    pub name: String,
+    pub before: u64,
+    pub after: u64,
+}
+
+impl GasChange {
+    /// Returns the change in percent of the gas used before.
+    pub fn percent(&self) -> f64 {
+        if self.before == 0 {
+            return if self.after == 0 { 0.0 } else { f64::INFINITY };
+        }
+
+        (self.after as f64 - self.before as f64) * 100.0 / self.before as f64
+    }
+}
+
+impl fmt::Display for GasChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} -> {} ({:+.2}%)",
+            self.name,
+            self.before,
+            self.after,
+            self.percent()
+        )
+    }
+}
+
+/// The differences between a gas snapshot and a test run.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GasDiff {
+    /// The tests using more gas than the snapshot, above the tolerance.
+    pub regressions: Vec<GasChange>,
+    /// The tests using less gas than the snapshot, above the tolerance.
+    pub improvements: Vec<GasChange>,
+    /// The tests missing from the snapshot.
+    pub new_tests: Vec<String>,
+}
+
+impl GasSnapshot {
+    /// Returns the path of the gas snapshot of the package at the given root.
+    pub fn path(package_root: &Utf8Path) -> Utf8PathBuf {
+        package_root.join(GAS_SNAPSHOT_FILE)
+    }
+
+    /// Creates the snapshot of the passing tests run with gas.
+    pub fn from_results(results: &[TestResult]) -> Self {
+        let tests = results
+            .iter()
+            .filter(|r| r.status == TestStatus::Passed)
+            .filter_map(|r| {
+                let gas_used = r.gas_used?;
+
+                let mut entry = r
+                    .resources
+                    .iter()
+                    .map(|(resource, count)| (resource.clone(), *count as u64))
+                    .collect::<BTreeMap<_, _>>();
+
+                entry.insert(GAS_USED_KEY.to_string(), gas_used);
+
+                Some((r.name.clone(), entry))
+            })
+            .collect();
+
+        Self { tests }
+    }
+
+    /// Reads the gas snapshot at the given path, `None` if it doesn't exist.
+    pub fn read(path: &Utf8Path) -> Result<Option<Self>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read gas snapshot `{path}`."))?;
+
+        toml::from_str(&content)
+            .map(Some)
+            .with_context(|| format!("Failed to parse gas snapshot `{path}`."))
+    }
+
+    /// Writes the gas snapshot to the given path.
+    pub fn write(&self, path: &Utf8Path) -> Result<()> {
+        let content = toml::to_string(self)?;
+
+        fs::write(path, content).with_context(|| format!("Failed to write gas snapshot `{path}`."))
+    }
+
+    /// Updates the snapshot with the tests of another one, keeping the other tests.
+    pub fn merge(&mut self, other: GasSnapshot) {
+        self.tests.extend(other.tests);
+    }
+
+    /// Returns the gas used by a test, if any.
+    pub fn gas_used(&self, name: &str) -> Option<u64> {
+        self.tests.get(name)?.get(GAS_USED_KEY).copied()
+    }
+
+    /// Compares the gas used by the tests of a run with this snapshot.
+    ///
+    /// The tests of the snapshot not run are ignored, as they may have been filtered out.
+    ///
+    /// # Arguments
+    ///
+    /// * `current` - The snapshot of the run.
+    /// * `tolerance` - The change of gas in percent under which a test is unchanged.
+    pub fn compare(&self, current: &GasSnapshot, tolerance: f64) -> GasDiff {
+        let mut diff = GasDiff::default();
+
+        for name in current.tests.keys() {
+            let Some(after) = current.gas_used(name) else {
+                continue;
+            };
+
+            let Some(before) = self.gas_used(name) else {
+                diff.new_tests.push(name.clone());
+                continue;
+            };
+
+            let change = GasChange {
+                name: name.clone(),
+                before,
+                after,
+            };
+
+            if change.percent() > tolerance {
+                diff.regressions.push(change);
+            } else if change.percent() < -tolerance {
+                diff.improvements.push(change);
+            }
+        }
+
+        diff
+    }
+}
+
+impl GasDiff {
+    /// Reports the differences to the UI.
+    pub fn report(&self, ui: &Ui) {
+        for change in &self.regressions {
+            messages::emit(
+                ui,
+                DiagnosticMessage::error(
+                    diagnostic_codes::GAS_REGRESSION.code,
+                    format!("gas regression {change}"),
+                ),
+            );
+        }
+
+        for change in &self.improvements {
+            ui.print(StatusMessage::new(
+                "gas_improvement",
+                format!("gas improvement {change}"),
+            ));
+        }
+
+        for name in &self.new_tests {
+            messages::emit(
+                ui,
+                DiagnosticMessage::warning(
+                    diagnostic_codes::MISSING_GAS_SNAPSHOT_TEST.code,
+                    format!("test {name} is missing from the gas snapshot"),
+                ),
+            );
+        }
+    }
+}
+
+/// Parses a gas tolerance, like `5%` or `2.5`, in percent.
+pub fn parse_tolerance(tolerance: &str) -> Result<f64> {
+    let value = tolerance.trim().trim_end_matches('%');
+
+    let tolerance = value
+        .parse::<f64>()
+        .ok()
+        .filter(|t| t.is_finite() && *t >= 0.0)
+        .with_context(|| {
+            format!("Invalid gas tolerance `{tolerance}`, expected a percentage like `5%`.")
+        })?;
+
+    Ok(tolerance)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(tests: &[(&str, u64)]) -> GasSnapshot {
+        GasSnapshot {
+            tests: tests
+                .iter()
+                .map(|(name, gas_used)| {
+                    (
+                        name.to_string(),
+                        BTreeMap::from([(GAS_USED_KEY.to_string(), *gas_used)]),
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_compare() {
+        let before = snapshot(&[
+            ("test_same", 1000),
+            ("test_small_change", 1000),
+            ("test_regression", 1000),
+            ("test_improvement", 1000),
+            ("test_not_run", 1000),
+        ]);
+        let after = snapshot(&[
+            ("test_same", 1000),
+            ("test_small_change", 1040),
+            ("test_regression", 1100),
+            ("test_improvement", 800),
+            ("test_new", 500),
+        ]);
+
+        let diff = before.compare(&after, 5.0);
+
+        assert_eq!(
+            diff.regressions,
+            vec![GasChange {
+                name: "test_regression".to_string(),
+                before: 1000,
+                after: 1100,
+            }]
+        );
+        assert_eq!(
+            diff.improvements,
+            vec![GasChange {
+                name: "test_improvement".to_string(),
+                before: 1000,
+                after: 800,
+            }]
+        );
+        assert_eq!(diff.new_tests, vec!["test_new".to_string()]);
+    }
+
+    #[test]
+    fn test_compare_without_tolerance() {
+        let diff = snapshot(&[("test", 1000)]).compare(&snapshot(&[("test", 1001)]), 0.0);
+
+        assert_eq!(diff.regressions.len(), 1);
+        assert!(diff.improvements.is_empty());
+    }
+
+    #[test]
+    fn test_change_percent() {
+        let change = |before, after| GasChange {
+            name: "test".to_string(),
+            before,
+            after,
+        };
+
+        assert_eq!(change(1000, 1100).percent(), 10.0);
+        assert_eq!(change(1000, 750).percent(), -25.0);
+        assert_eq!(change(0, 0).percent(), 0.0);
+        assert_eq!(change(0, 1).percent(), f64::INFINITY);
+
+        assert_eq!(
+            change(1000, 1100).to_string(),
+            "test: 1000 -> 1100 (+10.00%)"
+        );
+    }
+
+    #[test]
+    fn test_parse_tolerance() {
+        assert_eq!(parse_tolerance("5%").unwrap(), 5.0);
+        assert_eq!(parse_tolerance(" 2.5 ").unwrap(), 2.5);
+        assert_eq!(parse_tolerance("0").unwrap(), 0.0);
+
+        assert!(parse_tolerance("-1%").is_err());
+        assert!(parse_tolerance("five").is_err());
+        assert!(parse_tolerance("inf").is_err());
+        assert!(parse_tolerance("").is_err());
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut snapshot_a = snapshot(&[("test_a", 1000), ("test_b", 2000)]);
+        snapshot_a.merge(snapshot(&[("test_b", 2500), ("test_c", 3000)]));
+
+        assert_eq!(
+            snapshot_a,
+            snapshot(&[("test_a", 1000), ("test_b", 2500), ("test_c", 3000)])
+        );
+    }
+}
@@ -4,41 +4,71 @@ use std::process::exit;
 use anyhow::Result;
 use args::CompilerArgs;
 use clap::Parser;
-use dojo_compiler::compiler::{self, DojoCompiler};
+use dojo_compiler::compiler::messages::{self, DiagnosticMessage};
+use dojo_compiler::compiler::{self, DojoCompiler, DojoCompilerOptions};
+use dojo_compiler::diagnostic_codes;
 use dojo_compiler::plugin::CairoPluginRepository;
 use scarb::compiler::CompilerRepository;
 use scarb::core::Config;
-use scarb_ui::{OutputFormat, Ui};
+use scarb_ui::Ui;
 use tracing::trace;
 
 use crate::commands::Commands;
 
 mod args;
 mod commands;
+mod coverage;
+mod gas_snapshot;
+mod test_report;
+mod test_runner;
 
 fn main() {
     let args = CompilerArgs::parse();
     let _ = args.init_logging();
-    let ui = Ui::new(args.ui_verbosity(), OutputFormat::Text);
+    let ui = Ui::new(args.ui_verbosity(), args.ui_output_format());
 
-    if let Err(err) = cli_main(args) {
-        ui.anyhow(&err);
+    if let Err(err) = cli_main(args, &ui) {
+        messages::emit(
+            &ui,
+            DiagnosticMessage::error(diagnostic_codes::COMMAND_FAILED.code, format!("{err:?}")),
+        );
         exit(1);
     }
 }
 
-fn cli_main(args: CompilerArgs) -> Result<()> {
+fn cli_main(args: CompilerArgs, ui: &Ui) -> Result<()> {
+    // The commands working without a package are run before looking for its manifest.
+    let command = match args.command {
+        Commands::Backtrace(backtrace) => return backtrace.run(ui),
+        Commands::Explain(explain) => return explain.run(ui),
+        command => command,
+    };
+
     let mut compilers = CompilerRepository::std();
     let cairo_plugins = CairoPluginRepository::default();
 
-    match &args.command {
+    match &command {
         Commands::Build(args) => {
             trace!("Adding DojoCompiler to compiler repository.");
             compilers
-                .add(Box::new(DojoCompiler::new(args.output_debug_info)))
+                .add(Box::new(DojoCompiler::new(DojoCompilerOptions {
+                    output_debug_info: args.output_debug_info,
+                    use_cache: !args.no_cache,
+                    locked: args.locked,
+                    size_report: args.size_report.is_some(),
+                })))
+                .unwrap()
+        }
+        Commands::Dev(_) => {
+            trace!("Adding DojoCompiler to compiler repository.");
+            compilers
+                .add(Box::new(DojoCompiler::new(DojoCompilerOptions {
+                    use_cache: true,
+                    ..Default::default()
+                })))
                 .unwrap()
         }
-        Commands::Dev(_) | Commands::Clean(_) => {
+        Commands::Clean(_) => {
             trace!("Adding DojoCompiler to compiler repository.");
             compilers.add(Box::new(DojoCompiler::default())).unwrap()
         }
@@ -54,9 +84,10 @@ fn cli_main(args: CompilerArgs) -> Result<()> {
         .profile(args.profile_spec.determine()?)
         .offline(args.offline)
         .cairo_plugins(cairo_plugins.into())
-        .ui_verbosity(args.ui_verbosity())
+        .ui_verbosity(ui.verbosity())
+        .ui_output_format(ui.output_format())
         .compilers(compilers)
         .build()?;
 
-    commands::run(args.command, &config)
+    commands::run(command, &config)
 }
@@ -0,0 +1,310 @@
+//! Reports of the test runs, written to a file for the CI.
+//!
+//! The JSON report contains the results of each test suite (a compilation unit). The JUnit
+//! report follows the format understood by most CI systems, the gas and the resources used
+//! by each test being written as properties of the test case.
+
+use std::fmt::Write;
+use std::fs;
+
+use anyhow::{Context, Result};
+use camino::Utf8PathBuf;
+use clap::ValueEnum;
+use serde::Serialize;
+
+use crate::test_runner::{TestResult, TestStatus, TestsSummary};
+
+/// The format of a test report.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ReportFormat {
+    Junit,
+    Json,
+}
+
+/// The results of the tests of a compilation unit.
+#[derive(Debug, Clone)]
+pub struct TestSuite {
+    pub name: String,
+    pub summary: TestsSummary,
+}
+
+/// A report of the test runs, to be written to a file.
+#[derive(Debug, Clone)]
+pub struct TestReport {
+    pub format: ReportFormat,
+    pub path: Utf8PathBuf,
+}
+
+#[derive(Debug, Serialize)]
+struct JsonReport<'a> {
+    suites: Vec<JsonSuite<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+struct JsonSuite<'a> {
+    name: &'a str,
+    passed: usize,
+    failed: usize,
+    ignored: usize,
+    filtered_out: usize,
+    tests: &'a [TestResult],
+}
+
+impl TestReport {
+    /// Parses the `--report <FORMAT> <PATH>` arguments.
+    pub fn from_args(args: &[String]) -> Result<Self> {
+        let [format, path] = args else {
+            anyhow::bail!("Expected a report format and a report path.");
+        };
+
+        let format = ReportFormat::from_str(format, true).map_err(|_| {
+            anyhow::anyhow!("Invalid report format `{format}`, expected `junit` or `json`.")
+        })?;
+
+        Ok(Self {
+            format,
+            path: Utf8PathBuf::from(path),
+        })
+    }
+
+    /// Writes the report of the given test suites.
+    pub fn write(&self, suites: &[TestSuite]) -> Result<()> {
+        let content = match self.format {
+            ReportFormat::Json => to_json(suites)?,
+            ReportFormat::Junit => to_junit(suites),
+        };
+
+        if let Some(parent) = self.path.parent().filter(|p| !p.as_str().is_empty()) {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create report directory `{parent}`."))?;
+        }
+
+        fs::write(&self.path, content)
+            .with_context(|| format!("Failed to write test report `{}`.", self.path))
+    }
+}
+
+fn to_json(suites: &[TestSuite]) -> Result<String> {
+    let report = JsonReport {
+        suites: suites
+            .iter()
+            .map(|suite| JsonSuite {
+                name: &suite.name,
+                passed: suite.summary.count(TestStatus::Passed),
+                failed: suite.summary.count(TestStatus::Failed),
+                ignored: suite.summary.count(TestStatus::Ignored),
+                filtered_out: suite.summary.filtered_out,
+                tests: &suite.summary.results,
+            })
+            .collect(),
+    };
+
+    Ok(serde_json::to_string_pretty(&report)?)
+}
+
+fn to_junit(suites: &[TestSuite]) -> String {
+    let count = |status| {
+        suites
+            .iter()
+            .map(|s| s.summary.count(status))
+            .sum::<usize>()
+    };
+    let tests = suites
+        .iter()
+        .map(|s| s.summary.results.len())
+        .sum::<usize>();
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+
+    let _ = writeln!(
+        xml,
+        "<testsuites tests=\"{tests}\" failures=\"{}\" skipped=\"{}\">",
+        count(TestStatus::Failed),
+        count(TestStatus::Ignored)
+    );
+
+    for suite in suites {
+        let summary = &suite.summary;
+
+        let _ = writeln!(
+            xml,
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" skipped=\"{}\">",
+            escape_xml(&suite.name),
+            summary.results.len(),
+            summary.count(TestStatus::Failed),
+            summary.count(TestStatus::Ignored)
+        );
+
+        for result in &summary.results {
+            write_junit_test_case(&mut xml, result);
+        }
+
+        xml.push_str("  </testsuite>\n");
+    }
+
+    xml.push_str("</testsuites>\n");
+    xml
+}
+
+fn write_junit_test_case(xml: &mut String, result: &TestResult) {
+    let (classname, name) = result
+        .name
+        .rsplit_once("::")
+        .unwrap_or(("", result.name.as_str()));
+
+    let _ = writeln!(
+        xml,
+        "    <testcase name=\"{}\" classname=\"{}\">",
+        escape_xml(name),
+        escape_xml(classname)
+    );
+
+    let mut properties = vec![];
+
+    if let Some(gas_used) = result.gas_used {
+        properties.push(("gas_used".to_string(), gas_used.to_string()));
+    }
+
+    for (resource, count) in &result.resources {
+        properties.push((resource.clone(), count.to_string()));
+    }
+
+    if !properties.is_empty() {
+        xml.push_str("      <properties>\n");
+
+        for (name, value) in properties {
+            let _ = writeln!(
+                xml,
+                "        <property name=\"{}\" value=\"{}\"/>",
+                escape_xml(&name),
+                escape_xml(&value)
+            );
+        }
+
+        xml.push_str("      </properties>\n");
+    }
+
+    match result.status {
+        TestStatus::Passed => {}
+        TestStatus::Ignored => xml.push_str("      <skipped/>\n"),
+        TestStatus::Failed => {
+            let message = match &result.panic_message {
+                Some(message) => format!("Panicked with [{message}]."),
+                None => "Expected panic, but the test passed.".to_string(),
+            };
+
+            let _ = writeln!(
+                xml,
+                "      <failure message=\"{}\">{}</failure>",
+                escape_xml(&message),
+                escape_xml(&result.panic_data.clone().unwrap_or_default().join(", "))
+            );
+        }
+    }
+
+    xml.push_str("    </testcase>\n");
+}
+
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, BTreeSet};
+
+    use super::*;
+
+    fn test_result(name: &str, status: TestStatus) -> TestResult {
+        TestResult {
+            name: name.to_string(),
+            status,
+            gas_used: None,
+            panic_data: None,
+            panic_message: None,
+            resources: BTreeMap::new(),
+            executed_statements: BTreeSet::new(),
+        }
+    }
+
+    #[test]
+    fn test_escape_xml() {
+        assert_eq!(escape_xml("plain"), "plain");
+        assert_eq!(
+            escape_xml("<a href=\"x\">'&'</a>"),
+            "&lt;a href=&quot;x&quot;&gt;&apos;&amp;&apos;&lt;/a&gt;"
+        );
+    }
+
+    #[test]
+    fn test_to_junit() {
+        let passed = TestResult {
+            gas_used: Some(1200),
+            resources: BTreeMap::from([("steps".to_string(), 42)]),
+            ..test_result("pkg::tests::test_move", TestStatus::Passed)
+        };
+
+        let failed = TestResult {
+            panic_data: Some(vec!["0x616263".to_string()]),
+            panic_message: Some("0x616263 ('abc')".to_string()),
+            ..test_result("pkg::tests::test_<fail>", TestStatus::Failed)
+        };
+
+        let ignored = test_result("test_ignored", TestStatus::Ignored);
+
+        let suites = vec![TestSuite {
+            name: "pkg & tests".to_string(),
+            summary: TestsSummary {
+                results: vec![passed, failed, ignored],
+                filtered_out: 2,
+            },
+        }];
+
+        assert_eq!(
+            to_junit(&suites),
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites tests="3" failures="1" skipped="1">
+  <testsuite name="pkg &amp; tests" tests="3" failures="1" skipped="1">
+    <testcase name="test_move" classname="pkg::tests">
+      <properties>
+        <property name="gas_used" value="1200"/>
+        <property name="steps" value="42"/>
+      </properties>
+    </testcase>
+    <testcase name="test_&lt;fail&gt;" classname="pkg::tests">
+      <failure message="Panicked with [0x616263 (&apos;abc&apos;)].">0x616263</failure>
+    </testcase>
+    <testcase name="test_ignored" classname="">
+      <skipped/>
+    </testcase>
+  </testsuite>
+</testsuites>
+"#
+        );
+    }
+
+    #[test]
+    fn test_from_args() {
+        let report =
+            TestReport::from_args(&["junit".to_string(), "target/report.xml".to_string()]).unwrap();
+
+        assert_eq!(report.format, ReportFormat::Junit);
+        assert_eq!(report.path, Utf8PathBuf::from("target/report.xml"));
+
+        assert!(TestReport::from_args(&["xml".to_string(), "report.xml".to_string()]).is_err());
+        assert!(TestReport::from_args(&["json".to_string()]).is_err());
+    }
+}
@@ -0,0 +1,801 @@
+//! Runs the compiled tests one by one, collecting the result of each test.
+//!
+//! Unlike the `CompiledTestRunner` of the Cairo test runner, which only prints the results,
+//! the results are kept to be reported as text or as JSON lines, and written to a report file.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use anyhow::{Context, Result};
+use cairo_lang_runner::profiling::ProfilingInfoCollectionConfig;
+use cairo_lang_runner::{RunResultValue, SierraCasmRunner, StarknetState};
+use cairo_lang_sierra_to_casm::metadata::MetadataComputationConfig;
+use cairo_lang_test_plugin::test_config::{PanicExpectation, TestConfig, TestExpectation};
+use cairo_lang_test_plugin::{TestCompilation, TestCompilationMetadata};
+use dojo_compiler::compiler::messages::{self, DiagnosticMessage};
+use dojo_compiler::diagnostic_codes;
+use rayon::prelude::*;
+use scarb_ui::{Message, Ui};
+use serde::{Serialize, Serializer};
+
+/// The felt starting a `ByteArray` serialized in the panic data, without `0x` prefix.
+const BYTE_ARRAY_MAGIC: &str = "46a6158a16a947e5916b2a2ca68501a45e93d7110e81aa2d6438b1c57c879a3";
+/// The number of bytes of a full word of a `ByteArray`, which is also the maximum length of a
+/// short string.
+const BYTES_IN_WORD: usize = 31;
+
+/// The syscalls running the code of another contract, in a nested entry point.
+const NESTED_CALL_SYSCALLS: &[&str] = &["CallContract", "LibraryCall", "Deploy"];
+
+/// A shard of the tests, to split them across several machines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Shard {
+    /// The index of the shard, 1 based.
+    pub index: usize,
+    /// The number of shards.
+    pub count: usize,
+}
+
+impl Shard {
+    /// Parses a shard from its `i/n` representation, with `1 <= i <= n`.
+    pub fn parse(shard: &str) -> Result<Self> {
+        let parsed = shard.split_once('/').and_then(|(index, count)| {
+            Some(Self {
+                index: index.trim().parse().ok()?,
+                count: count.trim().parse().ok()?,
+            })
+        });
+
+        match parsed {
+            Some(shard) if shard.index >= 1 && shard.index <= shard.count => Ok(shard),
+            _ => anyhow::bail!("Invalid shard `{shard}`, expected `i/n` with 1 <= i <= n."),
+        }
+    }
+
+    /// Checks if the test at the given position of the sorted test list is in the shard.
+    fn contains(&self, position: usize) -> bool {
+        position % self.count == self.index - 1
+    }
+}
+
+/// The options of a test run.
+#[derive(Debug, Clone, Default)]
+pub struct TestRunOptions {
+    /// Runs only the tests whose name contains the filter.
+    pub filter: String,
+    /// Runs only the test whose name is the filter.
+    pub exact: bool,
+    /// Runs only the tests of the shard.
+    pub shard: Option<Shard>,
+    /// Runs the ignored tests as well.
+    pub include_ignored: bool,
+    /// Runs only the ignored tests.
+    pub ignored: bool,
+    /// Runs the tests with gas.
+    pub gas_enabled: bool,
+    /// Prints the resources used by each test.
+    pub print_resource_usage: bool,
+    /// Records the Sierra statements executed by each test.
+    pub coverage: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TestStatus {
+    Passed,
+    Failed,
+    Ignored,
+}
+
+/// The result of a test.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename = "test_result")]
+pub struct TestResult {
+    pub name: String,
+    pub status: TestStatus,
+    /// The gas used by the test, if run with gas.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gas_used: Option<u64>,
+    /// The panic data of the test as hexadecimal felts, if it panicked.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub panic_data: Option<Vec<String>>,
+    /// The panic data decoded as short strings and byte arrays, if it panicked.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub panic_message: Option<String>,
+    /// The steps, memory holes, builtins and syscalls used by the test.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub resources: BTreeMap<String, usize>,
+    /// The Sierra statements executed by the test, if run with coverage.
+    #[serde(skip)]
+    pub executed_statements: BTreeSet<usize>,
+}
+
+impl TestResult {
+    fn ignored(name: String) -> Self {
+        Self {
+            name,
+            status: TestStatus::Ignored,
+            gas_used: None,
+            panic_data: None,
+            panic_message: None,
+            resources: BTreeMap::new(),
+            executed_statements: BTreeSet::new(),
+        }
+    }
+
+    /// Checks if the test called or deployed a contract, whose code runs in a nested entry
+    /// point and isn't part of the executed statements.
+    pub fn calls_contracts(&self) -> bool {
+        NESTED_CALL_SYSCALLS
+            .iter()
+            .any(|syscall| self.resources.contains_key(&format!("syscall:{syscall}")))
+    }
+}
+
+impl Message for TestResult {
+    fn text(self) -> String {
+        let status = match self.status {
+            TestStatus::Passed => "ok",
+            TestStatus::Failed => "fail",
+            TestStatus::Ignored => "ignored",
+        };
+
+        let mut text = format!("test {} ... {status}", self.name);
+
+        if let Some(gas_used) = self.gas_used {
+            text.push_str(&format!(" (gas usage est.: {gas_used})"));
+        }
+
+        text
+    }
+
+    fn structured<S: Serializer>(self, ser: S) -> Result<S::Ok, S::Error> {
+        self.serialize(ser)
+    }
+}
+
+/// A test found in the compiled tests, with its attributes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename = "test")]
+pub struct TestListing {
+    pub name: String,
+    pub ignored: bool,
+    pub should_panic: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub available_gas: Option<usize>,
+}
+
+impl Message for TestListing {
+    fn text(self) -> String {
+        let mut attributes = vec![];
+
+        if self.ignored {
+            attributes.push("ignored".to_string());
+        }
+
+        if self.should_panic {
+            attributes.push("should_panic".to_string());
+        }
+
+        if let Some(available_gas) = self.available_gas {
+            attributes.push(format!("available_gas={available_gas}"));
+        }
+
+        if attributes.is_empty() {
+            format!("{}: test", self.name)
+        } else {
+            format!("{}: test [{}]", self.name, attributes.join(", "))
+        }
+    }
+
+    fn structured<S: Serializer>(self, ser: S) -> Result<S::Ok, S::Error> {
+        self.serialize(ser)
+    }
+}
+
+/// The results of a test run.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TestsSummary {
+    pub results: Vec<TestResult>,
+    /// The number of tests not matching the filter.
+    pub filtered_out: usize,
+}
+
+/// The counts of the results of a test run.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename = "test_summary")]
+struct SummaryMessage {
+    passed: usize,
+    failed: usize,
+    ignored: usize,
+    filtered_out: usize,
+}
+
+impl Message for SummaryMessage {
+    fn text(self) -> String {
+        format!(
+            "test result: {}. {} passed; {} failed; {} ignored; {} filtered out;",
+            if self.failed == 0 { "ok" } else { "FAILED" },
+            self.passed,
+            self.failed,
+            self.ignored,
+            self.filtered_out
+        )
+    }
+
+    fn structured<S: Serializer>(self, ser: S) -> Result<S::Ok, S::Error> {
+        self.serialize(ser)
+    }
+}
+
+impl TestsSummary {
+    /// Returns the number of tests with the given status.
+    pub fn count(&self, status: TestStatus) -> usize {
+        self.results.iter().filter(|r| r.status == status).count()
+    }
+
+    /// Returns the failed tests.
+    pub fn failed(&self) -> impl Iterator<Item = &TestResult> {
+        self.results
+            .iter()
+            .filter(|r| r.status == TestStatus::Failed)
+    }
+
+    /// Reports the summary of the run, each test result having been reported while running.
+    ///
+    /// The failures are listed as text, or emitted as diagnostics in the JSON mode.
+    pub fn report(&self, ui: &Ui) {
+        let failed = self.failed().collect::<Vec<_>>();
+
+        if !failed.is_empty() && !messages::is_json(ui) {
+            ui.print("failures:");
+        }
+
+        for result in failed {
+            let failure = match &result.panic_message {
+                Some(message) => format!("{} - Panicked with [{message}].", result.name),
+                None => format!("{} - Expected panic, but the test passed.", result.name),
+            };
+
+            if messages::is_json(ui) {
+                messages::emit(
+                    ui,
+                    DiagnosticMessage::error(diagnostic_codes::TEST_FAILED.code, failure),
+                );
+            } else {
+                ui.print(format!("   {failure}"));
+            }
+        }
+
+        ui.print(SummaryMessage {
+            passed: self.count(TestStatus::Passed),
+            failed: self.count(TestStatus::Failed),
+            ignored: self.count(TestStatus::Ignored),
+            filtered_out: self.filtered_out,
+        });
+    }
+}
+
+/// Runs the compiled tests matching the options, reporting each result to the UI.
+///
+/// # Arguments
+///
+/// * `compiled` - The compiled tests.
+/// * `options` - The options of the run.
+/// * `ui` - The UI to report the results to.
+pub fn run_tests(
+    compiled: TestCompilation,
+    options: &TestRunOptions,
+    ui: &Ui,
+) -> Result<TestsSummary> {
+    let TestCompilation {
+        sierra_program,
+        metadata,
+    } = compiled;
+
+    let (named_tests, filtered_out) = select_tests(metadata.named_tests, options);
+
+    let runner = SierraCasmRunner::new(
+        sierra_program.program,
+        options.gas_enabled.then(|| MetadataComputationConfig {
+            function_set_costs: metadata.function_set_costs,
+            linear_gas_solver: true,
+            linear_ap_change_solver: true,
+            skip_non_linear_solver_comparisons: false,
+            compute_runtime_costs: false,
+        }),
+        metadata.contracts_info,
+        options
+            .coverage
+            .then(ProfilingInfoCollectionConfig::default),
+    )
+    .context("Failed to set up the runner.")?;
+
+    if !messages::is_json(ui) {
+        ui.print(format!("running {} tests", named_tests.len()));
+    }
+
+    // The tests are run in parallel, their results being reported in order once all run.
+    let results = named_tests
+        .into_par_iter()
+        .map(|(name, test)| {
+            let is_ignored = test.ignored && !options.include_ignored && !options.ignored;
+
+            if is_ignored {
+                Ok(TestResult::ignored(name))
+            } else {
+                run_test(&runner, name, test, options)
+            }
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    for result in &results {
+        ui.print(result.clone());
+
+        if options.print_resource_usage && !messages::is_json(ui) {
+            for (resource, count) in &result.resources {
+                ui.print(format!("    {resource}: {count}"));
+            }
+        }
+    }
+
+    Ok(TestsSummary {
+        results,
+        filtered_out,
+    })
+}
+
+/// Runs a single test.
+///
+/// # Arguments
+///
+/// * `runner` - The runner of the compiled tests.
+/// * `name` - The full name of the test.
+/// * `test` - The configuration of the test.
+/// * `options` - The options of the run.
+fn run_test(
+    runner: &SierraCasmRunner,
+    name: String,
+    test: TestConfig,
+    options: &TestRunOptions,
+) -> Result<TestResult> {
+    let func = runner.find_function(&name)?;
+    let available_gas = if options.gas_enabled {
+        test.available_gas
+    } else {
+        None
+    };
+
+    let run = runner
+        .run_function_with_starknet_context(func, &[], available_gas, StarknetState::default())
+        .with_context(|| format!("Failed to run the test `{name}`."))?;
+
+    let (status, panic_data) = match (&run.value, &test.expectation) {
+        (RunResultValue::Success(_), TestExpectation::Success) => (TestStatus::Passed, None),
+        (RunResultValue::Success(_), TestExpectation::Panics(_)) => (TestStatus::Failed, None),
+        (RunResultValue::Panic(data), expectation) => {
+            let is_expected = match expectation {
+                TestExpectation::Success => false,
+                TestExpectation::Panics(PanicExpectation::Any) => true,
+                TestExpectation::Panics(PanicExpectation::Exact(expected)) => expected == data,
+            };
+
+            let panic_data = data
+                .iter()
+                .map(|felt| format!("{felt:#x}"))
+                .collect::<Vec<_>>();
+
+            if is_expected {
+                (TestStatus::Passed, Some(panic_data))
+            } else {
+                (TestStatus::Failed, Some(panic_data))
+            }
+        }
+    };
+
+    let panic_message = panic_data.as_deref().map(decode_panic_data);
+
+    let gas_used =
+        available_gas
+            .zip(run.gas_counter.as_ref())
+            .and_then(|(available, remaining)| {
+                let remaining = remaining.to_string().parse::<u64>().ok()?;
+                (available as u64).checked_sub(remaining)
+            });
+
+    let mut resources = BTreeMap::new();
+    let basic_resources = &run.used_resources.basic_resources;
+
+    resources.insert("steps".to_string(), basic_resources.n_steps);
+    resources.insert("memory_holes".to_string(), basic_resources.n_memory_holes);
+
+    for (builtin, count) in &basic_resources.builtin_instance_counter {
+        resources.insert(format!("{builtin:?}"), *count);
+    }
+
+    for (syscall, count) in &run.used_resources.syscalls {
+        resources.insert(format!("syscall:{syscall}"), *count);
+    }
+
+    // The statement weights are the steps spent in each statement, only telling which
+    // statements were executed.
+    let executed_statements = run
+        .profiling_info
+        .as_ref()
+        .map(|info| {
+            info.sierra_statement_weights
+                .iter_sorted()
+                .filter(|(_, weight)| **weight > 0)
+                .map(|(statement, _)| statement.0)
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Ok(TestResult {
+        name,
+        status,
+        gas_used,
+        panic_data,
+        panic_message,
+        resources,
+        executed_statements,
+    })
+}
+
+/// Lists the compiled tests matching the options, with their attributes.
+///
+/// # Arguments
+///
+/// * `metadata` - The metadata of the compiled tests.
+/// * `options` - The options of the run, the ignored tests being listed anyway.
+/// * `ui` - The UI to list the tests to.
+pub fn list_tests(metadata: &TestCompilationMetadata, options: &TestRunOptions, ui: &Ui) {
+    let (named_tests, _) = select_tests(metadata.named_tests.clone(), options);
+
+    for (name, test) in named_tests {
+        ui.print(TestListing {
+            name,
+            ignored: test.ignored,
+            should_panic: matches!(test.expectation, TestExpectation::Panics(_)),
+            available_gas: test.available_gas,
+        });
+    }
+}
+
+/// Selects the tests matching the filter and the shard of the options, sorted by name so the
+/// shards are deterministic. Like the Cairo test runner, only the ignored tests are selected
+/// when running the ignored tests.
+///
+/// Returns the selected tests and the number of tests filtered out.
+fn select_tests(
+    mut named_tests: Vec<(String, TestConfig)>,
+    options: &TestRunOptions,
+) -> (Vec<(String, TestConfig)>, usize) {
+    let total = named_tests.len();
+
+    named_tests.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    let selected = named_tests
+        .into_iter()
+        .filter(|(name, _)| {
+            if options.exact {
+                *name == options.filter
+            } else {
+                name.contains(&options.filter)
+            }
+        })
+        .filter(|(_, test)| !options.ignored || test.ignored)
+        .enumerate()
+        .filter(|(position, _)| options.shard.map_or(true, |s| s.contains(*position)))
+        .map(|(_, test)| test)
+        .collect::<Vec<_>>();
+
+    let filtered_out = total - selected.len();
+    (selected, filtered_out)
+}
+
+/// Decodes the panic data, given as hexadecimal felts, into a readable message.
+///
+/// A serialized `ByteArray` is decoded as a double quoted string, and a felt which is a
+/// printable short string is followed by the single quoted string, like `0x616263 ('abc')`.
+///
+/// # Arguments
+///
+/// * `panic_data` - The panic data, as `0x` prefixed hexadecimal felts.
+pub fn decode_panic_data(panic_data: &[String]) -> String {
+    let mut items = vec![];
+    let mut i = 0;
+
+    while i < panic_data.len() {
+        let felt = strip_hex_prefix(&panic_data[i]);
+
+        if felt == BYTE_ARRAY_MAGIC {
+            if let Some((text, len)) = decode_byte_array(&panic_data[i + 1..]) {
+                items.push(format!("{text:?}"));
+                i += 1 + len;
+                continue;
+            }
+        }
+
+        items.push(match decode_short_string(felt) {
+            Some(text) => format!("{} ('{text}')", panic_data[i]),
+            None => panic_data[i].clone(),
+        });
+
+        i += 1;
+    }
+
+    items.join(", ")
+}
+
+/// Decodes a serialized `ByteArray`: the number of full words, the full words, the pending
+/// word and the length of the pending word.
+///
+/// Returns the text and the number of felts of the serialized `ByteArray`.
+fn decode_byte_array(data: &[String]) -> Option<(String, usize)> {
+    let n_full_words = usize::from_str_radix(strip_hex_prefix(data.first()?), 16).ok()?;
+    let words = data.get(1..n_full_words.checked_add(3)?)?;
+
+    let mut bytes = vec![];
+
+    for word in &words[..n_full_words] {
+        bytes.extend(felt_bytes(word, BYTES_IN_WORD)?);
+    }
+
+    let pending_len = usize::from_str_radix(strip_hex_prefix(&words[n_full_words + 1]), 16).ok()?;
+
+    if pending_len >= BYTES_IN_WORD {
+        return None;
+    }
+
+    bytes.extend(felt_bytes(&words[n_full_words], pending_len)?);
+
+    Some((String::from_utf8(bytes).ok()?, n_full_words + 3))
+}
+
+/// Decodes a felt as a short string, if it only contains printable ASCII characters.
+fn decode_short_string(felt: &str) -> Option<String> {
+    let bytes = felt_bytes(felt, BYTES_IN_WORD)?;
+    let bytes = bytes
+        .into_iter()
+        .skip_while(|b| *b == 0)
+        .collect::<Vec<_>>();
+
+    if bytes.is_empty() || !bytes.iter().all(|b| b.is_ascii_graphic() || *b == b' ') {
+        return None;
+    }
+
+    String::from_utf8(bytes).ok()
+}
+
+/// Returns the big-endian bytes of a hexadecimal felt, padded to `len` bytes.
+/// Returns `None` if the felt doesn't fit in `len` bytes.
+fn felt_bytes(felt: &str, len: usize) -> Option<Vec<u8>> {
+    let hex = strip_hex_prefix(felt).trim_start_matches('0');
+
+    if hex.len() > len * 2 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    let hex = format!("{hex:0>width$}", width = len * 2);
+
+    (0..len)
+        .map(|i| u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).ok())
+        .collect()
+}
+
+fn strip_hex_prefix(felt: &str) -> &str {
+    felt.strip_prefix("0x").unwrap_or(felt)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn named_tests(names: &[&str]) -> Vec<(String, TestConfig)> {
+        names
+            .iter()
+            .map(|name| {
+                (
+                    name.to_string(),
+                    TestConfig {
+                        available_gas: None,
+                        expectation: TestExpectation::Success,
+                        ignored: name.ends_with("ignored"),
+                    },
+                )
+            })
+            .collect()
+    }
+
+    fn selected_names(selected: &[(String, TestConfig)]) -> Vec<&str> {
+        selected.iter().map(|(name, _)| name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_shard_parse() {
+        assert_eq!(Shard::parse("1/3").unwrap(), Shard { index: 1, count: 3 });
+        assert_eq!(
+            Shard::parse(" 3 / 3 ").unwrap(),
+            Shard { index: 3, count: 3 }
+        );
+
+        for invalid in ["0/3", "4/3", "1/0", "1", "a/3", "1/3/4", ""] {
+            assert!(Shard::parse(invalid).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn test_select_tests_filter() {
+        let tests = named_tests(&["pkg::b::test_move", "pkg::a::test_move", "pkg::test_spawn"]);
+
+        let options = TestRunOptions {
+            filter: "test_move".to_string(),
+            ..Default::default()
+        };
+        let (selected, filtered_out) = select_tests(tests.clone(), &options);
+        assert_eq!(
+            selected_names(&selected),
+            vec!["pkg::a::test_move", "pkg::b::test_move"]
+        );
+        assert_eq!(filtered_out, 1);
+
+        let options = TestRunOptions {
+            filter: "pkg::a::test_move".to_string(),
+            exact: true,
+            ..Default::default()
+        };
+        let (selected, filtered_out) = select_tests(tests.clone(), &options);
+        assert_eq!(selected_names(&selected), vec!["pkg::a::test_move"]);
+        assert_eq!(filtered_out, 2);
+
+        let options = TestRunOptions {
+            filter: "test_move".to_string(),
+            exact: true,
+            ..Default::default()
+        };
+        assert!(select_tests(tests, &options).0.is_empty());
+    }
+
+    #[test]
+    fn test_select_tests_ignored() {
+        let tests = named_tests(&["pkg::test_a", "pkg::test_b_ignored"]);
+
+        let (selected, filtered_out) = select_tests(tests.clone(), &TestRunOptions::default());
+        assert_eq!(selected.len(), 2);
+        assert_eq!(filtered_out, 0);
+
+        let options = TestRunOptions {
+            ignored: true,
+            ..Default::default()
+        };
+        let (selected, filtered_out) = select_tests(tests, &options);
+        assert_eq!(selected_names(&selected), vec!["pkg::test_b_ignored"]);
+        assert_eq!(filtered_out, 1);
+    }
+
+    #[test]
+    fn test_select_tests_shards() {
+        let tests = named_tests(&["t5", "t4", "t3", "t2", "t1"]);
+
+        let shards = (1..=2)
+            .map(|index| {
+                let options = TestRunOptions {
+                    shard: Some(Shard { index, count: 2 }),
+                    ..Default::default()
+                };
+                let (selected, filtered_out) = select_tests(tests.clone(), &options);
+                assert_eq!(selected.len() + filtered_out, tests.len());
+
+                selected_names(&selected)
+                    .into_iter()
+                    .map(str::to_string)
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        // The shards are disjoint, cover all the tests and don't depend on the input order.
+        assert_eq!(shards[0], vec!["t1", "t3", "t5"]);
+        assert_eq!(shards[1], vec!["t2", "t4"]);
+    }
+
+    fn hex(text: &str) -> String {
+        let hex = text.bytes().map(|b| format!("{b:02x}")).collect::<String>();
+        format!("0x{}", hex.trim_start_matches('0'))
+    }
+
+    fn byte_array(text: &str) -> Vec<String> {
+        let chunks = text.as_bytes().chunks(BYTES_IN_WORD).collect::<Vec<_>>();
+        let (full_words, pending) = match chunks.last() {
+            Some(last) if last.len() < BYTES_IN_WORD => (&chunks[..chunks.len() - 1], *last),
+            _ => (&chunks[..], &[][..]),
+        };
+
+        let mut data = vec![
+            format!("0x{BYTE_ARRAY_MAGIC}"),
+            format!("{:#x}", full_words.len()),
+        ];
+        data.extend(
+            full_words
+                .iter()
+                .map(|word| hex(std::str::from_utf8(word).unwrap())),
+        );
+        data.push(if pending.is_empty() {
+            "0x0".to_string()
+        } else {
+            hex(std::str::from_utf8(pending).unwrap())
+        });
+        data.push(format!("{:#x}", pending.len()));
+        data
+    }
+
+    #[test]
+    fn test_decode_short_strings() {
+        let panic_data = vec![
+            "0x616263".to_string(),
+            "0x1".to_string(),
+            hex("wrong caller"),
+        ];
+
+        assert_eq!(
+            decode_panic_data(&panic_data),
+            "0x616263 ('abc'), 0x1, 0x77726f6e672063616c6c6572 ('wrong caller')"
+        );
+    }
+
+    #[test]
+    fn test_decode_byte_array() {
+        assert_eq!(decode_panic_data(&byte_array("hello")), "\"hello\"");
+
+        let long_message = "a message longer than a single word of the byte array";
+        assert_eq!(
+            decode_panic_data(&byte_array(long_message)),
+            format!("{long_message:?}")
+        );
+
+        let exact_word = "abcdefghijklmnopqrstuvwxyz01234";
+        assert_eq!(
+            decode_panic_data(&byte_array(exact_word)),
+            format!("{exact_word:?}")
+        );
+    }
+
+    #[test]
+    fn test_decode_byte_array_with_other_felts() {
+        let mut panic_data = byte_array("not enough funds");
+        panic_data.push("0x1".to_string());
+        panic_data.insert(0, "0x0".to_string());
+
+        assert_eq!(
+            decode_panic_data(&panic_data),
+            "0x0, \"not enough funds\", 0x1"
+        );
+    }
+
+    #[test]
+    fn test_decode_invalid_byte_array() {
+        // A truncated byte array is not decoded, its felts being output as is.
+        let mut panic_data = byte_array("hello");
+        panic_data.truncate(3);
+
+        assert_eq!(
+            decode_panic_data(&panic_data),
+            format!("0x{BYTE_ARRAY_MAGIC}, 0x0, 0x68656c6c6f ('hello')")
+        );
+    }
+
+    #[test]
+    fn test_calls_contracts() {
+        let mut result = TestResult::ignored("test_spawn".to_string());
+        result
+            .resources
+            .insert("syscall:StorageWrite".to_string(), 2);
+
+        assert!(!result.calls_contracts());
+
+        result
+            .resources
+            .insert("syscall:CallContract".to_string(), 1);
+
+        assert!(result.calls_contracts());
+    }
+}
@@ -2,13 +2,17 @@
 //!
 //! The plugin generates aux data for models, contracts and events.
 //! Then the compiler uses this aux data to generate the manifests and organize the artifacts.
+//!
+//! The other files generated by the plugin (derives and interfaces) have a [`DojoGeneratedAuxData`]
+//! only identifying the plugin, to attribute their code to the plugin in the size report.
 
 use anyhow::Result;
-use cairo_lang_defs::plugin::GeneratedFileAuxData;
+use cairo_lang_defs::plugin::{DynGeneratedFileAuxData, GeneratedFileAuxData};
 use cairo_lang_starknet::plugin::aux_data::StarkNetContractAuxData;
 use convert_case::{Case, Casing};
 use dojo_types::naming;
 use serde::{Deserialize, Serialize};
+use starknet::core::utils::cairo_short_string_to_felt;
 use tracing::trace;
 
 use super::compiler::annotation::Member;
@@ -23,6 +27,9 @@ use crate::{
 pub struct ModelAuxData {
     pub name: String,
     pub namespace: String,
+    /// The rule of the namespace configuration that assigned the namespace.
+    pub namespace_rule: String,
+    pub version: u8,
     pub members: Vec<Member>,
 }
 
@@ -30,6 +37,8 @@ pub struct ModelAuxData {
 pub struct ContractAuxData {
     pub name: String,
     pub namespace: String,
+    /// The rule of the namespace configuration that assigned the namespace.
+    pub namespace_rule: String,
     pub systems: Vec<String>,
 }
 
@@ -37,9 +46,26 @@ pub struct ContractAuxData {
 pub struct EventAuxData {
     pub name: String,
     pub namespace: String,
+    /// The rule of the namespace configuration that assigned the namespace.
+    pub namespace_rule: String,
+    pub version: u8,
     pub members: Vec<Member>,
 }
 
+/// Aux data of the files generated by the plugin without resource, like the derives.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DojoGeneratedAuxData;
+
+/// Checks if the aux data is the one of a file generated by the Dojo plugin.
+pub fn is_dojo_aux_data(aux_data: &DynGeneratedFileAuxData) -> bool {
+    let aux_data = aux_data.0.as_any();
+
+    aux_data.is::<ModelAuxData>()
+        || aux_data.is::<EventAuxData>()
+        || aux_data.is::<ContractAuxData>()
+        || aux_data.is::<DojoGeneratedAuxData>()
+}
+
 pub trait AuxDataToAnnotation<T> {
     /// Returns the qualified path of the contract, since dependingo on the aux data type
     /// the qualified path is computed differently from the module path.
@@ -89,10 +115,21 @@ impl AuxDataToAnnotation<ModelAnnotation> for ModelAuxData {
     fn to_annotation(&self, module_path: &str) -> Result<ModelAnnotation> {
         let contract_qualified_path = self.contract_qualified_path(module_path);
 
+        // Models of version 0 use their name as selector.
+        let selector = match self.version {
+            0 => cairo_short_string_to_felt(&self.name)?,
+            _ => naming::compute_selector_from_names(&self.namespace, &self.name),
+        };
+
         let annotation = ModelAnnotation {
             qualified_path: contract_qualified_path.clone(),
             tag: naming::get_tag(&self.namespace, &self.name),
+            selector,
+            namespace_hash: naming::compute_bytearray_hash(&self.namespace),
+            version: self.version,
             members: self.members.clone(),
+            namespace_rule: self.namespace_rule.clone(),
+            ..Default::default()
         };
 
         trace!(
@@ -142,7 +179,12 @@ impl AuxDataToAnnotation<EventAnnotation> for EventAuxData {
         let annotation = EventAnnotation {
             qualified_path: contract_qualified_path.clone(),
             tag: naming::get_tag(&self.namespace, &self.name),
+            selector: naming::compute_selector_from_names(&self.namespace, &self.name),
+            namespace_hash: naming::compute_bytearray_hash(&self.namespace),
+            version: self.version,
             members: self.members.clone(),
+            namespace_rule: self.namespace_rule.clone(),
+            ..Default::default()
         };
 
         trace!(
@@ -171,6 +213,16 @@ impl GeneratedFileAuxData for ContractAuxData {
     }
 }
 
+impl GeneratedFileAuxData for DojoGeneratedAuxData {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn eq(&self, other: &dyn GeneratedFileAuxData) -> bool {
+        other.as_any().is::<Self>()
+    }
+}
+
 impl AuxDataToAnnotation<ContractAnnotation> for ContractAuxData {
     fn contract_qualified_path(&self, module_path: &str) -> String {
         // The module path for contracts is the path to the contract file, not the fully
@@ -186,6 +238,8 @@ impl AuxDataToAnnotation<ContractAnnotation> for ContractAuxData {
             qualified_path: contract_qualified_path.clone(),
             tag: naming::get_tag(&self.namespace, &self.name),
             systems: self.systems.clone(),
+            namespace_rule: self.namespace_rule.clone(),
+            ..Default::default()
         };
 
         trace!(
@@ -212,6 +266,7 @@ impl AuxDataToAnnotation<StarknetContractAnnotation> for StarkNetContractAuxData
         let annotation = StarknetContractAnnotation {
             qualified_path: contract_qualified_path.clone(),
             name: self.contract_name.to_string(),
+            ..Default::default()
         };
 
         trace!(
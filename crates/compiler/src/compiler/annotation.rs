@@ -12,26 +12,41 @@
 //! The qualified path in the annotation is the link connecting the artifact
 //! to the annotation.
 
+use std::collections::{BTreeMap, HashMap};
 use std::io::{Read, Write};
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use cairo_lang_compiler::db::RootDatabase;
 use cairo_lang_defs::db::DefsGroup;
+use cairo_lang_defs::ids::ModuleId;
 use cairo_lang_filesystem::ids::CrateId;
 use cairo_lang_starknet::plugin::aux_data::StarkNetContractAuxData;
+use cairo_lang_syntax::node::ids::SyntaxStablePtrId;
+use camino::Utf8Path;
+use dojo_types::model::Layout;
 use dojo_types::naming;
-use scarb::core::Workspace;
+use dojo_types::schema::Ty;
+use dojo_types::system::Dependency;
+use scarb::core::{Package, Workspace};
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
+use starknet::core::types::Felt;
+use tracing::trace;
 
+use super::dependencies::{
+    contract_module, functions_accesses, model_writes, to_dependencies, SystemAccesses,
+};
+use super::introspect::{introspect_struct, Introspection};
 use crate::aux_data::{AuxDataToAnnotation, ContractAuxData, EventAuxData, ModelAuxData};
+use crate::namespace_config::NamespaceConfig;
 use crate::scarb_extensions::WorkspaceExt;
 use crate::{
     CAIRO_PATH_SEPARATOR, RESOURCE_METADATA_QUALIFIED_PATH, WORLD_CONTRACT_TAG,
     WORLD_QUALIFIED_PATH,
 };
 
-const DOJO_ANNOTATION_FILE_NAME: &str = "annotations";
+/// Name of the annotations file, without extension.
+pub const DOJO_ANNOTATION_FILE_NAME: &str = "annotations";
 
 pub trait AnnotationInfo {
     fn filename(&self) -> String;
@@ -58,6 +73,19 @@ pub struct ContractAnnotation {
     pub qualified_path: String,
     pub tag: String,
     pub systems: Vec<String>,
+    /// The rule of the namespace configuration that assigned the namespace.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub namespace_rule: String,
+    /// The models read and written by each system, named by tag if the model is
+    /// compiled with the contract, or by its Cairo path otherwise.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub dependencies: BTreeMap<String, Vec<Dependency>>,
+    /// The write calls of the functions of the contract, systems or not, by Cairo path of the
+    /// written model. Only known during the build, to locate the calls.
+    #[serde(skip)]
+    pub model_writes: BTreeMap<String, Vec<SyntaxStablePtrId>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compiled_class_hash: Option<Felt>,
 }
 
 /// Represents the annotations of a dojo model.
@@ -68,7 +96,28 @@ pub struct ContractAnnotation {
 pub struct ModelAnnotation {
     pub qualified_path: String,
     pub tag: String,
+    #[serde(default)]
+    pub selector: Felt,
+    #[serde(default)]
+    pub namespace_hash: Felt,
+    #[serde(default)]
+    pub version: u8,
     pub members: Vec<Member>,
+    /// The rule of the namespace configuration that assigned the namespace.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub namespace_rule: String,
+    /// Number of felts of the values, `None` if dynamic.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unpacked_size: Option<usize>,
+    /// Number of felts of the bit-packed values, `None` if the layout is not fixed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub packed_size: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub layout: Option<Layout>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schema: Option<Ty>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compiled_class_hash: Option<Felt>,
 }
 
 /// Represents the annotations of a dojo event.
@@ -79,7 +128,28 @@ pub struct ModelAnnotation {
 pub struct EventAnnotation {
     pub qualified_path: String,
     pub tag: String,
+    #[serde(default)]
+    pub selector: Felt,
+    #[serde(default)]
+    pub namespace_hash: Felt,
+    #[serde(default)]
+    pub version: u8,
     pub members: Vec<Member>,
+    /// The rule of the namespace configuration that assigned the namespace.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub namespace_rule: String,
+    /// Number of felts of the values, `None` if dynamic.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unpacked_size: Option<usize>,
+    /// Number of felts of the bit-packed values, `None` if the layout is not fixed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub packed_size: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub layout: Option<Layout>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schema: Option<Ty>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compiled_class_hash: Option<Felt>,
 }
 
 /// Represents the world contract annotation.
@@ -90,6 +160,8 @@ pub struct EventAnnotation {
 pub struct WorldAnnotation {
     pub qualified_path: String,
     pub tag: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compiled_class_hash: Option<Felt>,
 }
 
 impl Default for WorldAnnotation {
@@ -97,6 +169,7 @@ impl Default for WorldAnnotation {
         Self {
             qualified_path: WORLD_QUALIFIED_PATH.to_string(),
             tag: WORLD_CONTRACT_TAG.to_string(),
+            compiled_class_hash: None,
         }
     }
 }
@@ -109,6 +182,34 @@ impl Default for WorldAnnotation {
 pub struct StarknetContractAnnotation {
     pub qualified_path: String,
     pub name: String,
+    /// The tag of the contract, if a namespace mapping assigned it a namespace.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+    /// The rule of the namespace configuration that assigned the namespace.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub namespace_rule: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compiled_class_hash: Option<Felt>,
+}
+
+/// Computes the introspection of a model or event struct.
+///
+/// The introspection is informative only: a type not supported by the static introspection
+/// (like a type with a custom `Introspect` implementation) doesn't fail the build,
+/// the layout and the schema are omitted from the annotation instead.
+fn introspect(
+    db: &RootDatabase,
+    module_id: ModuleId,
+    name: &str,
+    tag: &str,
+) -> Option<Introspection> {
+    match introspect_struct(db, module_id, name) {
+        Ok(introspection) => Some(introspection),
+        Err(e) => {
+            trace!(tag, error = ?e, "Failed to introspect the resource, layout and schema are omitted.");
+            None
+        }
+    }
 }
 
 impl AnnotationInfo for ModelAnnotation {
@@ -131,7 +232,10 @@ impl AnnotationInfo for ContractAnnotation {
 
 impl AnnotationInfo for StarknetContractAnnotation {
     fn filename(&self) -> String {
-        self.qualified_path.replace(CAIRO_PATH_SEPARATOR, "_")
+        match &self.tag {
+            Some(tag) => naming::get_filename_from_tag(tag),
+            None => self.qualified_path.replace(CAIRO_PATH_SEPARATOR, "_"),
+        }
     }
 }
 
@@ -183,6 +287,12 @@ impl DojoAnnotation {
     pub fn from_aux_data(db: &RootDatabase, crate_ids: &[CrateId]) -> Result<Self> {
         let mut annotations = DojoAnnotation::default();
 
+        // The model accesses by function of each contract, by index of the contract
+        // annotation, and the model tags by Cairo path of the model struct, to name the
+        // dependencies once all the models are known.
+        let mut contracts_accesses: Vec<(usize, BTreeMap<String, SystemAccesses>)> = vec![];
+        let mut model_tags: HashMap<String, String> = HashMap::new();
+
         for crate_id in crate_ids {
             for module_id in db.crate_modules(*crate_id).as_ref() {
                 let file_infos = db
@@ -199,18 +309,51 @@ impl DojoAnnotation {
 
                     if let Some(aux_data) = aux_data.downcast_ref::<ContractAuxData>() {
                         let annotation = aux_data.to_annotation(&module_path)?;
+
+                        if let Some(contract_module_id) =
+                            contract_module(db, *module_id, &aux_data.name)
+                        {
+                            let accesses = functions_accesses(db, contract_module_id);
+                            contracts_accesses.push((annotations.contracts.len(), accesses));
+                        }
+
                         annotations.contracts.push(annotation);
                         continue;
                     }
 
                     if let Some(aux_data) = aux_data.downcast_ref::<ModelAuxData>() {
-                        let annotation = aux_data.to_annotation(&module_path)?;
+                        let mut annotation = aux_data.to_annotation(&module_path)?;
+
+                        if let Some(introspection) =
+                            introspect(db, *module_id, &aux_data.name, &annotation.tag)
+                        {
+                            annotation.unpacked_size = introspection.unpacked_size;
+                            annotation.packed_size = introspection.packed_size;
+                            annotation.layout = Some(introspection.layout);
+                            annotation.schema = Some(introspection.schema);
+                        }
+
+                        model_tags.insert(
+                            format!("{}{}{}", module_path, CAIRO_PATH_SEPARATOR, aux_data.name),
+                            annotation.tag.clone(),
+                        );
+
                         annotations.models.push(annotation);
                         continue;
                     }
 
                     if let Some(aux_data) = aux_data.downcast_ref::<EventAuxData>() {
-                        let annotation = aux_data.to_annotation(&module_path)?;
+                        let mut annotation = aux_data.to_annotation(&module_path)?;
+
+                        if let Some(introspection) =
+                            introspect(db, *module_id, &aux_data.name, &annotation.tag)
+                        {
+                            annotation.unpacked_size = introspection.unpacked_size;
+                            annotation.packed_size = introspection.packed_size;
+                            annotation.layout = Some(introspection.layout);
+                            annotation.schema = Some(introspection.schema);
+                        }
+
                         annotations.events.push(annotation);
                         continue;
                     }
@@ -219,10 +362,7 @@ impl DojoAnnotation {
                         let annotation = aux_data.to_annotation(&module_path)?;
 
                         if annotation.qualified_path == WORLD_QUALIFIED_PATH {
-                            annotations.world = WorldAnnotation {
-                                qualified_path: WORLD_QUALIFIED_PATH.to_string(),
-                                tag: WORLD_CONTRACT_TAG.to_string(),
-                            };
+                            annotations.world = WorldAnnotation::default();
                         } else if annotation.qualified_path == RESOURCE_METADATA_QUALIFIED_PATH {
                             // Skip this annotation as not used in the migration process.
                             continue;
@@ -234,6 +374,20 @@ impl DojoAnnotation {
             }
         }
 
+        for (index, mut accesses) in contracts_accesses {
+            let contract = &mut annotations.contracts[index];
+            contract.model_writes = model_writes(&accesses);
+
+            // The dependencies are only the ones of the systems.
+            accesses.retain(|name, _| contract.systems.contains(name));
+            contract.dependencies = to_dependencies(&accesses, |path| {
+                model_tags
+                    .get(path)
+                    .cloned()
+                    .unwrap_or_else(|| path.to_string())
+            });
+        }
+
         // Since dojo resources are just starknet contracts under the hood,
         // we remove them from the sn_contracts list. We can't filter them earlier
         // as we need to wait all the annotations to be extracted before filtering.
@@ -247,14 +401,174 @@ impl DojoAnnotation {
         Ok(annotations)
     }
 
-    /// Reads the annotations from the target directory of the provided workspace,
+    /// Sets the dojo annotations of the main crates and of the selected resources of the
+    /// external crates, with the starknet contracts tagged from the namespace configuration.
+    ///
+    /// Only the plugins aux data is required, hence the annotations can be computed without
+    /// generating the code of the contracts.
+    ///
+    /// # Arguments
+    ///
+    /// * `db` - The database of the compilation unit.
+    /// * `crate_ids` - The main crates of the compilation unit.
+    /// * `external_crate_ids` - The crates of the external contracts.
+    /// * `namespace_config` - The namespace configuration of the profile.
+    /// * `is_selected` - Returns whether the external resource at the given qualified path is
+    ///   annotated.
+    pub fn from_crates(
+        db: &RootDatabase,
+        crate_ids: &[CrateId],
+        external_crate_ids: &[CrateId],
+        namespace_config: &NamespaceConfig,
+        is_selected: impl Fn(&str) -> bool,
+    ) -> Result<Self> {
+        let mut annotations = DojoAnnotation::from_aux_data(db, crate_ids)?;
+
+        let external_crate_ids = external_crate_ids
+            .iter()
+            .filter(|crate_id| !crate_ids.contains(crate_id))
+            .copied()
+            .collect::<Vec<_>>();
+
+        if !external_crate_ids.is_empty() {
+            let external_annotations = DojoAnnotation::from_aux_data(db, &external_crate_ids)?;
+            annotations.extend_selected(external_annotations, is_selected);
+        }
+
+        annotations.set_starknet_contract_tags(namespace_config)?;
+
+        Ok(annotations)
+    }
+
+    /// Adds the resources of another annotation, except its world, keeping only the
+    /// selected ones.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The annotation to add the resources of.
+    /// * `is_selected` - Returns whether the resource at the given qualified path is kept.
+    pub fn extend_selected(&mut self, other: DojoAnnotation, is_selected: impl Fn(&str) -> bool) {
+        self.contracts.extend(
+            other
+                .contracts
+                .into_iter()
+                .filter(|c| is_selected(&c.qualified_path)),
+        );
+        self.models.extend(
+            other
+                .models
+                .into_iter()
+                .filter(|m| is_selected(&m.qualified_path)),
+        );
+        self.events.extend(
+            other
+                .events
+                .into_iter()
+                .filter(|e| is_selected(&e.qualified_path)),
+        );
+        self.sn_contracts.extend(
+            other
+                .sn_contracts
+                .into_iter()
+                .filter(|c| is_selected(&c.qualified_path)),
+        );
+    }
+
+    /// Sets the tag of the starknet contracts matched by a pattern of the namespace mappings.
+    /// The other starknet contracts keep no tag.
+    ///
+    /// Since the tag names the artifact and the entries of the manifest and the lock, an error
+    /// is returned if a tag is already used by another resource, like two contracts with the
+    /// same name mapped to the same namespace.
+    ///
+    /// # Arguments
+    ///
+    /// * `namespace_config` - The namespace configuration of the profile.
+    pub fn set_starknet_contract_tags(&mut self, namespace_config: &NamespaceConfig) -> Result<()> {
+        for c in self.sn_contracts.iter_mut() {
+            let module_path = c
+                .qualified_path
+                .rsplit_once(CAIRO_PATH_SEPARATOR)
+                .map_or("", |(module_path, _)| module_path);
+
+            let resolved = namespace_config.resolve_starknet_contract(&c.name, module_path);
+
+            c.tag = resolved
+                .as_ref()
+                .map(|(namespace, _)| naming::get_tag(namespace, &c.name));
+            c.namespace_rule = resolved.map_or(String::new(), |(_, rule)| rule.to_string());
+        }
+
+        let mut tagged = self
+            .contracts
+            .iter()
+            .map(|c| (c.tag.as_str(), c.qualified_path.as_str()))
+            .chain(
+                self.models
+                    .iter()
+                    .map(|m| (m.tag.as_str(), m.qualified_path.as_str())),
+            )
+            .chain(
+                self.events
+                    .iter()
+                    .map(|e| (e.tag.as_str(), e.qualified_path.as_str())),
+            )
+            .collect::<HashMap<_, _>>();
+
+        for c in &self.sn_contracts {
+            let Some(tag) = &c.tag else {
+                continue;
+            };
+
+            if let Some(other) = tagged.insert(tag, &c.qualified_path) {
+                anyhow::bail!(
+                    "Starknet contract `{}` has the tag `{tag}` already used by `{other}`. Map \
+                     them to different namespaces or rename one of them.",
+                    c.qualified_path
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Sets the compiled class hash of each resource, from the given compiled class hashes
+    /// by qualified path. Resources without compiled class hash are reset to `None`.
+    ///
+    /// # Arguments
+    ///
+    /// * `compiled_class_hashes` - The compiled class hashes by qualified path.
+    pub fn set_compiled_class_hashes(&mut self, compiled_class_hashes: &HashMap<String, Felt>) {
+        let get = |qualified_path: &str| compiled_class_hashes.get(qualified_path).copied();
+
+        self.world.compiled_class_hash = get(&self.world.qualified_path);
+
+        for c in self.contracts.iter_mut() {
+            c.compiled_class_hash = get(&c.qualified_path);
+        }
+
+        for m in self.models.iter_mut() {
+            m.compiled_class_hash = get(&m.qualified_path);
+        }
+
+        for e in self.events.iter_mut() {
+            e.compiled_class_hash = get(&e.qualified_path);
+        }
+
+        for c in self.sn_contracts.iter_mut() {
+            c.compiled_class_hash = get(&c.qualified_path);
+        }
+    }
+
+    /// Reads the annotations from the target directory of the provided dojo package,
     /// for the current profile.
     ///
     /// # Arguments
     ///
     /// * `workspace` - The workspace to read the annotations from.
-    pub fn read(workspace: &Workspace<'_>) -> Result<Self> {
-        let target_dir = workspace.target_dir_profile();
+    /// * `package` - The dojo package of the artifacts.
+    pub fn read(workspace: &Workspace<'_>, package: &Package) -> Result<Self> {
+        let target_dir = workspace.dojo_target_dir(package);
 
         let mut file = target_dir.open_ro(
             format!("{}.toml", DOJO_ANNOTATION_FILE_NAME),
@@ -270,24 +584,155 @@ impl DojoAnnotation {
         Ok(annotations)
     }
 
-    /// Writes the annotations to the target directory of the provided workspace,
+    /// Reads the annotations from the given directory, without locking the file.
+    ///
+    /// Used by the plugin, which only knows the target directory of the dojo package
+    /// being compiled.
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - The directory containing the annotations file.
+    pub fn read_from_dir(dir: &Utf8Path) -> Result<Self> {
+        let file_path = dir.join(format!("{}.toml", DOJO_ANNOTATION_FILE_NAME));
+
+        let content = std::fs::read_to_string(&file_path)
+            .with_context(|| format!("Failed to read Dojo annotations `{file_path}`."))?;
+
+        Ok(toml::from_str(&content)?)
+    }
+
+    /// Writes the annotations to the target directory of the provided dojo package,
     /// for the current profile.
     ///
     /// # Arguments
     ///
     /// * `workspace` - The workspace to write the annotations to.
-    pub fn write(&self, workspace: &Workspace<'_>) -> Result<()> {
-        let target_dir = workspace.target_dir_profile();
+    /// * `package` - The dojo package of the artifacts.
+    pub fn write(&self, workspace: &Workspace<'_>, package: &Package) -> Result<()> {
+        let target_dir = workspace.dojo_target_dir(package);
         let content = toml::to_string(&self)?;
 
+        // Keep the file untouched if the annotations didn't change, which is
+        // mostly the case for incremental builds.
+        let file_path = target_dir
+            .child(format!("{}.toml", DOJO_ANNOTATION_FILE_NAME))
+            .to_string();
+
+        if std::fs::read_to_string(&file_path).is_ok_and(|existing| existing == content) {
+            return Ok(());
+        }
+
         let mut file = target_dir.create_rw(
             format!("{}.toml", DOJO_ANNOTATION_FILE_NAME),
             "Dojo annotations",
             workspace.config(),
         )?;
 
-        let _written = file.write(content.as_bytes())?;
+        file.write_all(content.as_bytes())?;
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    fn sn_contract(qualified_path: &str) -> StarknetContractAnnotation {
+        StarknetContractAnnotation {
+            qualified_path: qualified_path.to_string(),
+            name: qualified_path
+                .rsplit(CAIRO_PATH_SEPARATOR)
+                .next()
+                .unwrap()
+                .to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn namespace_config(mappings: &[(&str, &str)]) -> NamespaceConfig {
+        NamespaceConfig {
+            default: "ns".to_string(),
+            mappings: Some(
+                mappings
+                    .iter()
+                    .map(|(k, v)| (k.to_string(), v.to_string()))
+                    .collect(),
+            ),
+        }
+    }
+
+    #[test]
+    fn test_set_starknet_contract_tags() {
+        let mut annotation = DojoAnnotation {
+            sn_contracts: vec![
+                sn_contract("game::a::erc20"),
+                sn_contract("game::b::erc721"),
+            ],
+            ..Default::default()
+        };
+
+        annotation
+            .set_starknet_contract_tags(&namespace_config(&[("path:game::a", "tokens")]))
+            .unwrap();
+
+        assert_eq!(
+            annotation.sn_contracts[0].tag.as_deref(),
+            Some("tokens-erc20")
+        );
+        assert_eq!(annotation.sn_contracts[1].tag, None);
+    }
+
+    #[test]
+    fn test_set_starknet_contract_tags_duplicate() {
+        let mut annotation = DojoAnnotation {
+            sn_contracts: vec![sn_contract("game::a::erc20"), sn_contract("game::b::erc20")],
+            ..Default::default()
+        };
+
+        let err = annotation
+            .set_starknet_contract_tags(&namespace_config(&[("path:game::*", "tokens")]))
+            .unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "Starknet contract `game::b::erc20` has the tag `tokens-erc20` already used by \
+             `game::a::erc20`. Map them to different namespaces or rename one of them."
+        );
+    }
+
+    #[test]
+    fn test_read_annotations_without_selector() {
+        // The annotations written before the selector, the namespace hash and the version of
+        // the models and events were added.
+        let annotation: DojoAnnotation = toml::from_str(indoc! {r#"
+            contracts = []
+            sn_contracts = []
+
+            [world]
+            kind = "DojoWorld"
+            qualified_path = "dojo::world::world_contract::world"
+            tag = "dojo-world"
+
+            [[models]]
+            kind = "DojoModel"
+            qualified_path = "pkg::models::m_Position"
+            tag = "ns-Position"
+            members = []
+
+            [[events]]
+            kind = "DojoEvent"
+            qualified_path = "pkg::events::e_Moved"
+            tag = "ns-Moved"
+            members = []
+        "#})
+        .unwrap();
+
+        assert_eq!(annotation.models[0].selector, Felt::ZERO);
+        assert_eq!(annotation.models[0].version, 0);
+        assert_eq!(annotation.events[0].namespace_hash, Felt::ZERO);
+        assert_eq!(annotation.events[0].version, 0);
+    }
+}
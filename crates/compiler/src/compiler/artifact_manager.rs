@@ -9,26 +9,38 @@
 //!
 //! When using annotations, the qualified path is the link connecting the artifact
 //! to the annotation.
+//!
+//! The annotations also give the files expected in the output directories of the resources,
+//! the other artifact files being stale (left by a renamed resource for instance).
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::fs;
 use std::ops::DerefMut;
 use std::rc::Rc;
 
 use anyhow::{Context, Result};
 use cairo_lang_compiler::db::RootDatabase;
 use cairo_lang_filesystem::ids::CrateId;
+use cairo_lang_starknet_classes::casm_contract_class::CasmContractClass;
 use cairo_lang_starknet_classes::contract_class::ContractClass;
-use scarb::core::Workspace;
+use camino::{Utf8Path, Utf8PathBuf};
+use scarb::core::{Package, Workspace};
 use scarb::flock::Filesystem;
 use starknet::core::types::Felt;
 use tracing::trace;
 
-use crate::compiler::cairo_compiler::compute_class_hash_of_contract_class;
-use crate::scarb_extensions::WorkspaceExt;
-use crate::{CONTRACTS_DIR, EVENTS_DIR, MODELS_DIR};
+use crate::compiler::cairo_compiler::{
+    compute_class_hash_of_contract_class, compute_compiled_class_hash,
+};
+use crate::namespace_config::NamespaceConfig;
+use crate::scarb_extensions::{FilesystemExt, WorkspaceExt};
+use crate::{CONTRACTS_DIR, EVENTS_DIR, MODELS_DIR, STARKNET_CONTRACTS_DIR, WORLD_CONTRACT_TAG};
 
-use super::annotation::{AnnotationInfo, DojoAnnotation};
+use super::annotation::{AnnotationInfo, DojoAnnotation, DOJO_ANNOTATION_FILE_NAME};
+use super::manifest::BuildManifest;
+use super::messages::{self, ArtifactMessage};
 use super::scarb_internal::debug::SierraToCairoDebugInfo;
+use super::size_report::{ContractSize, SizeReport};
 
 #[derive(Debug, Clone)]
 pub struct CompiledArtifact {
@@ -38,6 +50,43 @@ pub struct CompiledArtifact {
     pub contract_class: Rc<ContractClass>,
     /// Optional debug info for the Sierra class.
     pub debug_info: Option<Rc<SierraToCairoDebugInfo>>,
+    /// The compiled class hash, if the CASM class was compiled.
+    pub compiled_class_hash: Option<Felt>,
+    /// Optional CASM class, compiled from the Sierra class.
+    pub casm_class: Option<Rc<CasmContractClass>>,
+    /// The size metrics of the compiled class.
+    pub size: Option<ContractSize>,
+}
+
+/// The suffix of the CASM class files, before the `.json` extension.
+pub const CASM_FILE_SUFFIX: &str = "compiled_contract_class";
+
+impl CompiledArtifact {
+    /// Returns true if the artifact has the outputs required by the build.
+    ///
+    /// # Arguments
+    ///
+    /// * `debug_info` - Whether the debug info is output.
+    /// * `casm` - Whether the CASM class is output.
+    pub fn has_outputs(&self, debug_info: bool, casm: bool) -> bool {
+        (!debug_info || self.debug_info.is_some()) && (!casm || self.casm_class.is_some())
+    }
+
+    /// Returns the names of the files of the artifact, given the file name of the resource
+    /// without extension.
+    pub fn file_names(&self, file_name: &str) -> Vec<String> {
+        let mut file_names = vec![format!("{file_name}.json")];
+
+        if self.debug_info.is_some() {
+            file_names.push(format!("{file_name}.debug.json"));
+        }
+
+        if self.casm_class.is_some() {
+            file_names.push(format!("{file_name}.{CASM_FILE_SUFFIX}.json"));
+        }
+
+        file_names
+    }
 }
 
 /// A type alias for a map of compiled artifacts by their path.
@@ -47,27 +96,63 @@ type CompiledArtifactByPath = HashMap<String, CompiledArtifact>;
 pub struct ArtifactManager<'w> {
     /// The workspace of the current compilation.
     workspace: &'w Workspace<'w>,
+    /// The dojo package of the artifacts.
+    package: Package,
     /// The compiled artifacts.
     compiled_artifacts: CompiledArtifactByPath,
+    /// Qualified paths of the artifacts reused from a previous build,
+    /// which are already written to the filesystem.
+    reused_artifacts: HashSet<String>,
     /// Dojo annotation.
     dojo_annotation: DojoAnnotation,
 }
 
 impl<'w> ArtifactManager<'w> {
-    /// Creates a new artifact manager.
-    pub fn new(workspace: &'w Workspace<'_>) -> Self {
+    /// Creates a new artifact manager for the artifacts of the given dojo package.
+    pub fn new(workspace: &'w Workspace<'_>, package: &Package) -> Self {
         Self {
             workspace,
+            package: package.clone(),
             compiled_artifacts: HashMap::new(),
+            reused_artifacts: HashSet::new(),
             dojo_annotation: DojoAnnotation::default(),
         }
     }
 
     /// Sets the dojo annotations form the aux data extracted from the database.
-    pub fn set_dojo_annotation(&mut self, db: &RootDatabase, crate_ids: &[CrateId]) -> Result<()> {
+    ///
+    /// The resources of the external crates (like other dojo packages of the workspace) are
+    /// only annotated if they were selected for compilation by `build-external-contracts`.
+    /// The starknet contracts are tagged from the namespace configuration.
+    pub fn set_dojo_annotation(
+        &mut self,
+        db: &RootDatabase,
+        crate_ids: &[CrateId],
+        external_crate_ids: &[CrateId],
+        namespace_config: &NamespaceConfig,
+    ) -> Result<()> {
         // Ensures that the dojo annotations are empty to not keep any stale data.
         self.dojo_annotation = DojoAnnotation::default();
-        self.dojo_annotation = DojoAnnotation::from_aux_data(db, crate_ids)?;
+        self.dojo_annotation = DojoAnnotation::from_crates(
+            db,
+            crate_ids,
+            external_crate_ids,
+            namespace_config,
+            |qualified_path| self.compiled_artifacts.contains_key(qualified_path),
+        )?;
+
+        let compiled_class_hashes = self
+            .compiled_artifacts
+            .iter()
+            .filter_map(|(path, artifact)| {
+                artifact
+                    .compiled_class_hash
+                    .map(|hash| (path.clone(), hash))
+            })
+            .collect::<HashMap<_, _>>();
+
+        self.dojo_annotation
+            .set_compiled_class_hashes(&compiled_class_hashes);
 
         Ok(())
     }
@@ -77,6 +162,16 @@ impl<'w> ArtifactManager<'w> {
         &self.dojo_annotation
     }
 
+    /// Returns the size report of the compiled artifacts.
+    pub fn size_report(&self) -> SizeReport {
+        SizeReport::new(
+            self.compiled_artifacts
+                .values()
+                .filter_map(|artifact| artifact.size.clone())
+                .collect(),
+        )
+    }
+
     /// Returns the workspace of the current compilation.
     pub fn workspace(&self) -> &Workspace<'_> {
         self.workspace
@@ -112,11 +207,30 @@ impl<'w> ArtifactManager<'w> {
         self.compiled_artifacts.insert(qualified_path, artifact);
     }
 
-    /// Writes all the dojo annotations and artifacts to the filesystem.
+    /// Adds an artifact reused from a previous build to the manager.
+    /// Reused artifacts are not written again to the filesystem if their files still exist.
+    ///
+    /// # Arguments
+    ///
+    /// * `qualified_path` - The cairo module qualified path of the artifact.
+    /// * `artifact` - The artifact read from the previous build.
+    pub fn add_reused_artifact(&mut self, qualified_path: String, artifact: CompiledArtifact) {
+        trace!(qualified_path, "Adding reused artifact to the manager.");
+        self.reused_artifacts.insert(qualified_path.clone());
+        self.compiled_artifacts.insert(qualified_path, artifact);
+    }
+
+    /// Writes all the dojo annotations, the build manifest, the size report and the artifacts
+    /// to the filesystem.
+    ///
+    /// The stale artifacts of the output directories, which don't belong to any annotated
+    /// resource, are removed.
     pub fn write(&self) -> Result<()> {
-        self.dojo_annotation.write(self.workspace)?;
+        self.dojo_annotation.write(self.workspace, &self.package)?;
+        BuildManifest::from_artifacts(self)?.write(self.workspace, &self.package)?;
+        self.size_report().write(self.workspace, &self.package)?;
 
-        let target_dir = self.workspace.target_dir_profile();
+        let target_dir = self.workspace.dojo_target_dir(&self.package);
 
         self.write_sierra_class(
             &self.dojo_annotation.world.qualified_path,
@@ -143,29 +257,51 @@ impl<'w> ArtifactManager<'w> {
         }
 
         for sn_contract in &self.dojo_annotation.sn_contracts {
-            // TODO: we might want to use namespace for starknet contracts too.
             let file_name = sn_contract.filename();
+            let target_dir = target_dir.child(STARKNET_CONTRACTS_DIR);
             self.write_sierra_class(&sn_contract.qualified_path, &target_dir, &file_name)?;
         }
 
+        let ui = self.workspace.config().ui();
+
+        let package_names = self
+            .workspace
+            .members()
+            .map(|p| p.id.name.to_string())
+            .collect::<Vec<_>>();
+
+        let legacy_artifacts = prune_legacy_dojo_layout(
+            self.workspace.target_dir_profile().path_unchecked(),
+            &package_names,
+        )?;
+
+        for path in prune_stale_artifacts(&target_dir, &self.dojo_annotation)?
+            .into_iter()
+            .chain(legacy_artifacts)
+        {
+            ui.verbose(format!("Removed stale artifact `{path}`."));
+        }
+
         Ok(())
     }
 
-    /// Reads the artifacts from the filesystem by reading the dojo annotations.
+    /// Reads the artifacts from the filesystem by reading the dojo annotations
+    /// and the size report.
     pub fn read(&mut self, workspace: &'w Workspace<'_>) -> Result<()> {
-        self.dojo_annotation = DojoAnnotation::read(workspace)?;
+        self.dojo_annotation = DojoAnnotation::read(workspace, &self.package)?;
+        let package_dir = workspace.dojo_target_dir(&self.package);
 
         self.add_artifact(
             self.dojo_annotation.world.qualified_path.to_string(),
             self.read_compiled_artifact(
                 &self.dojo_annotation.world.qualified_path,
-                &workspace.target_dir_profile(),
+                &package_dir,
                 &self.dojo_annotation.world.filename(),
             )?,
         );
 
         for contract in self.dojo_annotation.contracts.clone() {
-            let target_dir = workspace.target_dir_profile().child(CONTRACTS_DIR);
+            let target_dir = package_dir.child(CONTRACTS_DIR);
 
             self.add_artifact(
                 contract.qualified_path.to_string(),
@@ -178,7 +314,7 @@ impl<'w> ArtifactManager<'w> {
         }
 
         for model in self.dojo_annotation.models.clone() {
-            let target_dir = workspace.target_dir_profile().child(MODELS_DIR);
+            let target_dir = package_dir.child(MODELS_DIR);
 
             self.add_artifact(
                 model.qualified_path.to_string(),
@@ -187,7 +323,7 @@ impl<'w> ArtifactManager<'w> {
         }
 
         for event in self.dojo_annotation.events.clone() {
-            let target_dir = workspace.target_dir_profile().child(EVENTS_DIR);
+            let target_dir = package_dir.child(EVENTS_DIR);
 
             self.add_artifact(
                 event.qualified_path.to_string(),
@@ -196,7 +332,7 @@ impl<'w> ArtifactManager<'w> {
         }
 
         for sn_contract in self.dojo_annotation.sn_contracts.clone() {
-            let target_dir = workspace.target_dir_profile();
+            let target_dir = package_dir.child(STARKNET_CONTRACTS_DIR);
 
             self.add_artifact(
                 sn_contract.qualified_path.to_string(),
@@ -208,6 +344,12 @@ impl<'w> ArtifactManager<'w> {
             );
         }
 
+        for size in SizeReport::read(workspace, &self.package)?.contracts {
+            if let Some(artifact) = self.compiled_artifacts.get_mut(&size.qualified_path) {
+                artifact.size = Some(size);
+            }
+        }
+
         Ok(())
     }
 
@@ -231,6 +373,17 @@ impl<'w> ArtifactManager<'w> {
             .get_artifact(qualified_path)
             .context(format!("Artifact file for `{}` not found.", qualified_path))?;
 
+        // A reused artifact is written again if any of its files was removed.
+        if self.reused_artifacts.contains(qualified_path)
+            && artifact
+                .file_names(file_name)
+                .iter()
+                .all(|name| target_dir.child(name).exists())
+        {
+            trace!(qualified_path, "Sierra class unchanged, skipping write.");
+            return Ok(());
+        }
+
         let mut file = target_dir.create_rw(
             format!("{file_name}.json"),
             &format!("sierra class file for `{}`", qualified_path),
@@ -240,6 +393,13 @@ impl<'w> ArtifactManager<'w> {
         serde_json::to_writer_pretty(file.deref_mut(), &*artifact.contract_class)
             .with_context(|| format!("failed to serialize sierra class file: {qualified_path}"))?;
 
+        self.emit_written(
+            "sierra",
+            target_dir,
+            &format!("{file_name}.json"),
+            qualified_path,
+        );
+
         if let Some(debug_info) = &artifact.debug_info {
             let mut file = target_dir.create_rw(
                 format!("{file_name}.debug.json"),
@@ -250,13 +410,51 @@ impl<'w> ArtifactManager<'w> {
             serde_json::to_writer_pretty(file.deref_mut(), &**debug_info).with_context(|| {
                 format!("failed to serialize sierra debug info: {qualified_path}")
             })?;
+
+            self.emit_written(
+                "debug",
+                target_dir,
+                &format!("{file_name}.debug.json"),
+                qualified_path,
+            );
+        }
+
+        if let Some(casm_class) = &artifact.casm_class {
+            let mut file = target_dir.create_rw(
+                format!("{file_name}.{CASM_FILE_SUFFIX}.json"),
+                &format!("casm class file for `{}`", qualified_path),
+                self.workspace.config(),
+            )?;
+
+            serde_json::to_writer_pretty(file.deref_mut(), &**casm_class).with_context(|| {
+                format!("failed to serialize casm class file: {qualified_path}")
+            })?;
+
+            self.emit_written(
+                "casm",
+                target_dir,
+                &format!("{file_name}.{CASM_FILE_SUFFIX}.json"),
+                qualified_path,
+            );
         }
 
         Ok(())
     }
 
+    /// Emits an artifact file written to the filesystem.
+    fn emit_written(&self, kind: &str, target_dir: &Filesystem, file: &str, qualified_path: &str) {
+        messages::emit_artifact(
+            &self.workspace.config().ui(),
+            ArtifactMessage {
+                kind: kind.to_string(),
+                path: target_dir.path_unchecked().join(file).to_string(),
+                qualified_path: qualified_path.to_string(),
+            },
+        );
+    }
+
     /// Reads a Sierra contract class from a JSON file.
-    /// If debug info is available, it will also be read from a separate file.
+    /// If debug info or CASM class are available, they will also be read from separate files.
     ///
     /// # Arguments
     ///
@@ -303,12 +501,398 @@ impl<'w> ArtifactManager<'w> {
             None
         };
 
+        // CASM class may or may not be present.
+        let casm_file_name = format!("{file_name}.{CASM_FILE_SUFFIX}.json");
+        let casm_class: Option<CasmContractClass> = if target_dir.child(&casm_file_name).exists() {
+            trace!(target_dir = ?target_dir, qualified_path, file_name, "Reading casm class.");
+
+            let mut file = target_dir.open_ro(
+                casm_file_name,
+                &format!("casm class file for `{}`", qualified_path),
+                self.workspace.config(),
+            )?;
+
+            Some(serde_json::from_reader(file.deref_mut())?)
+        } else {
+            None
+        };
+
         let compiled_artifact = CompiledArtifact {
             class_hash,
             contract_class: Rc::new(contract_class),
             debug_info,
+            compiled_class_hash: casm_class.as_ref().map(compute_compiled_class_hash),
+            casm_class: casm_class.map(Rc::new),
+            size: None,
         };
 
         Ok(compiled_artifact)
     }
 }
+
+/// Removes the artifact files of the output directories of the resources which don't belong
+/// to any resource of the annotation, like the files of a renamed model or of a contract
+/// moved to another namespace.
+///
+/// # Arguments
+///
+/// * `target_dir` - The dojo target directory of the package.
+/// * `annotation` - The annotation of the current resources.
+///
+/// # Returns
+///
+/// The paths of the removed files, sorted.
+pub fn prune_stale_artifacts(
+    target_dir: &Filesystem,
+    annotation: &DojoAnnotation,
+) -> Result<Vec<Utf8PathBuf>> {
+    let resource_dirs = [
+        (
+            CONTRACTS_DIR,
+            annotation
+                .contracts
+                .iter()
+                .map(|c| c.filename())
+                .collect::<HashSet<_>>(),
+        ),
+        (
+            MODELS_DIR,
+            annotation.models.iter().map(|m| m.filename()).collect(),
+        ),
+        (
+            EVENTS_DIR,
+            annotation.events.iter().map(|e| e.filename()).collect(),
+        ),
+        (
+            STARKNET_CONTRACTS_DIR,
+            annotation
+                .sn_contracts
+                .iter()
+                .map(|c| c.filename())
+                .collect(),
+        ),
+    ];
+
+    let mut removed = vec![];
+
+    for (dir, filenames) in resource_dirs {
+        let dir = target_dir.child(dir).path_unchecked().to_path_buf();
+
+        removed.extend(remove_artifacts(&dir, |file_name| {
+            is_stale_artifact(file_name, &filenames)
+        })?);
+    }
+
+    removed.sort();
+    Ok(removed)
+}
+
+/// Removes the starknet contract artifacts left at the root of the profile directory by the
+/// builds preceding the [`STARKNET_CONTRACTS_DIR`] directory.
+///
+/// Those files are named from the qualified path of the contract, with the path separators
+/// replaced by underscores, hence start with the name of the crate declaring the contract.
+/// Only the artifacts of a Sierra class file are removed, to keep the CASM written by the
+/// `starknet-contract` target of Scarb in the same directory.
+///
+/// # Arguments
+///
+/// * `target_dir` - The profile directory, where the legacy builds wrote the artifacts.
+/// * `crate_names` - The names of the crates the starknet contracts may be declared in.
+///
+/// # Returns
+///
+/// The paths of the removed files, sorted.
+pub fn prune_legacy_starknet_contracts(
+    target_dir: &Filesystem,
+    crate_names: &[String],
+) -> Result<Vec<Utf8PathBuf>> {
+    if !target_dir.exists() {
+        return Ok(vec![]);
+    }
+
+    let contracts = legacy_starknet_contracts(&target_dir.list_files()?, crate_names);
+
+    let dir = target_dir.path_unchecked().to_path_buf();
+    let mut removed = remove_artifacts(&dir, |file_name| {
+        artifact_stem(file_name).is_some_and(|stem| contracts.contains(stem))
+    })?;
+
+    removed.sort();
+    Ok(removed)
+}
+
+/// Removes the dojo artifacts left in the profile directory by the builds preceding the
+/// package subdirectories of the dojo target directory: the resource directories, the world
+/// class and the annotations.
+///
+/// An entry named after a package of the workspace is the target directory of this package,
+/// hence is kept. Only the artifact files are removed from the resource directories, a
+/// directory being removed once empty.
+///
+/// # Arguments
+///
+/// * `profile_dir` - The profile directory, where the legacy builds wrote the artifacts.
+/// * `package_names` - The names of the packages of the workspace.
+///
+/// # Returns
+///
+/// The paths of the removed files, sorted.
+pub fn prune_legacy_dojo_layout(
+    profile_dir: &Utf8Path,
+    package_names: &[String],
+) -> Result<Vec<Utf8PathBuf>> {
+    let mut removed = vec![];
+
+    for dir in [
+        CONTRACTS_DIR,
+        MODELS_DIR,
+        EVENTS_DIR,
+        STARKNET_CONTRACTS_DIR,
+    ] {
+        if package_names.iter().any(|name| name == dir) {
+            continue;
+        }
+
+        let dir = profile_dir.join(dir);
+        removed.extend(remove_artifacts(&dir, |file_name| {
+            artifact_stem(file_name).is_some()
+        })?);
+
+        // Fails if the directory doesn't exist or still has other files.
+        let _ = fs::remove_dir(&dir);
+    }
+
+    let annotation_file = format!("{DOJO_ANNOTATION_FILE_NAME}.toml");
+
+    removed.extend(remove_artifacts(profile_dir, |file_name| {
+        file_name == annotation_file || artifact_stem(file_name) == Some(WORLD_CONTRACT_TAG)
+    })?);
+
+    removed.sort();
+    Ok(removed)
+}
+
+/// Removes the files of a directory matching the given predicate on their name.
+fn remove_artifacts(dir: &Utf8Path, is_stale: impl Fn(&str) -> bool) -> Result<Vec<Utf8PathBuf>> {
+    let mut removed = vec![];
+
+    if !dir.exists() {
+        return Ok(removed);
+    }
+
+    let entries = fs::read_dir(dir)
+        .with_context(|| format!("Failed to read artifacts directory `{dir}`."))?;
+
+    for entry in entries {
+        let entry = entry?;
+
+        if !entry.file_type()?.is_file() {
+            continue;
+        }
+
+        let Some(file_name) = entry.file_name().to_str().map(str::to_string) else {
+            continue;
+        };
+
+        if is_stale(&file_name) {
+            let path = dir.join(&file_name);
+
+            trace!(%path, "Removing stale artifact.");
+            fs::remove_file(&path)
+                .with_context(|| format!("Failed to remove stale artifact `{path}`."))?;
+
+            removed.push(path);
+        }
+    }
+
+    Ok(removed)
+}
+
+/// Returns the name of the resource of an artifact file, without its extensions.
+///
+/// # Arguments
+///
+/// * `file_name` - The name of the file, like `ns-actions.debug.json`.
+fn artifact_stem(file_name: &str) -> Option<&str> {
+    let stem = file_name.strip_suffix(".json")?;
+
+    let casm_suffix = format!(".{CASM_FILE_SUFFIX}");
+    let stem = stem
+        .strip_suffix(".debug")
+        .or_else(|| stem.strip_suffix(casm_suffix.as_str()))
+        .unwrap_or(stem);
+
+    Some(stem)
+}
+
+/// Checks if a file of an output directory is an artifact of none of the given resources.
+///
+/// # Arguments
+///
+/// * `file_name` - The name of the file, like `ns-actions.debug.json`.
+/// * `filenames` - The filenames of the resources, without extension.
+fn is_stale_artifact(file_name: &str, filenames: &HashSet<String>) -> bool {
+    artifact_stem(file_name).is_some_and(|stem| !filenames.contains(stem))
+}
+
+/// Returns the filenames of the starknet contracts written by a legacy build, given by their
+/// Sierra class file, like `my_crate_erc20_ERC20.json`.
+///
+/// The files of Scarb (like `my_crate_ERC20.contract_class.json` or
+/// `my_crate_unittest.test.json`) have several extensions, hence are not matched.
+///
+/// # Arguments
+///
+/// * `file_names` - The names of the files of the directory.
+/// * `crate_names` - The names of the crates the starknet contracts may be declared in.
+fn legacy_starknet_contracts(file_names: &[String], crate_names: &[String]) -> HashSet<String> {
+    file_names
+        .iter()
+        .filter_map(|file_name| file_name.strip_suffix(".json"))
+        .filter(|stem| !stem.contains('.'))
+        .filter(|stem| {
+            crate_names.iter().any(|crate_name| {
+                stem.strip_prefix(crate_name.as_str())
+                    .and_then(|rest| rest.strip_prefix('_'))
+                    .is_some_and(|rest| !rest.is_empty())
+            })
+        })
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_artifact_outputs() {
+        let contract_class: ContractClass =
+            serde_json::from_str(include_str!("test_data/contract_class.json")).unwrap();
+
+        let mut artifact = CompiledArtifact {
+            class_hash: Felt::ZERO,
+            contract_class: Rc::new(contract_class),
+            debug_info: None,
+            compiled_class_hash: None,
+            casm_class: None,
+            size: None,
+        };
+
+        assert!(artifact.has_outputs(false, false));
+        assert!(!artifact.has_outputs(true, false));
+        assert!(!artifact.has_outputs(false, true));
+        assert_eq!(artifact.file_names("ns-actions"), vec!["ns-actions.json"]);
+
+        artifact.debug_info = Some(Rc::new(SierraToCairoDebugInfo {
+            sierra_statements_to_cairo_info: HashMap::new(),
+        }));
+
+        assert!(artifact.has_outputs(true, false));
+        assert_eq!(
+            artifact.file_names("ns-actions"),
+            vec!["ns-actions.json", "ns-actions.debug.json"]
+        );
+    }
+
+    #[test]
+    fn test_is_stale_artifact() {
+        let filenames = HashSet::from(["ns-Position".to_string(), "ns-actions".to_string()]);
+
+        assert!(!is_stale_artifact("ns-Position.json", &filenames));
+        assert!(!is_stale_artifact("ns-actions.debug.json", &filenames));
+        assert!(!is_stale_artifact(
+            "ns-actions.compiled_contract_class.json",
+            &filenames
+        ));
+
+        assert!(is_stale_artifact("ns-Pos.json", &filenames));
+        assert!(is_stale_artifact("old_ns-actions.debug.json", &filenames));
+        assert!(is_stale_artifact(
+            "ns-Moves.compiled_contract_class.json",
+            &filenames
+        ));
+
+        assert!(!is_stale_artifact("README.md", &filenames));
+    }
+
+    #[test]
+    fn test_legacy_starknet_contracts() {
+        let crate_names = vec!["my_game".to_string(), "dojo".to_string()];
+        let file_names = [
+            "my_game_erc20_ERC20.json",
+            "my_game_erc20_ERC20.debug.json",
+            "dojo_utils_Token.json",
+            "world.json",
+            "manifest.json",
+            "my_game_.json",
+            "other_erc20_ERC20.json",
+            "my_game_erc20_ERC20.toml",
+            "my_game_Token.contract_class.json",
+            "my_game_Token.compiled_contract_class.json",
+            "my_game_unittest.test.json",
+        ]
+        .map(str::to_string);
+
+        assert_eq!(
+            legacy_starknet_contracts(&file_names, &crate_names),
+            HashSet::from([
+                "my_game_erc20_ERC20".to_string(),
+                "dojo_utils_Token".to_string()
+            ])
+        );
+    }
+
+    #[test]
+    fn test_prune_legacy_dojo_layout() {
+        let temp_dir = assert_fs::TempDir::new().unwrap();
+        let profile_dir = Utf8Path::from_path(temp_dir.path()).unwrap();
+
+        let files = [
+            "contracts/ns-actions.json",
+            "contracts/ns-actions.debug.json",
+            "contracts/README.md",
+            "models/ns-Position.json",
+            "events/ns-Moved.json",
+            "dojo-world.json",
+            "dojo-world.compiled_contract_class.json",
+            "annotations.toml",
+            "my_game.starknet_artifacts.json",
+            "my_game/annotations.toml",
+            "my_game/contracts/ns-actions.json",
+        ];
+
+        for file in files {
+            let path = profile_dir.join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, "").unwrap();
+        }
+
+        // A package named `events` has its artifacts in the `events` directory.
+        let package_names = ["my_game".to_string(), "events".to_string()];
+        let removed = prune_legacy_dojo_layout(profile_dir, &package_names).unwrap();
+
+        assert_eq!(
+            removed,
+            [
+                "annotations.toml",
+                "contracts/ns-actions.debug.json",
+                "contracts/ns-actions.json",
+                "dojo-world.compiled_contract_class.json",
+                "dojo-world.json",
+                "models/ns-Position.json",
+            ]
+            .map(|file| profile_dir.join(file))
+        );
+
+        assert!(profile_dir.join("contracts/README.md").exists());
+        assert!(!profile_dir.join("models").exists());
+        assert!(profile_dir.join("events/ns-Moved.json").exists());
+        assert!(profile_dir.join("my_game.starknet_artifacts.json").exists());
+        assert!(profile_dir.join("my_game/annotations.toml").exists());
+        assert!(profile_dir
+            .join("my_game/contracts/ns-actions.json")
+            .exists());
+    }
+}
@@ -0,0 +1,218 @@
+//! Symbolization of Sierra statements.
+//!
+//! The debug info written alongside the Sierra classes (`<name>.debug.json`) maps each
+//! Sierra statement to the Cairo locations it was generated from. The locations follow
+//! the code generated by the Dojo plugin back to the code of the user, and a statement
+//! of an inlined function has one location per inlined call, the innermost first.
+//!
+//! This module turns the statement indices of a failure (a panic in a test, a trace,
+//! a revert...) into a backtrace of Cairo functions, files and lines.
+
+use std::fmt;
+use std::fs::File;
+use std::io::BufReader;
+
+use anyhow::{Context, Result};
+use camino::Utf8Path;
+
+use super::scarb_internal::debug::{Location, SierraToCairoDebugInfo};
+
+/// Name displayed for the frames without function.
+const UNKNOWN_FUNCTION: &str = "<unknown>";
+
+/// A frame of a symbolized backtrace.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    /// The Sierra statement index of the frame.
+    pub statement_idx: usize,
+    /// The Cairo path of the function, if known.
+    pub function: Option<String>,
+    /// The file of the frame, relative to the project root. `None` if the statement
+    /// has no location in the user code.
+    pub file_path: Option<String>,
+    /// The line of the frame, 1 based.
+    pub line: Option<usize>,
+    /// The column of the frame, 1 based.
+    pub col: Option<usize>,
+}
+
+impl Frame {
+    fn from_location(statement_idx: usize, location: &Location) -> Self {
+        Self {
+            statement_idx,
+            function: location.function.clone(),
+            file_path: Some(location.file_path.clone()),
+            line: Some(location.start.line + 1),
+            col: Some(location.start.col + 1),
+        }
+    }
+
+    fn unknown(statement_idx: usize) -> Self {
+        Self {
+            statement_idx,
+            function: None,
+            file_path: None,
+            line: None,
+            col: None,
+        }
+    }
+}
+
+impl fmt::Display for Frame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "[{}] {}",
+            self.statement_idx,
+            self.function.as_deref().unwrap_or(UNKNOWN_FUNCTION)
+        )?;
+
+        match (&self.file_path, self.line, self.col) {
+            (Some(file_path), Some(line), Some(col)) => {
+                write!(f, "\n    at {file_path}:{line}:{col}")
+            }
+            _ => write!(f, "\n    at <no source location>"),
+        }
+    }
+}
+
+/// A symbolized backtrace, the innermost frame first.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Backtrace {
+    pub frames: Vec<Frame>,
+}
+
+impl fmt::Display for Backtrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, frame) in self.frames.iter().enumerate() {
+            writeln!(f, "{i:>4}: {frame}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Reads the debug info of a Sierra class from its `.debug.json` file.
+pub fn read_debug_info(path: &Utf8Path) -> Result<SierraToCairoDebugInfo> {
+    let file =
+        File::open(path).with_context(|| format!("Failed to open debug info file `{path}`."))?;
+
+    serde_json::from_reader(BufReader::new(file))
+        .with_context(|| format!("Failed to parse debug info file `{path}`."))
+}
+
+/// Parses a list of Sierra statement indices, separated by commas or whitespaces.
+///
+/// # Arguments
+///
+/// * `input` - The statement indices, like `12, 45 78`.
+pub fn parse_statement_indices(input: &str) -> Result<Vec<usize>> {
+    input
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|s| !s.is_empty())
+        .map(|s| {
+            s.parse::<usize>()
+                .with_context(|| format!("Invalid Sierra statement index `{s}`."))
+        })
+        .collect()
+}
+
+/// Symbolizes the given Sierra statements.
+///
+/// Each statement gives one frame per Cairo location, the inlined functions of the
+/// statement being expanded. A statement without location gives an unknown frame.
+///
+/// # Arguments
+///
+/// * `debug_info` - The debug info of the Sierra class the statements belong to.
+/// * `statement_indices` - The statement indices, the innermost first.
+pub fn symbolize(debug_info: &SierraToCairoDebugInfo, statement_indices: &[usize]) -> Backtrace {
+    let frames = statement_indices
+        .iter()
+        .flat_map(|idx| {
+            let locations = debug_info
+                .sierra_statements_to_cairo_info
+                .get(idx)
+                .map(|info| info.cairo_locations.as_slice())
+                .unwrap_or_default();
+
+            if locations.is_empty() {
+                vec![Frame::unknown(*idx)]
+            } else {
+                locations
+                    .iter()
+                    .map(|location| Frame::from_location(*idx, location))
+                    .collect()
+            }
+        })
+        .collect();
+
+    Backtrace { frames }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::compiler::scarb_internal::debug::{SierraStatementToCairoDebugInfo, TextPosition};
+
+    fn location(function: &str, file_path: &str, line: usize) -> Location {
+        Location {
+            start: TextPosition { line, col: 4 },
+            end: TextPosition { line, col: 10 },
+            file_path: file_path.to_string(),
+            function: Some(function.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_parse_statement_indices() {
+        assert_eq!(
+            parse_statement_indices("12, 45 78\n3").unwrap(),
+            vec![12, 45, 78, 3]
+        );
+        assert!(parse_statement_indices("").unwrap().is_empty());
+        assert!(parse_statement_indices("12,abc").is_err());
+    }
+
+    #[test]
+    fn test_symbolize() {
+        let debug_info = SierraToCairoDebugInfo {
+            sierra_statements_to_cairo_info: HashMap::from([
+                (
+                    10,
+                    SierraStatementToCairoDebugInfo {
+                        cairo_locations: vec![
+                            location("pkg::utils::helper", "src/utils.cairo", 2),
+                            location("pkg::actions::actions::spawn", "src/actions.cairo", 20),
+                        ],
+                    },
+                ),
+                (
+                    20,
+                    SierraStatementToCairoDebugInfo {
+                        cairo_locations: vec![],
+                    },
+                ),
+            ]),
+        };
+
+        let backtrace = symbolize(&debug_info, &[10, 20, 30]);
+
+        assert_eq!(backtrace.frames.len(), 4);
+        assert_eq!(
+            backtrace.frames[0].function.as_deref(),
+            Some("pkg::utils::helper")
+        );
+        assert_eq!(backtrace.frames[1].line, Some(21));
+        assert_eq!(backtrace.frames[1].col, Some(5));
+        assert_eq!(backtrace.frames[2], Frame::unknown(20));
+        assert_eq!(backtrace.frames[3], Frame::unknown(30));
+
+        assert_eq!(
+            backtrace.frames[1].to_string(),
+            "[10] pkg::actions::actions::spawn\n    at src/actions.cairo:21:5"
+        );
+    }
+}
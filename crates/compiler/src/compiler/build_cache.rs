@@ -0,0 +1,383 @@
+//! Build cache for the Dojo compiler.
+//!
+//! Compiling all the contracts of a large world is slow, when most of the time only
+//! a few of them are affected by a change. The build cache records a fingerprint
+//! of the inputs of each compiled contract, and the artifacts of the previous build
+//! are reused for the contracts whose fingerprint didn't change.
+//!
+//! The fingerprint of a contract is computed from:
+//! - the compiler version and the options affecting the compiled classes, including the
+//!   Cairo compiler configuration of the compilation unit,
+//! - the items of the contract module (after the plugins expansion),
+//! - the items of every module that is not a contract module, and the public items of the
+//!   contract modules, since the contract may depend on any of them.
+
+use std::collections::{BTreeMap, HashMap};
+use std::hash::{Hash, Hasher};
+use std::io::{Read, Write};
+
+use anyhow::Result;
+use cairo_lang_compiler::db::RootDatabase;
+use cairo_lang_defs::db::DefsGroup;
+use cairo_lang_defs::ids::{LanguageElementId, ModuleId, ModuleItemId};
+use cairo_lang_filesystem::ids::CrateId;
+use cairo_lang_starknet::contract::ContractDeclaration;
+use cairo_lang_syntax::node::db::SyntaxGroup;
+use cairo_lang_syntax::node::kind::SyntaxKind;
+use cairo_lang_utils::Upcast;
+use scarb::core::{Package, Workspace};
+use scarb_stable_hash::StableHasher;
+use serde::{Deserialize, Serialize};
+use tracing::trace;
+
+use crate::scarb_extensions::WorkspaceExt;
+use crate::CAIRO_PATH_SEPARATOR;
+
+use super::version::generate_version;
+
+const BUILD_CACHE_FILE_NAME: &str = "build_cache";
+
+/// Fingerprints of the contracts compiled during the last build, by qualified path.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct BuildCache {
+    pub fingerprints: BTreeMap<String, String>,
+}
+
+impl BuildCache {
+    /// Creates a new build cache from the given fingerprints.
+    pub fn new(fingerprints: HashMap<String, String>) -> Self {
+        Self {
+            fingerprints: fingerprints.into_iter().collect(),
+        }
+    }
+
+    /// Checks if the contract with the given qualified path was compiled
+    /// with the same inputs during the last build.
+    pub fn is_fresh(&self, qualified_path: &str, fingerprint: &str) -> bool {
+        self.fingerprints
+            .get(qualified_path)
+            .is_some_and(|f| f == fingerprint)
+    }
+
+    /// Reads the build cache from the target directory of the provided dojo package,
+    /// for the current profile. Returns an empty cache if no build cache is found.
+    ///
+    /// # Arguments
+    ///
+    /// * `workspace` - The workspace to read the build cache from.
+    /// * `package` - The dojo package of the artifacts.
+    pub fn read(workspace: &Workspace<'_>, package: &Package) -> Result<Self> {
+        let target_dir = workspace.dojo_target_dir(package);
+        let file_name = format!("{}.toml", BUILD_CACHE_FILE_NAME);
+
+        if !target_dir.child(&file_name).exists() {
+            return Ok(Self::default());
+        }
+
+        let mut file = target_dir.open_ro(file_name, "Dojo build cache", workspace.config())?;
+
+        let mut content = String::new();
+        file.read_to_string(&mut content)?;
+
+        // An unreadable cache is not an error, it only leads to a full rebuild.
+        Ok(toml::from_str(&content).unwrap_or_default())
+    }
+
+    /// Writes the build cache to the target directory of the provided dojo package,
+    /// for the current profile.
+    ///
+    /// # Arguments
+    ///
+    /// * `workspace` - The workspace to write the build cache to.
+    /// * `package` - The dojo package of the artifacts.
+    pub fn write(&self, workspace: &Workspace<'_>, package: &Package) -> Result<()> {
+        let target_dir = workspace.dojo_target_dir(package);
+        let content = toml::to_string(&self)?;
+
+        let mut file = target_dir.create_rw(
+            format!("{}.toml", BUILD_CACHE_FILE_NAME),
+            "Dojo build cache",
+            workspace.config(),
+        )?;
+
+        file.write_all(content.as_bytes())?;
+
+        Ok(())
+    }
+}
+
+/// The code of a module, as hashed by the fingerprints.
+#[derive(Debug, Clone)]
+struct ModuleCode {
+    path: String,
+    items: Vec<ItemCode>,
+}
+
+/// The code of a module item, as hashed by the fingerprints.
+#[derive(Debug, Clone)]
+struct ItemCode {
+    text: String,
+    is_public: bool,
+    /// The path of the module declared by the item, if it's a submodule.
+    submodule_path: Option<String>,
+}
+
+/// Computes the fingerprint of each contract, by qualified path.
+///
+/// # Arguments
+///
+/// * `db` - The database containing the contracts.
+/// * `crate_ids` - The crates of the compilation unit.
+/// * `contracts` - The contracts to fingerprint.
+/// * `options` - The compilation options affecting the compiled artifacts.
+pub fn fingerprint_contracts(
+    db: &RootDatabase,
+    crate_ids: &[CrateId],
+    contracts: &[ContractDeclaration],
+    options: impl Hash,
+) -> HashMap<String, String> {
+    let contract_paths = contracts
+        .iter()
+        .map(|decl| decl.module_id().full_path(db))
+        .collect::<Vec<_>>();
+
+    let modules = crate_ids
+        .iter()
+        .flat_map(|crate_id| {
+            db.crate_modules(*crate_id)
+                .iter()
+                .copied()
+                .collect::<Vec<_>>()
+        })
+        .map(|module_id| module_code(db, module_id))
+        .collect::<Vec<_>>();
+
+    fingerprint_modules(&modules, &contract_paths, options)
+}
+
+/// Computes the fingerprint of each contract from the code of the modules.
+fn fingerprint_modules(
+    modules: &[ModuleCode],
+    contract_paths: &[String],
+    options: impl Hash,
+) -> HashMap<String, String> {
+    let contract_of = |module_path: &str| {
+        contract_paths
+            .iter()
+            .find(|contract_path| is_in_contract_module(module_path, contract_path))
+    };
+
+    // Items shared by all the contracts, hashed once.
+    let mut shared_hasher = StableHasher::new();
+    generate_version().hash(&mut shared_hasher);
+    options.hash(&mut shared_hasher);
+
+    let mut contract_modules: HashMap<&str, Vec<&ModuleCode>> = HashMap::new();
+
+    for module in modules {
+        let contract_path = contract_of(&module.path);
+
+        match contract_path {
+            Some(contract_path) => contract_modules
+                .entry(contract_path.as_str())
+                .or_default()
+                .push(module),
+            None => module.path.hash(&mut shared_hasher),
+        }
+
+        for item in &module.items {
+            // The contracts submodules are hashed with their contract.
+            if item
+                .submodule_path
+                .as_ref()
+                .is_some_and(|path| contract_of(path).is_some())
+            {
+                continue;
+            }
+
+            // Only the public items of a contract module may be used by the other contracts.
+            if contract_path.is_none() || item.is_public {
+                item.text.hash(&mut shared_hasher);
+            }
+        }
+    }
+
+    let shared_fingerprint = shared_hasher.finish();
+
+    contract_paths
+        .iter()
+        .map(|contract_path| {
+            let mut hasher = StableHasher::new();
+            shared_fingerprint.hash(&mut hasher);
+            contract_path.hash(&mut hasher);
+
+            for module in contract_modules
+                .get(contract_path.as_str())
+                .map(|m| m.as_slice())
+                .unwrap_or_default()
+            {
+                module.path.hash(&mut hasher);
+
+                for item in &module.items {
+                    item.text.hash(&mut hasher);
+                }
+            }
+
+            let fingerprint = hasher.finish_as_short_hash();
+            trace!(contract_path, fingerprint, "Contract fingerprint.");
+
+            (contract_path.clone(), fingerprint)
+        })
+        .collect()
+}
+
+/// Checks if the module path is the contract module or one of its submodules.
+fn is_in_contract_module(module_path: &str, contract_path: &str) -> bool {
+    module_path == contract_path
+        || module_path.starts_with(&format!("{contract_path}{CAIRO_PATH_SEPARATOR}"))
+}
+
+/// Returns the code of a module. A module which can't be resolved has no items.
+fn module_code(db: &RootDatabase, module_id: ModuleId) -> ModuleCode {
+    let syntax_db: &dyn SyntaxGroup = db.upcast();
+
+    let items = db
+        .module_items(module_id)
+        .map(|items| items.to_vec())
+        .unwrap_or_default()
+        .into_iter()
+        .map(|item_id| {
+            let node = item_id.untyped_stable_ptr(db).lookup(syntax_db);
+
+            ItemCode {
+                text: node.get_text(syntax_db),
+                is_public: node
+                    .children(syntax_db)
+                    .any(|child| child.kind(syntax_db) == SyntaxKind::VisibilityPub),
+                submodule_path: match item_id {
+                    ModuleItemId::Submodule(submodule_id) => {
+                        Some(ModuleId::Submodule(submodule_id).full_path(db))
+                    }
+                    _ => None,
+                },
+            }
+        })
+        .collect();
+
+    ModuleCode {
+        path: module_id.full_path(db),
+        items,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(text: &str) -> ItemCode {
+        ItemCode {
+            text: text.to_string(),
+            is_public: text.starts_with("pub "),
+            submodule_path: None,
+        }
+    }
+
+    fn submodule(parent: &str, name: &str) -> ItemCode {
+        ItemCode {
+            text: format!("mod {name} {{}}"),
+            is_public: false,
+            submodule_path: Some(format!("{parent}::{name}")),
+        }
+    }
+
+    /// A package with two contracts `c1` and `c2`, and a `utils` module.
+    fn modules(c1_item: &str, c2_item: &str, utils_item: &str) -> Vec<ModuleCode> {
+        vec![
+            ModuleCode {
+                path: "pkg".to_string(),
+                items: vec![
+                    submodule("pkg", "c1"),
+                    submodule("pkg", "c2"),
+                    submodule("pkg", "utils"),
+                ],
+            },
+            ModuleCode {
+                path: "pkg::c1".to_string(),
+                items: vec![item(c1_item)],
+            },
+            ModuleCode {
+                path: "pkg::c2".to_string(),
+                items: vec![item(c2_item)],
+            },
+            ModuleCode {
+                path: "pkg::utils".to_string(),
+                items: vec![item(utils_item)],
+            },
+        ]
+    }
+
+    fn fingerprints(modules: &[ModuleCode], options: impl Hash) -> HashMap<String, String> {
+        fingerprint_modules(
+            modules,
+            &["pkg::c1".to_string(), "pkg::c2".to_string()],
+            options,
+        )
+    }
+
+    fn cache(fingerprints: &HashMap<String, String>) -> BuildCache {
+        BuildCache::new(fingerprints.clone())
+    }
+
+    fn is_fresh(cache: &BuildCache, fingerprints: &HashMap<String, String>, path: &str) -> bool {
+        cache.is_fresh(path, &fingerprints[path])
+    }
+
+    #[test]
+    fn test_cache_hit() {
+        let base = fingerprints(&modules("fn a() {}", "fn b() {}", "fn u() {}"), "opts");
+        let cache = cache(&base);
+
+        let same = fingerprints(&modules("fn a() {}", "fn b() {}", "fn u() {}"), "opts");
+        assert!(is_fresh(&cache, &same, "pkg::c1"));
+        assert!(is_fresh(&cache, &same, "pkg::c2"));
+
+        // A private change in a contract only affects this contract.
+        let changed = fingerprints(&modules("fn a() { 1; }", "fn b() {}", "fn u() {}"), "opts");
+        assert!(!is_fresh(&cache, &changed, "pkg::c1"));
+        assert!(is_fresh(&cache, &changed, "pkg::c2"));
+    }
+
+    #[test]
+    fn test_cache_miss() {
+        let base = fingerprints(&modules("fn a() {}", "fn b() {}", "fn u() {}"), "opts");
+        let cache = cache(&base);
+
+        // A change in a shared module affects all the contracts.
+        let changed = fingerprints(&modules("fn a() {}", "fn b() {}", "fn u() { 1; }"), "opts");
+        assert!(!is_fresh(&cache, &changed, "pkg::c1"));
+        assert!(!is_fresh(&cache, &changed, "pkg::c2"));
+
+        // A public item of a contract may be used by the other contract.
+        let changed = fingerprints(&modules("pub fn a() {}", "fn b() {}", "fn u() {}"), "opts");
+        assert!(!is_fresh(&cache, &changed, "pkg::c1"));
+        assert!(!is_fresh(&cache, &changed, "pkg::c2"));
+
+        // The compiler configuration affects all the contracts.
+        let changed = fingerprints(
+            &modules("fn a() {}", "fn b() {}", "fn u() {}"),
+            "other opts",
+        );
+        assert!(!is_fresh(&cache, &changed, "pkg::c1"));
+        assert!(!is_fresh(&cache, &changed, "pkg::c2"));
+
+        // A contract not built before is never fresh.
+        assert!(!BuildCache::default().is_fresh("pkg::c1", &base["pkg::c1"]));
+    }
+
+    #[test]
+    fn test_is_in_contract_module() {
+        assert!(is_in_contract_module("pkg::c1", "pkg::c1"));
+        assert!(is_in_contract_module("pkg::c1::inner", "pkg::c1"));
+        assert!(!is_in_contract_module("pkg::c10", "pkg::c1"));
+    }
+}
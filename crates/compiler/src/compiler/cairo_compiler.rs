@@ -1,60 +1,104 @@
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::rc::Rc;
 
 use anyhow::{anyhow, Context, Result};
 use cairo_lang_compiler::db::RootDatabase;
 use cairo_lang_compiler::CompilerConfig;
-use cairo_lang_defs::ids::NamedLanguageElementId;
-use cairo_lang_filesystem::db::FilesGroup;
+use cairo_lang_defs::db::DefsGroup;
+use cairo_lang_defs::ids::{LanguageElementId, NamedLanguageElementId};
+use cairo_lang_filesystem::db::{FilesGroup, CORELIB_CRATE_NAME};
 use cairo_lang_filesystem::ids::{CrateId, CrateLongId};
-use cairo_lang_starknet::compile::compile_prepared_db;
+use cairo_lang_sierra_generator::program_generator::SierraProgramDebugInfo;
 use cairo_lang_starknet::contract::{find_contracts, ContractDeclaration};
-use cairo_lang_starknet_classes::allowed_libfuncs::{AllowedLibfuncsError, ListSelector};
-use cairo_lang_starknet_classes::contract_class::ContractClass;
+use cairo_lang_starknet_classes::casm_contract_class::CasmContractClass;
+use cairo_lang_starknet_classes::contract_class::{ContractClass, ContractEntryPoint};
 use cairo_lang_utils::UpcastMut;
+use convert_case::{Case, Casing};
 use itertools::{izip, Itertools};
+use rayon::prelude::*;
 use scarb::compiler::helpers::build_compiler_config;
-use scarb::compiler::{CairoCompilationUnit, CompilationUnitAttributes, Compiler};
+use scarb::compiler::{CairoCompilationUnit, CompilationUnit, CompilationUnitAttributes, Compiler};
 use scarb::core::{Config, Package, TargetKind, Workspace};
-use scarb::ops::CompileOpts;
+use scarb::ops::{CompileOpts, FeaturesOpts, FeaturesSelector};
 use scarb_ui::args::{FeaturesSpec, PackagesFilter};
 use scarb_ui::Ui;
 use serde::{Deserialize, Serialize};
 use smol_str::SmolStr;
-use starknet::core::types::contract::SierraClass;
+use starknet::core::types::contract::{
+    EntryPointsByType, SierraClass, SierraClassDebugInfo, SierraEntryPoint,
+};
 use starknet::core::types::Felt;
 use tracing::{trace, trace_span};
 
+use crate::aux_data::ModelAuxData;
+use crate::diagnostic_codes;
 use crate::scarb_extensions::{ProfileSpec, WorkspaceExt};
 use crate::WORLD_QUALIFIED_PATH;
 
-use super::artifact_manager::{ArtifactManager, CompiledArtifact};
-use super::contract_selector::ContractSelector;
+use super::annotation::DojoAnnotation;
+use super::artifact_manager::{
+    prune_legacy_dojo_layout, prune_legacy_starknet_contracts, prune_stale_artifacts,
+    ArtifactManager, CompiledArtifact,
+};
+use super::build_cache::{fingerprint_contracts, BuildCache};
+use super::config::{config_paths, find_dojo_packages, DojoConfigLoader};
+use super::contract_selector::{ContractSelector, SelectorMatches};
+use super::lock::{verify_locked, BuildLock};
+use super::messages::{self, DiagnosticMessage, StatusMessage};
+use super::permissions::check_writers;
+use super::profile_config::ProfileConfig;
 use super::scarb_internal;
+use super::scarb_internal::compile::compile_prepared_db_with_debug_info;
 use super::scarb_internal::debug::SierraToCairoDebugInfo;
+use super::size_report::ContractSize;
 use super::version::check_package_dojo_version;
 
 pub const DOJO_TARGET_NAME: &str = "dojo";
 
-#[derive(Debug, Default)]
-pub struct DojoCompiler {
+/// The options of the [`DojoCompiler`], set by the build command.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DojoCompilerOptions {
     /// Output the debug information of the compiled Sierra contracts.
     ///
-    /// Mainly used for the Walnut debugger integration. It is used
-    /// internally by Walnut to build the Dojo project with the Sierra
-    /// debug information. This flag has no use outside of that.
-    output_debug_info: bool,
+    /// Used by the Walnut debugger integration, and to symbolize the
+    /// Sierra statements of a failure with the [`super::backtrace`] module.
+    pub output_debug_info: bool,
+    /// Reuse the artifacts of the previous build for the contracts
+    /// whose inputs didn't change.
+    pub use_cache: bool,
+    /// Verify the class hashes against the lock file instead of updating it.
+    ///
+    /// Implies not using the cache, the verified class hashes being computed from freshly
+    /// compiled classes.
+    pub locked: bool,
+    /// Compile the CASM of the contracts to report their size, even if not output.
+    pub size_report: bool,
+}
+
+#[derive(Debug, Default)]
+pub struct DojoCompiler {
+    options: DojoCompilerOptions,
 }
 
 impl DojoCompiler {
-    pub fn new(output_debug_info: bool) -> Self {
-        Self { output_debug_info }
+    pub fn new(options: DojoCompilerOptions) -> Self {
+        Self { options }
     }
 
+    /// Compiles the workspace.
+    ///
+    /// If `use_cache` is false, the artifacts of the built dojo packages are cleaned
+    /// before compiling, forcing a full rebuild.
+    ///
+    /// If `allow_incompatible_dojo` is true, packages depending on a dojo version not supported
+    /// by the compiler are built anyway, with a warning.
     pub fn compile_workspace(
         config: &Config,
         packages_filter: Option<PackagesFilter>,
         features: FeaturesSpec,
+        use_cache: bool,
+        allow_incompatible_dojo: bool,
     ) -> Result<()> {
         let ws = scarb::ops::read_workspace(config.manifest_path(), config)?;
 
@@ -64,13 +108,27 @@ impl DojoCompiler {
             ws.members().collect()
         };
 
+        let resolve = scarb::ops::resolve_workspace(&ws)?;
+
         for p in &packages {
-            check_package_dojo_version(&ws, p)?;
+            check_package_dojo_version(&ws, p, &resolve, allow_incompatible_dojo)?;
         }
 
         ws.profile_check()?;
 
-        DojoCompiler::clean(config, ProfileSpec::WorkspaceCurrent)?;
+        if !use_cache {
+            // Only the artifacts of the dojo packages being built are removed, the other
+            // dojo packages of the workspace keep their artifacts.
+            let dojo_packages = find_dojo_packages(&ws);
+
+            for package in dojo_packages
+                .iter()
+                .filter(|d| packages.iter().any(|p| p.id == d.id))
+            {
+                // Ignore fails to remove the directory as it might not exist.
+                let _ = fs::remove_dir_all(ws.dojo_target_dir(package).to_string());
+            }
+        }
 
         trace!(?packages);
 
@@ -87,6 +145,13 @@ impl DojoCompiler {
 
         trace!(?compile_info, "Compiled workspace.");
 
+        if !compile_info.compile_error_units.is_empty() {
+            anyhow::bail!(
+                "Could not compile: {}.",
+                compile_info.compile_error_units.join(", ")
+            );
+        }
+
         Ok(())
     }
 
@@ -120,12 +185,158 @@ impl DojoCompiler {
 
         Ok(())
     }
+
+    /// Removes the stale artifacts of the dojo packages for the current profile, without
+    /// rebuilding them.
+    ///
+    /// The expected artifacts are given by the annotations of the current sources, computed
+    /// with the plugins and the semantic analysis only. The artifacts written at the root of
+    /// the profile directory by the legacy builds are removed too.
+    pub fn clean_stale(config: &Config) -> Result<()> {
+        let ws = scarb::ops::read_workspace(config.manifest_path(), config)?;
+
+        ws.profile_check()?;
+
+        // The legacy dojo artifacts are in the profile directory, even if no package has been
+        // built since.
+        let package_names = ws
+            .members()
+            .map(|p| p.id.name.to_string())
+            .collect::<Vec<_>>();
+
+        for path in
+            prune_legacy_dojo_layout(ws.target_dir_profile().path_unchecked(), &package_names)?
+        {
+            config.ui().print(
+                StatusMessage::new("removed", format!("Removed stale artifact `{path}`."))
+                    .with_path(&path),
+            );
+        }
+
+        let resolve = scarb::ops::resolve_workspace(&ws)?;
+        let features = FeaturesOpts {
+            features: FeaturesSelector::Features(vec![]),
+            no_default_features: false,
+        };
+
+        let units = scarb::ops::generate_compilation_units(&resolve, &features, &ws)?
+            .into_iter()
+            .filter_map(|unit| match unit {
+                CompilationUnit::Cairo(unit)
+                    if unit.main_component().target_kind() == TargetKind::new(DOJO_TARGET_NAME) =>
+                {
+                    Some(unit)
+                }
+                _ => None,
+            });
+
+        for mut unit in units {
+            let package = unit.main_component().package.clone();
+            let target_dir = ws.dojo_target_dir(&package);
+
+            if !target_dir.exists() {
+                trace!(package = ?package.id.name, "Package not built, nothing to clean.");
+                continue;
+            }
+
+            let (annotation, crate_names) =
+                current_dojo_annotation(&mut unit, &ws).with_context(|| {
+                    format!(
+                        "Failed to compute the annotations of package `{}`.",
+                        package.id.name
+                    )
+                })?;
+
+            let mut removed = prune_stale_artifacts(&target_dir, &annotation)?;
+            removed.extend(prune_legacy_starknet_contracts(
+                &ws.target_dir_profile(),
+                &crate_names,
+            )?);
+
+            for path in &removed {
+                config.ui().print(
+                    StatusMessage::new("removed", format!("Removed stale artifact `{path}`."))
+                        .with_path(path),
+                );
+            }
+
+            if removed.is_empty() {
+                config.ui().print(StatusMessage::new(
+                    "up_to_date",
+                    format!("No stale artifact for package `{}`.", package.id.name),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Computes the dojo annotation of the current sources of a compilation unit, without
+/// generating the code of the contracts.
+///
+/// # Returns
+///
+/// The annotation, and the names of the crates whose resources are annotated.
+fn current_dojo_annotation(
+    unit: &mut CairoCompilationUnit,
+    ws: &Workspace<'_>,
+) -> Result<(DojoAnnotation, Vec<String>)> {
+    let props: Props = unit.main_component().target_props()?;
+    props.verify()?;
+
+    let package = unit.main_component().package.clone();
+    let dojo_config: ProfileConfig = DojoConfigLoader::from_package(&package, ws)?;
+
+    let mut db = scarb_internal::build_dojo_root_database(unit, ws)?;
+
+    let main_crate_ids = collect_main_crate_ids(unit, &db, true);
+
+    // The external resources are annotated if their contract is selected for compilation.
+    let selected = find_project_contracts(
+        &mut db,
+        main_crate_ids.clone(),
+        props.build_external_contracts.clone(),
+        &ws.config().ui(),
+    )?
+    .iter()
+    .map(|decl| decl.module_id().full_path(&db))
+    .collect::<HashSet<_>>();
+
+    let external_crate_ids = props
+        .build_external_contracts
+        .as_ref()
+        .map(|selectors| collect_crates_ids_from_selectors(&db, selectors))
+        .unwrap_or_default();
+
+    let annotation = DojoAnnotation::from_crates(
+        &db,
+        &main_crate_ids,
+        &external_crate_ids,
+        &dojo_config.namespace,
+        |qualified_path| selected.contains(qualified_path),
+    )?;
+
+    let crate_names = main_crate_ids
+        .iter()
+        .chain(&external_crate_ids)
+        .map(|crate_id| crate_id.name(&db).to_string())
+        .unique()
+        .collect();
+
+    Ok((annotation, crate_names))
 }
 
 #[derive(Debug, Default, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct Props {
     pub build_external_contracts: Option<Vec<ContractSelector>>,
+    /// Output the CASM of the contracts along with their Sierra class.
+    #[serde(default)]
+    pub casm: bool,
+    /// Add the pythonic hints to the CASM output.
+    #[serde(default)]
+    pub casm_add_pythonic_hints: bool,
 }
 
 impl Props {
@@ -160,8 +371,24 @@ impl Compiler for DojoCompiler {
         let props: Props = unit.main_component().target_props()?;
         props.verify()?;
 
+        let package = &unit.main_component().package;
+
         let main_crate_ids = collect_main_crate_ids(&unit, db, true);
-        let compiler_config = build_compiler_config(db, &unit, &main_crate_ids, ws);
+        let mut compiler_config = build_compiler_config(db, &unit, &main_crate_ids, ws);
+
+        if messages::is_json(&ws.config().ui()) {
+            let ignore_warnings_crate_ids = db
+                .crates()
+                .into_iter()
+                .filter(|crate_id| !main_crate_ids.contains(crate_id))
+                .collect::<Vec<_>>();
+
+            compiler_config.diagnostics_reporter = messages::diagnostics_reporter(
+                ws.config().ui(),
+                &ignore_warnings_crate_ids,
+                unit.compiler_config.allow_warnings,
+            );
+        }
 
         trace!(unit = %unit.name(), ?props, "Compiling unit dojo compiler.");
 
@@ -172,12 +399,150 @@ impl Compiler for DojoCompiler {
             &ws.config().ui(),
         )?;
 
-        let mut artifact_manager =
-            compile_contracts(db, &contracts, compiler_config, ws, self.output_debug_info)?;
+        let dojo_config: ProfileConfig = DojoConfigLoader::from_package(package, ws)?;
+
+        // The CASM compilation is slow, it's only done when the CASM is output or its size
+        // is required.
+        let compile_casm =
+            props.casm || self.options.size_report || dojo_config.budget.requires_casm();
+
+        let fingerprints = fingerprint_contracts(
+            db,
+            &collect_all_crate_ids(&unit, db),
+            &contracts,
+            (
+                self.options.output_debug_info,
+                props.casm,
+                compile_casm,
+                props.casm_add_pythonic_hints,
+                unit_compiler_config(&unit),
+            ),
+        );
+
+        let previous_build = if self.options.use_cache && !self.options.locked {
+            read_previous_build(ws, package)
+        } else {
+            None
+        };
+
+        let (reused_contracts, contracts_to_compile): (Vec<_>, Vec<_>) =
+            contracts.iter().partition(|decl| {
+                let qualified_path = decl.module_id().full_path(db);
+
+                // The previous artifact must have all the outputs, a deleted debug info or
+                // CASM file being only restored by compiling the contract again.
+                previous_build.as_ref().is_some_and(|(cache, artifacts)| {
+                    cache.is_fresh(&qualified_path, &fingerprints[&qualified_path])
+                        && artifacts.get_artifact(&qualified_path).is_some_and(|a| {
+                            a.has_outputs(self.options.output_debug_info, props.casm)
+                        })
+                })
+            });
+
+        trace!(
+            reused = reused_contracts.len(),
+            to_compile = contracts_to_compile.len(),
+            "Contracts partitioned using the build cache."
+        );
+
+        let mut artifact_manager = compile_contracts(
+            db,
+            &contracts_to_compile,
+            compiler_config,
+            ws,
+            package,
+            &props,
+            self.options.output_debug_info,
+            compile_casm,
+        )?;
+
+        if let Some((_, previous_artifacts)) = &previous_build {
+            for decl in reused_contracts {
+                let qualified_path = decl.module_id().full_path(db);
+                let artifact = previous_artifacts
+                    .get_artifact(&qualified_path)
+                    .expect("Reused artifact must exist in the previous build.")
+                    .clone();
+
+                artifact_manager.add_reused_artifact(qualified_path, artifact);
+            }
+        }
+
+        // The resources of the external contracts may come from other dojo packages.
+        let external_crate_ids = props
+            .build_external_contracts
+            .as_ref()
+            .map(|selectors| collect_crates_ids_from_selectors(db, selectors))
+            .unwrap_or_default();
+
+        artifact_manager.set_dojo_annotation(
+            db,
+            &main_crate_ids,
+            &external_crate_ids,
+            &dojo_config.namespace,
+        )?;
+
+        dojo_config.validate(
+            &config_paths(package, ws)?,
+            artifact_manager.dojo_annotation(),
+        )?;
+
+        dojo_config
+            .libfuncs
+            .check(&artifact_manager, package.root(), &ws.config().ui())?;
+
+        for warning in check_writers(
+            db,
+            &main_crate_ids,
+            artifact_manager.dojo_annotation(),
+            &dojo_config,
+        ) {
+            messages::emit(
+                &ws.config().ui(),
+                DiagnosticMessage::warning(
+                    diagnostic_codes::MISSING_WRITER_PERMISSION.code,
+                    warning,
+                ),
+            );
+        }
+
+        let lock_path = BuildLock::path(package.manifest_path(), ws.current_profile()?.as_str());
+        let built_lock = BuildLock::from_artifacts(&artifact_manager)?;
+
+        // The lock is verified before writing anything, to not leave classes that don't
+        // match the lock in the target directory.
+        if self.options.locked {
+            for warning in verify_locked(&lock_path, &built_lock)? {
+                messages::emit(
+                    &ws.config().ui(),
+                    DiagnosticMessage::warning(
+                        diagnostic_codes::LOCKED_CLASS_HASH_MISMATCH.code,
+                        warning,
+                    ),
+                );
+            }
+        }
+
+        // The budgets are checked before writing, for an exceeded budget with the `error`
+        // level to leave the previous artifacts in the target directory.
+        for warning in artifact_manager
+            .size_report()
+            .check_budgets(&dojo_config.budget)?
+        {
+            messages::emit(
+                &ws.config().ui(),
+                DiagnosticMessage::warning(diagnostic_codes::BUDGET_EXCEEDED.code, warning),
+            );
+        }
 
-        artifact_manager.set_dojo_annotation(db, &main_crate_ids)?;
         artifact_manager.write()?;
 
+        if !self.options.locked {
+            built_lock.write(&lock_path)?;
+        }
+
+        BuildCache::new(fingerprints).write(ws, package)?;
+
         Ok(())
     }
 }
@@ -203,30 +568,45 @@ fn find_project_contracts(
 
         let crate_ids = collect_crates_ids_from_selectors(db, &external_contracts);
 
-        let filtered_contracts: Vec<ContractDeclaration> = find_contracts(db, crate_ids.as_ref())
+        let mut contracts = find_contracts(db, crate_ids.as_ref())
             .into_iter()
-            .filter(|decl| {
-                let contract_path = decl.module_id().full_path(db);
-                external_contracts
-                    .iter()
-                    .any(|selector| selector.matches(&contract_path))
-            })
-            .collect::<Vec<ContractDeclaration>>();
+            .map(|decl| (decl.module_id().full_path(db), decl))
+            .collect::<HashMap<String, ContractDeclaration>>();
+
+        let candidates = contracts
+            .iter()
+            .map(|(path, decl)| (path.clone(), is_dojo_model(db, decl)))
+            .sorted()
+            .collect::<Vec<(String, bool)>>();
+
+        let (selected, matches) = SelectorMatches::select(&external_contracts, &candidates);
+
+        for (selector, paths) in &matches.matches {
+            if !paths.is_empty() {
+                ui.verbose(format!(
+                    "Selector `{}` matched: {}",
+                    selector.full_path(),
+                    paths.join(", ")
+                ));
+            }
+        }
 
-        // Display warnings for external contracts that were not found, due to invalid paths
+        // Display warnings for selectors that matched nothing, due to invalid paths
         // most of the time.
-        external_contracts
+        for selector in matches.unmatched() {
+            messages::emit(
+                ui,
+                DiagnosticMessage::warning(
+                    diagnostic_codes::CONTRACT_NOT_FOUND.code,
+                    format!("No contract found for path `{}`.", selector.full_path()),
+                ),
+            );
+        }
+
+        let filtered_contracts = selected
             .iter()
-            .filter(|selector| {
-                !filtered_contracts.iter().any(|decl| {
-                    let contract_path = decl.module_id().full_path(db);
-                    selector.matches(&contract_path)
-                })
-            })
-            .for_each(|selector| {
-                let diagnostic = format!("No contract found for path `{}`.", selector.full_path());
-                ui.warn(diagnostic);
-            });
+            .filter_map(|path| contracts.remove(path))
+            .collect::<Vec<ContractDeclaration>>();
 
         filtered_contracts
     } else {
@@ -250,77 +630,150 @@ fn find_project_contracts(
     Ok(all_contracts)
 }
 
+/// Reads the build cache and the artifacts of the previous build, if any.
+///
+/// A previous build that can't be read is not an error, all the contracts
+/// are compiled in that case.
+fn read_previous_build<'w>(
+    ws: &'w Workspace<'w>,
+    package: &Package,
+) -> Option<(BuildCache, ArtifactManager<'w>)> {
+    let cache = match BuildCache::read(ws, package) {
+        Ok(cache) if !cache.fingerprints.is_empty() => cache,
+        Ok(_) => return None,
+        Err(e) => {
+            trace!(error = ?e, "Failed to read the build cache.");
+            return None;
+        }
+    };
+
+    let mut artifacts = ArtifactManager::new(ws, package);
+
+    if let Err(e) = artifacts.read(ws) {
+        trace!(error = ?e, "Failed to read the artifacts of the previous build.");
+        return None;
+    }
+
+    Some((cache, artifacts))
+}
+
+/// Returns the configuration of the compilation unit affecting the compiled classes
+/// (`sierra-replace-ids`, `inlining-strategy`, editions and cfgs...), to fingerprint the
+/// contracts.
+fn unit_compiler_config(unit: &CairoCompilationUnit) -> String {
+    let components = unit
+        .components
+        .iter()
+        .map(|c| {
+            format!(
+                "{}:{:?}:{:?}",
+                c.cairo_package_name(),
+                c.package.manifest.edition,
+                c.cfg_set
+            )
+        })
+        .join(",");
+
+    format!("{:?};{:?};{components}", unit.compiler_config, unit.cfg_set)
+}
+
 /// Compiles the contracts.
+///
+/// Even if no contract has to be compiled, the diagnostics of the database are checked.
+///
+/// The CASM is compiled if `compile_casm` is true, to compute the size of the contracts,
+/// but only kept in the artifacts if requested in the props.
 fn compile_contracts<'w>(
     db: &mut RootDatabase,
-    contracts: &[ContractDeclaration],
+    contracts: &[&ContractDeclaration],
     compiler_config: CompilerConfig<'_>,
     ws: &'w Workspace<'w>,
+    package: &Package,
+    props: &Props,
     do_output_debug_info: bool,
+    compile_casm: bool,
 ) -> Result<ArtifactManager<'w>> {
-    let contracts: Vec<&ContractDeclaration> = contracts.iter().collect::<Vec<_>>();
-
-    let classes = {
+    let compiled_classes = {
         let _ = trace_span!("compile_starknet").enter();
-        compile_prepared_db(db, &contracts, compiler_config)?
+        compile_prepared_db_with_debug_info(db, contracts, compiler_config)?
     };
 
-    let debug_info_classes: Vec<Option<SierraToCairoDebugInfo>> = if do_output_debug_info {
-        let debug_classes =
-            scarb_internal::debug::compile_prepared_db_to_debug_info(db, &contracts)?;
-
-        debug_classes
+    let (classes, debug_infos): (Vec<ContractClass>, Vec<SierraProgramDebugInfo>) =
+        compiled_classes
             .into_iter()
-            .map(|d| {
-                Some(scarb_internal::debug::get_sierra_to_cairo_debug_info(
-                    &d, db,
-                ))
-            })
+            .map(|c| (c.contract_class, c.debug_info))
+            .unzip();
+
+    let debug_info_classes: Vec<Option<SierraToCairoDebugInfo>> = if do_output_debug_info {
+        debug_infos
+            .iter()
+            .map(|d| Some(scarb_internal::debug::get_sierra_to_cairo_debug_info(d, db)))
             .collect()
     } else {
         vec![None; contracts.len()]
     };
 
-    let mut artifact_manager = ArtifactManager::new(ws);
-    let list_selector = ListSelector::default();
+    let class_hashes: Vec<Result<Felt>> = {
+        let _ = trace_span!("compute_class_hashes").enter();
+        classes
+            .par_iter()
+            .map(compute_class_hash_of_contract_class)
+            .collect()
+    };
 
-    for (decl, contract_class, debug_info) in izip!(contracts, classes, debug_info_classes) {
-        let contract_name = decl.submodule_id.name(db.upcast_mut());
+    let casm_classes: Vec<Option<Result<CasmContractClass>>> = {
+        let _ = trace_span!("compile_casm").enter();
+        classes
+            .par_iter()
+            .map(|class| {
+                compile_casm.then(|| {
+                    CasmContractClass::from_contract_class(
+                        class.clone(),
+                        props.casm_add_pythonic_hints,
+                        usize::MAX,
+                    )
+                    .map_err(anyhow::Error::from)
+                })
+            })
+            .collect()
+    };
+
+    let mut artifact_manager = ArtifactManager::new(ws, package);
+
+    for (decl, contract_class, sierra_debug_info, debug_info, class_hash, casm_class) in izip!(
+        contracts,
+        classes,
+        debug_infos,
+        debug_info_classes,
+        class_hashes,
+        casm_classes
+    ) {
         // note that the qualified path is in snake case while
         // the `full_path()` method of StructId uses the original struct name case.
         // (see in `get_dojo_model_artifacts`)
         let qualified_path = decl.module_id().full_path(db.upcast_mut());
 
-        match contract_class.validate_version_compatible(list_selector.clone()) {
-            Ok(()) => {}
-            Err(AllowedLibfuncsError::UnsupportedLibfunc {
-                invalid_libfunc,
-                allowed_libfuncs_list_name: _,
-            }) => {
-                let diagnostic = format! {r#"
-                    Contract `{contract_name}` ({qualified_path}) includes `{invalid_libfunc}` function that is not allowed in the default libfuncs for public Starknet networks (mainnet, sepolia).
-                    It will work on Katana, but don't forget to remove it before deploying on a public Starknet network.
-                "#};
-
-                ws.config().ui().warn(diagnostic);
-            }
-            Err(e) => {
-                return Err(e).with_context(|| {
-                    format!(
-                        "Failed to check allowed libfuncs for contract: {}",
-                        contract_name
-                    )
-                });
-            }
-        }
+        let class_hash = class_hash.with_context(|| {
+            format!(
+                "problem computing class hash for contract `{}`",
+                qualified_path.clone()
+            )
+        })?;
+
+        let casm_class = casm_class
+            .transpose()
+            .with_context(|| format!("failed to compile CASM for contract `{qualified_path}`"))?;
+
+        let size = ContractSize::new(
+            &qualified_path,
+            &contract_class,
+            casm_class.as_ref(),
+            &sierra_debug_info,
+            package.root(),
+            db,
+        );
 
-        let class_hash =
-            compute_class_hash_of_contract_class(&contract_class).with_context(|| {
-                format!(
-                    "problem computing class hash for contract `{}`",
-                    qualified_path.clone()
-                )
-            })?;
+        let casm_class = casm_class.filter(|_| props.casm);
 
         artifact_manager.add_artifact(
             qualified_path,
@@ -328,6 +781,9 @@ fn compile_contracts<'w>(
                 class_hash,
                 contract_class: Rc::new(contract_class),
                 debug_info: debug_info.map(Rc::new),
+                compiled_class_hash: casm_class.as_ref().map(compute_compiled_class_hash),
+                casm_class: casm_class.map(Rc::new),
+                size: Some(size),
             },
         );
     }
@@ -336,15 +792,57 @@ fn compile_contracts<'w>(
 }
 
 /// Computes the class hash of a contract class.
+///
+/// The Sierra class is built directly from the contract class, only the ABI is
+/// converted, since its hash is computed from its JSON representation.
 pub fn compute_class_hash_of_contract_class(class: &ContractClass) -> Result<Felt> {
-    let class_str = serde_json::to_string(&class)?;
-    let sierra_class = serde_json::from_str::<SierraClass>(&class_str)
-        .map_err(|e| anyhow!("error parsing Sierra class: {e}"))?;
+    let abi = match &class.abi {
+        Some(abi) => serde_json::from_value(serde_json::to_value(abi)?)
+            .map_err(|e| anyhow!("error parsing contract ABI: {e}"))?,
+        None => vec![],
+    };
+
+    let entry_points = |entry_points: &[ContractEntryPoint]| {
+        entry_points
+            .iter()
+            .map(|e| SierraEntryPoint {
+                selector: Felt::from_bytes_be_slice(&e.selector.to_bytes_be()),
+                function_idx: e.function_idx as u64,
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let sierra_class = SierraClass {
+        sierra_program: class
+            .sierra_program
+            .iter()
+            .map(|felt| Felt::from_bytes_be_slice(&felt.value.to_bytes_be()))
+            .collect(),
+        // The debug info is not part of the class hash.
+        sierra_program_debug_info: SierraClassDebugInfo {
+            type_names: vec![],
+            libfunc_names: vec![],
+            user_func_names: vec![],
+        },
+        contract_class_version: class.contract_class_version.clone(),
+        entry_points_by_type: EntryPointsByType {
+            constructor: entry_points(&class.entry_points_by_type.constructor),
+            external: entry_points(&class.entry_points_by_type.external),
+            l1_handler: entry_points(&class.entry_points_by_type.l1_handler),
+        },
+        abi,
+    };
+
     sierra_class
         .class_hash()
         .map_err(|e| anyhow!("problem hashing sierra contract: {e}"))
 }
 
+/// Computes the compiled class hash of a CASM class.
+pub fn compute_compiled_class_hash(casm_class: &CasmContractClass) -> Felt {
+    Felt::from_bytes_be(&casm_class.compiled_class_hash().to_bytes_be())
+}
+
 /// Collects the main crate ids for Dojo including the core crates.
 pub fn collect_main_crate_ids(
     unit: &CairoCompilationUnit,
@@ -365,12 +863,46 @@ pub fn collect_main_crate_ids(
     main_crate_ids
 }
 
+/// Checks if the contract is the contract generated for a dojo model, declared
+/// alongside the model struct.
+fn is_dojo_model(db: &RootDatabase, decl: &ContractDeclaration) -> bool {
+    let name = decl.submodule_id.name(db);
+
+    let Ok(file_infos) = db.module_generated_file_aux_data(decl.submodule_id.parent_module(db))
+    else {
+        return false;
+    };
+
+    file_infos
+        .iter()
+        .filter_map(|aux_data| aux_data.as_ref().map(|aux_data| aux_data.0.as_any()))
+        .filter_map(|aux_data| aux_data.downcast_ref::<ModelAuxData>())
+        .any(|model| model.name.to_case(Case::Snake) == name)
+}
+
 /// Collects the crate ids containing the given contract selectors.
+///
+/// Negated selectors only exclude contracts and add no crate. A selector with a glob
+/// package applies to all the crates of the database, except the corelib.
 pub fn collect_crates_ids_from_selectors(
     db: &RootDatabase,
     contract_selectors: &[ContractSelector],
 ) -> Vec<CrateId> {
-    contract_selectors
+    let selectors = contract_selectors
+        .iter()
+        .filter(|selector| !selector.is_negated())
+        .collect::<Vec<_>>();
+
+    if selectors.iter().any(|selector| selector.has_glob_package()) {
+        return db
+            .crates()
+            .into_iter()
+            .filter(|crate_id| crate_id.name(db) != CORELIB_CRATE_NAME)
+            .sorted_by_key(|crate_id| crate_id.name(db))
+            .collect();
+    }
+
+    selectors
         .iter()
         .map(|selector| selector.package().into())
         .unique()
@@ -384,3 +916,41 @@ pub fn collect_all_crate_ids(unit: &CairoCompilationUnit, db: &RootDatabase) ->
         .map(|component| db.intern_crate(CrateLongId::Real(component.cairo_package_name())))
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_class_hash_matches_json_round_trip() {
+        let class: ContractClass =
+            serde_json::from_str(include_str!("test_data/contract_class.json")).unwrap();
+
+        // The class hash was computed by parsing the JSON of the contract class as a Sierra
+        // class, which must give the same hash.
+        let sierra_class =
+            serde_json::from_str::<SierraClass>(&serde_json::to_string(&class).unwrap()).unwrap();
+
+        assert_eq!(
+            compute_class_hash_of_contract_class(&class).unwrap(),
+            sierra_class.class_hash().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_class_hash_without_abi() {
+        let mut class: ContractClass =
+            serde_json::from_str(include_str!("test_data/contract_class.json")).unwrap();
+        class.abi = None;
+
+        let mut class_json = serde_json::to_value(&class).unwrap();
+        class_json["abi"] = serde_json::json!([]);
+
+        let sierra_class = serde_json::from_value::<SierraClass>(class_json).unwrap();
+
+        assert_eq!(
+            compute_class_hash_of_contract_class(&class).unwrap(),
+            sierra_class.class_hash().unwrap()
+        );
+    }
+}
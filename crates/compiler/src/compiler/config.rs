@@ -1,12 +1,23 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use camino::Utf8PathBuf;
 use scarb::core::{Package, TargetKind, Workspace};
+use scarb_ui::args::PackagesFilter;
 use serde::Deserialize;
+use toml::{Table, Value};
 use tracing::{trace, warn};
 
 use crate::namespace_config::NamespaceConfig;
 
+/// The sections of the configuration whose `${NAME}` references are interpolated.
+///
+/// Only the commands using the values of these sections interpolate them, the compilation
+/// leaving the references as they are so a missing secret doesn't break the build.
+pub const INTERPOLATED_SECTIONS: [&str; 2] = ["env", "migration"];
+
 /// Dojo compiler configuration file contents.
+///
+/// Only the namespace configuration is extracted, the other sections being ignored.
+/// The full file is described by [`super::profile_config::ProfileConfig`].
 #[derive(Debug, Clone, Default, Deserialize)]
 pub struct CompilerConfig {
     pub namespace: NamespaceConfig,
@@ -32,7 +43,20 @@ where
     T: serde::de::DeserializeOwned + Default,
 {
     /// Loads the Dojo configuration from the given package.
+    ///
+    /// The `${NAME}` references are left as they are, see
+    /// [`DojoConfigLoader::from_package_with_env`].
     pub fn from_package(package: &Package, ws: &Workspace<'_>) -> Result<T> {
+        Self::load(package, ws, false)
+    }
+
+    /// Loads the Dojo configuration from the given package, interpolating the environment
+    /// variables of the [`INTERPOLATED_SECTIONS`].
+    pub fn from_package_with_env(package: &Package, ws: &Workspace<'_>) -> Result<T> {
+        Self::load(package, ws, true)
+    }
+
+    fn load(package: &Package, ws: &Workspace<'_>, with_env: bool) -> Result<T> {
         // If it's a lib, we can try to extract dojo data. If failed -> then we can return default.
         // But like so, if some metadata are here, we get them.
         // [[target.dojo]] shouldn't be used with [lib] as no files will be deployed.
@@ -45,67 +69,251 @@ where
             ));
         }
 
-        let profile = ws.current_profile()?;
-        let manifest_dir = &Utf8PathBuf::from(package.manifest_path().parent().unwrap());
-        let dev_config_path = manifest_dir.join("dojo_dev.toml");
-        let config_path = manifest_dir.join(format!("dojo_{}.toml", profile.as_str()));
-
-        trace!(package = ?package.id.name, manifest_dir = ?manifest_dir, profile = ?profile, "Loading dojo config.");
+        let config_paths = config_paths(package, ws)?;
 
-        if !dev_config_path.exists() {
+        let Some(mut config) = load_merged_config(&config_paths)? else {
             if !is_lib {
+                let profile = ws.current_profile()?;
                 warn!("Dojo configuration file not found, using default config. Consider adding `dojo_{profile}.toml` alongside your `Scarb.toml` to configure Dojo with this profile.");
             }
 
             return Ok(Default::default());
-        }
-
-        // If the profile file is not found, default to the dev config, if any.
-        let config_path = if !config_path.exists() {
-            dev_config_path
-        } else {
-            config_path
         };
 
-        let content = std::fs::read_to_string(&config_path)?;
-        let config: T = toml::from_str(&content)?;
+        if with_env {
+            interpolate_sections(&mut config)?;
+        }
+
+        let config: T = T::deserialize(config).with_context(|| {
+            format!(
+                "Invalid Dojo configuration (merged from {}).",
+                display_paths(&config_paths)
+            )
+        })?;
         Ok(config)
     }
 
-    /// Loads the Dojo metadata from the workspace, where exactly one package with [[target.dojo]] is required.
-    pub fn from_workspace(ws: &Workspace<'_>) -> Result<T> {
-        let dojo_packages: Vec<Package> = ws
-            .members()
-            .filter(|package| {
-                package.target(&TargetKind::new("dojo")).is_some()
-                    && package.target(&TargetKind::new("lib")).is_none()
+    /// Loads the Dojo configuration of each package with [[target.dojo]] of the workspace.
+    pub fn from_workspace(ws: &Workspace<'_>) -> Result<Vec<(Package, T)>> {
+        let dojo_packages = find_dojo_packages(ws);
+
+        if dojo_packages.is_empty() {
+            ws.config()
+                .ui()
+                .warn("No package with [[target.dojo]] found in workspace.");
+        }
+
+        dojo_packages
+            .into_iter()
+            .map(|package| {
+                let config = Self::from_package(&package, ws)?;
+                Ok((package, config))
             })
-            .collect();
-
-        match dojo_packages.len() {
-            0 => {
-                ws.config()
-                    .ui()
-                    .warn("No package with [[target.dojo]] found in workspace.");
-                Ok(Default::default())
+            .collect()
+    }
+}
+
+/// Returns the packages with [[target.dojo]] of the workspace, sorted by name.
+///
+/// Each of them is a distinct world, with its own configuration and artifacts.
+pub fn find_dojo_packages(ws: &Workspace<'_>) -> Vec<Package> {
+    let mut dojo_packages: Vec<Package> = ws
+        .members()
+        .filter(|package| {
+            package.target(&TargetKind::new("dojo")).is_some()
+                && package.target(&TargetKind::new("lib")).is_none()
+        })
+        .collect();
+
+    dojo_packages.sort_by(|a, b| a.id.name.as_str().cmp(b.id.name.as_str()));
+    dojo_packages
+}
+
+/// Returns the packages with [[target.dojo]] of the workspace selected by the filter,
+/// all of them if there is no filter.
+pub fn select_dojo_packages(
+    ws: &Workspace<'_>,
+    filter: Option<&PackagesFilter>,
+) -> Result<Vec<Package>> {
+    let dojo_packages = find_dojo_packages(ws);
+
+    let Some(filter) = filter else {
+        return Ok(dojo_packages);
+    };
+
+    let selected = filter.match_many(ws)?;
+
+    Ok(dojo_packages
+        .into_iter()
+        .filter(|package| selected.iter().any(|p| p.id == package.id))
+        .collect())
+}
+
+/// Returns the Dojo configuration files of the given package for the current profile,
+/// in the order they are merged. Only the existing files are returned.
+///
+/// The layers are:
+///  * `dojo_dev.toml`, the base configuration,
+///  * `dojo_<profile>.toml`, overriding the base configuration for the profile,
+///  * `dojo_<profile>.local.toml`, local overrides not meant to be committed.
+pub fn config_paths(package: &Package, ws: &Workspace<'_>) -> Result<Vec<Utf8PathBuf>> {
+    let profile = ws.current_profile()?;
+    let manifest_dir = &Utf8PathBuf::from(package.manifest_path().parent().unwrap());
+
+    trace!(package = ?package.id.name, manifest_dir = ?manifest_dir, profile = ?profile, "Loading dojo config.");
+
+    let mut layers = vec![manifest_dir.join("dojo_dev.toml")];
+
+    if profile.as_str() != "dev" {
+        layers.push(manifest_dir.join(format!("dojo_{}.toml", profile.as_str())));
+    }
+
+    layers.push(manifest_dir.join(format!("dojo_{}.local.toml", profile.as_str())));
+
+    Ok(layers.into_iter().filter(|path| path.exists()).collect())
+}
+
+/// Loads and merges the given configuration files.
+///
+/// The `${NAME}` references are left as they are, see [`interpolate_sections`].
+///
+/// # Returns
+///
+/// The merged configuration, `None` if no file is given.
+///
+/// # Arguments
+///
+/// * `config_paths` - The configuration files, in the order they are merged.
+pub fn load_merged_config(config_paths: &[Utf8PathBuf]) -> Result<Option<Value>> {
+    let mut merged: Option<Table> = None;
+
+    for path in config_paths {
+        let content = std::fs::read_to_string(path)?;
+        let layer: Table = toml::from_str(&content)
+            .with_context(|| format!("Invalid Dojo configuration `{path}`."))?;
+
+        match merged.as_mut() {
+            Some(merged) => merge_tables(merged, layer),
+            None => merged = Some(layer),
+        }
+    }
+
+    Ok(merged.map(Value::Table))
+}
+
+/// Interpolates the environment variables referenced by the [`INTERPOLATED_SECTIONS`] of a
+/// merged configuration.
+pub fn interpolate_sections(config: &mut Value) -> Result<()> {
+    let lookup = |name: &str| std::env::var(name).ok();
+
+    for section in INTERPOLATED_SECTIONS {
+        if let Some(value) = config.get_mut(section) {
+            interpolate_env(value, section, &lookup)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the given paths as a comma separated list, for the error messages.
+pub fn display_paths(paths: &[Utf8PathBuf]) -> String {
+    paths
+        .iter()
+        .map(|p| format!("`{p}`"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Merges a configuration layer into the base configuration.
+///
+/// Tables are merged key by key, any other value of the layer replaces the base one.
+fn merge_tables(base: &mut Table, layer: Table) {
+    for (key, value) in layer {
+        match base.get_mut(&key) {
+            Some(Value::Table(base_table)) if value.is_table() => {
+                if let Value::Table(layer) = value {
+                    merge_tables(base_table, layer);
+                }
             }
-            1 => {
-                let dojo_package = dojo_packages
-                    .into_iter()
-                    .next()
-                    .expect("Package must exist as len is 1.");
-                Ok(Self::from_package(&dojo_package, ws)?)
+            Some(base_value) => *base_value = value,
+            None => {
+                base.insert(key, value);
             }
-            _ => {
-                let error_message = "Multiple packages with [[target.dojo]] found in workspace. Please specify a package \
-                 using --package option or maybe one of them must be declared as a [lib].";
+        }
+    }
+}
 
-                ws.config().ui().error(error_message);
+/// Replaces the `${NAME}` references in all the strings of the value by the value of
+/// the variable returned by `lookup`. `$${` is an escaped `${`.
+///
+/// # Arguments
+///
+/// * `value` - The value to interpolate.
+/// * `key_path` - The path of the value in the configuration, for the error messages.
+/// * `lookup` - Returns the value of a variable, `None` if not set.
+fn interpolate_env(
+    value: &mut Value,
+    key_path: &str,
+    lookup: &dyn Fn(&str) -> Option<String>,
+) -> Result<()> {
+    let child_path = |key: &str| {
+        if key_path.is_empty() {
+            key.to_string()
+        } else {
+            format!("{key_path}.{key}")
+        }
+    };
 
-                Err(anyhow::anyhow!(error_message))
+    match value {
+        Value::String(s) => *s = interpolate(s, key_path, lookup)?,
+        Value::Array(values) => {
+            for (i, v) in values.iter_mut().enumerate() {
+                interpolate_env(v, &child_path(&i.to_string()), lookup)?;
+            }
+        }
+        Value::Table(table) => {
+            for (k, v) in table.iter_mut() {
+                interpolate_env(v, &child_path(k), lookup)?;
             }
         }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+/// Interpolates the `${NAME}` references of a string.
+fn interpolate(s: &str, key_path: &str, lookup: &dyn Fn(&str) -> Option<String>) -> Result<String> {
+    let mut result = String::with_capacity(s.len());
+    let mut rest = s;
+
+    while let Some(start) = rest.find('$') {
+        result.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        if let Some(escaped) = rest.strip_prefix("$${") {
+            result.push_str("${");
+            rest = escaped;
+        } else if let Some(reference) = rest.strip_prefix("${") {
+            let end = reference
+                .find('}')
+                .with_context(|| format!("Unterminated `${{` in the value of `{key_path}`."))?;
+            let name = &reference[..end];
+
+            let value = lookup(name).with_context(|| {
+                format!("Environment variable `{name}` used by `{key_path}` is not set.")
+            })?;
+
+            result.push_str(&value);
+            rest = &reference[end + 1..];
+        } else {
+            result.push('$');
+            rest = &rest[1..];
+        }
     }
+
+    result.push_str(rest);
+    Ok(result)
 }
 
 #[cfg(test)]
@@ -152,8 +360,179 @@ default = "ns1"
         let config = setup_default_config(&scarb_toml);
         let workspace = scarb::ops::read_workspace(&scarb_toml, &config).unwrap();
 
-        let config: CompilerConfig = DojoConfigLoader::from_workspace(&workspace).unwrap();
-        assert_eq!(config.namespace.default, "ns1");
+        let configs: Vec<(Package, CompilerConfig)> =
+            DojoConfigLoader::from_workspace(&workspace).unwrap();
+        assert_eq!(configs.len(), 1);
+        assert_eq!(configs[0].1.namespace.default, "ns1");
+    }
+
+    #[test]
+    fn test_multiple_dojo_packages_from_workspace() {
+        let temp_dir = TempDir::new().unwrap();
+        let temp_path = Utf8PathBuf::from_path_buf(temp_dir.path().to_path_buf()).unwrap();
+
+        fs::write(
+            temp_path.join("Scarb.toml"),
+            "[workspace]\nmembers = [\"game\", \"marketplace\"]\n",
+        )
+        .unwrap();
+
+        for (name, namespace) in [("marketplace", "market"), ("game", "game")] {
+            let package_dir = temp_path.join(name);
+            fs::create_dir_all(package_dir.join("src")).unwrap();
+            fs::write(package_dir.join("src").join("lib.cairo"), "").unwrap();
+            fs::write(
+                package_dir.join("Scarb.toml"),
+                format!("[package]\nname = \"{name}\"\nversion = \"0.1.0\"\n\n[[target.dojo]]\n"),
+            )
+            .unwrap();
+            fs::write(
+                package_dir.join("dojo_dev.toml"),
+                format!("[namespace]\ndefault = \"{namespace}\"\n"),
+            )
+            .unwrap();
+        }
+
+        let scarb_toml = temp_path.join("Scarb.toml");
+        let config = setup_default_config(&scarb_toml);
+        let workspace = scarb::ops::read_workspace(&scarb_toml, &config).unwrap();
+
+        let configs: Vec<(Package, CompilerConfig)> =
+            DojoConfigLoader::from_workspace(&workspace).unwrap();
+
+        let namespaces = configs
+            .iter()
+            .map(|(package, config)| {
+                (
+                    package.id.name.to_string(),
+                    config.namespace.default.clone(),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            namespaces,
+            vec![
+                ("game".to_string(), "game".to_string()),
+                ("marketplace".to_string(), "market".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_merge_tables() {
+        let mut base: Table = toml::from_str(
+            r#"
+[world]
+name = "simple"
+seed = "simple"
+
+[env]
+rpc_url = "http://localhost:5050/"
+
+[writers]
+"ns" = ["ns-c1"]
+"#,
+        )
+        .unwrap();
+
+        let layer: Table = toml::from_str(
+            r#"
+[env]
+rpc_url = "https://api.cartridge.gg/x/starknet/sepolia"
+private_key = "${PRIVATE_KEY}"
+
+[writers]
+"ns" = ["ns-c2"]
+"#,
+        )
+        .unwrap();
+
+        merge_tables(&mut base, layer);
+
+        assert_eq!(base["world"]["name"].as_str(), Some("simple"));
+        assert_eq!(
+            base["env"]["rpc_url"].as_str(),
+            Some("https://api.cartridge.gg/x/starknet/sepolia")
+        );
+        assert_eq!(base["env"]["private_key"].as_str(), Some("${PRIVATE_KEY}"));
+        assert_eq!(
+            base["writers"]["ns"].as_array().unwrap(),
+            &vec![Value::from("ns-c2")]
+        );
+    }
+
+    #[test]
+    fn test_interpolate_env() {
+        let lookup = |name: &str| (name == "KEY").then(|| "0x1".to_string());
+
+        let mut value = Value::Table(
+            toml::from_str(
+                r#"
+[env]
+private_key = "${KEY}"
+account_address = "prefix_${KEY}_$${KEY}_$5"
+"#,
+            )
+            .unwrap(),
+        );
+
+        interpolate_env(&mut value, "", &lookup).unwrap();
+
+        assert_eq!(value["env"]["private_key"].as_str(), Some("0x1"));
+        assert_eq!(
+            value["env"]["account_address"].as_str(),
+            Some("prefix_0x1_${KEY}_$5")
+        );
+
+        let err = interpolate("${MISSING}", "env.private_key", &lookup).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Environment variable `MISSING` used by `env.private_key` is not set."
+        );
+        assert!(interpolate("${KEY", "env.private_key", &lookup).is_err());
+    }
+
+    #[test]
+    fn test_load_merged_config_keeps_references() {
+        let temp_dir = TempDir::new().unwrap();
+        let temp_path = Utf8PathBuf::from_path_buf(temp_dir.path().to_path_buf()).unwrap();
+        let dojo_dev = temp_path.join("dojo_dev.toml");
+
+        fs::write(
+            &dojo_dev,
+            r#"
+[world]
+name = "${DOJO_TEST_UNSET_WORLD_NAME}"
+
+[env]
+private_key = "${DOJO_TEST_UNSET_PRIVATE_KEY}"
+"#,
+        )
+        .unwrap();
+
+        let mut config = load_merged_config(&[dojo_dev]).unwrap().unwrap();
+
+        assert_eq!(
+            config["env"]["private_key"].as_str(),
+            Some("${DOJO_TEST_UNSET_PRIVATE_KEY}")
+        );
+
+        let err = interpolate_sections(&mut config).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Environment variable `DOJO_TEST_UNSET_PRIVATE_KEY` used by `env.private_key` is \
+             not set."
+        );
+
+        config["env"]["private_key"] = Value::from("0x1");
+        interpolate_sections(&mut config).unwrap();
+
+        // Only the sections using the values are interpolated.
+        assert_eq!(
+            config["world"]["name"].as_str(),
+            Some("${DOJO_TEST_UNSET_WORLD_NAME}")
+        );
     }
 
     #[test]
@@ -187,6 +566,7 @@ version = "0.1.0"
         let config = setup_default_config(&scarb_toml);
         let workspace = scarb::ops::read_workspace(&scarb_toml, &config).unwrap();
 
-        let _: CompilerConfig = DojoConfigLoader::from_workspace(&workspace).unwrap();
+        let _: Vec<(Package, CompilerConfig)> =
+            DojoConfigLoader::from_workspace(&workspace).unwrap();
     }
 }
@@ -2,6 +2,26 @@
 //!
 //! The contract selector is used to select contracts to be compiled, and identify the contracts
 //! inside the database using glob patterns and Cairo qualified paths.
+//!
+//! The patterns are matched segment by segment on the Cairo path of the contracts:
+//!  * `*` matches any characters of a segment, and `?` a single character,
+//!  * `**` matches any number of segments, including none,
+//!  * a last segment ending with `*` also matches all the paths under the matched ones,
+//!    `pkg::*` selecting all the contracts of `pkg`,
+//!  * a last segment without glob is also compared in snake case, to select model contracts
+//!    from their struct name (`pkg::models::Position`).
+//!
+//! A pattern prefixed with `!` excludes the contracts it matches.
+//!
+//! In the manifest, a selector is either a pattern or a table with the pattern and options:
+//!
+//! ```toml
+//! build-external-contracts = [
+//!     "openzeppelin::presets::*",
+//!     "!openzeppelin::presets::mocks::*",
+//!     { path = "armory::models", models = true },
+//! ]
+//! ```
 
 use anyhow::Result;
 use convert_case::{Case, Casing};
@@ -11,31 +31,118 @@ use serde::{Deserialize, Serialize};
 use crate::CAIRO_PATH_SEPARATOR;
 
 pub const GLOB_PATH_SELECTOR: &str = "*";
+/// Glob matching any number of path segments.
+pub const GLOB_SEGMENTS_SELECTOR: &str = "**";
+/// Prefix of the selectors excluding the contracts they match.
+pub const NEGATION_PREFIX: &str = "!";
+
+/// Options of a contract selector.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SelectorOptions {
+    /// Also selects the dojo models declared under the paths matched by the selector.
+    pub models: bool,
+}
+
+/// A contract selector, as written in the manifest.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged, deny_unknown_fields)]
+enum ContractSelectorDef {
+    Path(String),
+    Table {
+        path: String,
+        #[serde(default)]
+        models: bool,
+    },
+}
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-pub struct ContractSelector(String);
+#[serde(from = "ContractSelectorDef", into = "ContractSelectorDef")]
+pub struct ContractSelector {
+    path: String,
+    options: SelectorOptions,
+}
+
+impl From<ContractSelectorDef> for ContractSelector {
+    fn from(def: ContractSelectorDef) -> Self {
+        match def {
+            ContractSelectorDef::Path(path) => Self::new(path),
+            ContractSelectorDef::Table { path, models } => {
+                Self::new(path).with_options(SelectorOptions { models })
+            }
+        }
+    }
+}
+
+impl From<ContractSelector> for ContractSelectorDef {
+    fn from(selector: ContractSelector) -> Self {
+        if selector.options == SelectorOptions::default() {
+            ContractSelectorDef::Path(selector.path)
+        } else {
+            ContractSelectorDef::Table {
+                path: selector.path,
+                models: selector.options.models,
+            }
+        }
+    }
+}
 
 impl ContractSelector {
     pub fn new(path: String) -> Self {
-        Self(path)
+        Self {
+            path,
+            options: SelectorOptions::default(),
+        }
+    }
+
+    /// Sets the options of the selector.
+    pub fn with_options(mut self, options: SelectorOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Returns the options of the selector.
+    pub fn options(&self) -> &SelectorOptions {
+        &self.options
+    }
+
+    /// Checks if the selector excludes the contracts it matches.
+    pub fn is_negated(&self) -> bool {
+        self.path.starts_with(NEGATION_PREFIX)
+    }
+
+    /// Returns the pattern of the selector, without the negation prefix.
+    pub fn pattern(&self) -> &str {
+        self.path
+            .strip_prefix(NEGATION_PREFIX)
+            .unwrap_or(&self.path)
     }
 
     pub fn package(&self) -> PackageName {
-        let parts = self
-            .0
+        let pattern = self.pattern();
+        let parts = pattern
             .split_once(CAIRO_PATH_SEPARATOR)
-            .unwrap_or((self.0.as_str(), ""));
+            .unwrap_or((pattern, ""));
         PackageName::new(parts.0)
     }
 
+    /// Checks if the package segment of the pattern is a glob, the selector
+    /// then applying to the contracts of all the packages.
+    pub fn has_glob_package(&self) -> bool {
+        self.pattern()
+            .split(CAIRO_PATH_SEPARATOR)
+            .next()
+            .is_some_and(is_glob)
+    }
+
     /// Returns the path with the model name in snake case.
     /// This is used to match the output of the `compile()` function and Dojo plugin naming for
     /// models contracts.
+    #[deprecated(note = "the last segment of a pattern is compared in snake case by `matches`")]
     pub fn path_with_model_snake_case(&self) -> String {
         let (path, last_segment) = self
-            .0
+            .path
             .rsplit_once(CAIRO_PATH_SEPARATOR)
-            .unwrap_or(("", &self.0));
+            .unwrap_or(("", &self.path));
 
         // We don't want to snake case the whole path because some of names like `erc20`
         // will be changed to `erc_20`, and leading to invalid paths.
@@ -50,46 +157,220 @@ impl ContractSelector {
     }
 
     /// Checks if the contract selector is/has a wildcard.
-    /// Wildcard selectors are only supported in the last segment of the path.
+    #[deprecated(note = "globs are supported in any segment, use `matches` instead")]
     pub fn is_wildcard(&self) -> bool {
-        self.0.ends_with(GLOB_PATH_SELECTOR)
+        self.path.ends_with(GLOB_PATH_SELECTOR)
     }
 
     /// Returns the partial path without the wildcard.
+    #[deprecated(note = "globs are supported in any segment, use `matches` instead")]
     pub fn partial_path(&self) -> String {
         let parts = self
-            .0
+            .path
             .split_once(GLOB_PATH_SELECTOR)
-            .unwrap_or((self.0.as_str(), ""));
+            .unwrap_or((self.path.as_str(), ""));
         parts.0.to_string()
     }
 
-    /// Returns the full path.
+    /// Returns the full path, with the negation prefix if any.
     pub fn full_path(&self) -> String {
-        self.0.clone()
+        self.path.clone()
     }
 
-    /// Checks if the contract path matches the selector with wildcard support.
+    /// Checks if the contract path matches the pattern of the selector, whether the
+    /// selector is negated or not.
     pub fn matches(&self, contract_path: &str) -> bool {
-        if self.is_wildcard() {
-            contract_path.starts_with(&self.partial_path())
-        } else {
-            contract_path == self.path_with_model_snake_case()
+        let patterns = self
+            .pattern()
+            .split(CAIRO_PATH_SEPARATOR)
+            .collect::<Vec<_>>();
+        let segments = contract_path
+            .split(CAIRO_PATH_SEPARATOR)
+            .collect::<Vec<_>>();
+
+        match_segments(&patterns, &segments)
+    }
+
+    /// Checks if the contract is selected by the selector, whether the selector is negated
+    /// or not. With the `models` option, the model contracts declared under a matched path
+    /// are selected too.
+    ///
+    /// # Arguments
+    ///
+    /// * `contract_path` - The Cairo path of the contract.
+    /// * `is_model` - Whether the contract is the contract of a dojo model.
+    pub fn selects(&self, contract_path: &str, is_model: bool) -> bool {
+        if self.matches(contract_path) {
+            return true;
+        }
+
+        if !(self.options.models && is_model) {
+            return false;
         }
+
+        let segments = contract_path
+            .split(CAIRO_PATH_SEPARATOR)
+            .collect::<Vec<_>>();
+
+        (1..segments.len()).any(|len| self.matches(&segments[..len].join(CAIRO_PATH_SEPARATOR)))
     }
 
     /// Validates the contract selector.
-    /// The contract selector is valid if it has at most one wildcard.
+    ///
+    /// The pattern must have no empty segment, and `**` must be a whole segment.
     pub fn is_valid(&self) -> Result<()> {
-        if self.full_path().matches(GLOB_PATH_SELECTOR).count() > 1 {
-            anyhow::bail!("Contract path `{}` has multiple wildcard selectors, only one '*' selector is allowed.",
-            self.full_path());
+        let pattern = self.pattern();
+
+        if pattern.is_empty() {
+            anyhow::bail!("Contract path `{}` is empty.", self.full_path());
+        }
+
+        for segment in pattern.split(CAIRO_PATH_SEPARATOR) {
+            if segment.is_empty() {
+                anyhow::bail!("Contract path `{}` has an empty segment.", self.full_path());
+            }
+
+            if segment.contains(GLOB_SEGMENTS_SELECTOR) && segment != GLOB_SEGMENTS_SELECTOR {
+                anyhow::bail!(
+                    "Contract path `{}` has an invalid segment `{segment}`, `**` must be a whole \
+                     segment.",
+                    self.full_path()
+                );
+            }
+
+            if segment.contains(NEGATION_PREFIX) {
+                anyhow::bail!(
+                    "Contract path `{}` has an invalid segment `{segment}`, `!` is only allowed \
+                     as prefix of the path.",
+                    self.full_path()
+                );
+            }
         }
 
         Ok(())
     }
 }
 
+/// Checks if a pattern segment has glob characters.
+fn is_glob(segment: &str) -> bool {
+    segment.contains(['*', '?'])
+}
+
+/// Matches the path segments against the pattern segments.
+fn match_segments(patterns: &[&str], segments: &[&str]) -> bool {
+    match patterns {
+        [] => segments.is_empty(),
+        [GLOB_SEGMENTS_SELECTOR, rest @ ..] => {
+            match_segments(rest, segments)
+                || (!segments.is_empty() && match_segments(patterns, &segments[1..]))
+        }
+        // A last segment ending with `*` also matches the paths under the matched ones.
+        [last] if last.ends_with(GLOB_PATH_SELECTOR) => {
+            !segments.is_empty() && match_glob(last, segments[0])
+        }
+        [last] if !is_glob(last) => {
+            segments.len() == 1
+                && (*last == segments[0] || last.to_case(Case::Snake) == segments[0])
+        }
+        [pattern, rest @ ..] => {
+            !segments.is_empty()
+                && match_glob(pattern, segments[0])
+                && match_segments(rest, &segments[1..])
+        }
+    }
+}
+
+/// Matches a path segment against a glob, where `*` matches any characters and `?`
+/// a single character.
+fn match_glob(glob: &str, text: &str) -> bool {
+    let glob = glob.chars().collect::<Vec<_>>();
+    let text = text.chars().collect::<Vec<_>>();
+
+    // Indexes of the last `*` of the glob and of the text matched by it, to backtrack.
+    let (mut g, mut t) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if g < glob.len() && (glob[g] == '?' || glob[g] == text[t]) {
+            g += 1;
+            t += 1;
+        } else if g < glob.len() && glob[g] == '*' {
+            star = Some((g, t));
+            g += 1;
+        } else if let Some((star_g, star_t)) = star {
+            g = star_g + 1;
+            t = star_t + 1;
+            star = Some((star_g, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+
+    glob[g..].iter().all(|c| *c == '*')
+}
+
+/// The contracts matched by each selector.
+#[derive(Debug, Clone, Default)]
+pub struct SelectorMatches {
+    /// The selectors with the paths of the contracts they matched, in the order
+    /// of the selectors.
+    pub matches: Vec<(ContractSelector, Vec<String>)>,
+}
+
+impl SelectorMatches {
+    /// Selects the contracts from a list of selectors: a contract is selected if
+    /// it's selected by at least one selector, and excluded by no negated selector.
+    ///
+    /// # Arguments
+    ///
+    /// * `selectors` - The selectors, in the order of the manifest.
+    /// * `contracts` - The paths of the candidate contracts, with whether each one is a model.
+    ///
+    /// # Returns
+    ///
+    /// The paths of the selected contracts, and the matches of each selector.
+    pub fn select(
+        selectors: &[ContractSelector],
+        contracts: &[(String, bool)],
+    ) -> (Vec<String>, Self) {
+        let matches = selectors
+            .iter()
+            .map(|selector| {
+                let paths = contracts
+                    .iter()
+                    .filter(|(path, is_model)| selector.selects(path, *is_model))
+                    .map(|(path, _)| path.clone())
+                    .collect();
+
+                (selector.clone(), paths)
+            })
+            .collect::<Vec<(ContractSelector, Vec<String>)>>();
+
+        let is_matched_by = |path: &String, negated: bool| {
+            matches
+                .iter()
+                .any(|(selector, paths)| selector.is_negated() == negated && paths.contains(path))
+        };
+
+        let selected = contracts
+            .iter()
+            .map(|(path, _)| path)
+            .filter(|path| is_matched_by(path, false) && !is_matched_by(path, true))
+            .cloned()
+            .collect();
+
+        (selected, Self { matches })
+    }
+
+    /// Returns the selectors that matched no contract.
+    pub fn unmatched(&self) -> impl Iterator<Item = &ContractSelector> {
+        self.matches
+            .iter()
+            .filter(|(_, paths)| paths.is_empty())
+            .map(|(selector, _)| selector)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -107,6 +388,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(deprecated)]
     fn test_path_with_model_snake_case() {
         let selector = ContractSelector::new("my_package::MyContract".to_string());
         assert_eq!(
@@ -130,6 +412,18 @@ mod tests {
         );
     }
 
+    #[test]
+    #[allow(deprecated)]
+    fn test_wildcard() {
+        let selector = ContractSelector::new("my_package::sub_*".to_string());
+        assert!(selector.is_wildcard());
+        assert_eq!(selector.partial_path(), "my_package::sub_");
+
+        let selector = ContractSelector::new("my_package::MyContract".to_string());
+        assert!(!selector.is_wildcard());
+        assert_eq!(selector.partial_path(), "my_package::MyContract");
+    }
+
     #[test]
     fn test_full_path() {
         let selector = ContractSelector::new("my_package::sub_package::MyContract".to_string());
@@ -142,8 +436,23 @@ mod tests {
             ContractSelector::new("my_package::sub_package::MyContract".to_string());
         assert!(valid_selector.is_valid().is_ok());
 
-        let invalid_selector = ContractSelector::new("my_package::*::*::MyContract".to_string());
-        assert!(invalid_selector.is_valid().is_err());
+        let glob_selector = ContractSelector::new("my_package::*::*::MyContract".to_string());
+        assert!(glob_selector.is_valid().is_ok());
+
+        for invalid in [
+            "",
+            "!",
+            "my_package::::MyContract",
+            "my_package::a**",
+            "my::!b",
+        ] {
+            assert!(
+                ContractSelector::new(invalid.to_string())
+                    .is_valid()
+                    .is_err(),
+                "{invalid}"
+            );
+        }
     }
 
     #[test]
@@ -154,4 +463,80 @@ mod tests {
         assert!(!selector.matches("other_package::sub_package::MyContract"));
         assert!(!selector.matches("package::sub_package::OtherContract"));
     }
+
+    #[test]
+    fn test_matches_glob() {
+        let selector = ContractSelector::new("openzeppelin::token::*::erc20".to_string());
+        assert!(selector.matches("openzeppelin::token::presets::erc20"));
+        assert!(!selector.matches("openzeppelin::token::presets::erc721"));
+        assert!(!selector.matches("openzeppelin::token::a::b::erc20"));
+
+        let selector = ContractSelector::new("**::presets::*".to_string());
+        assert!(selector.matches("openzeppelin::presets::account"));
+        assert!(selector.matches("oz::token::presets::erc20"));
+        assert!(!selector.matches("oz::token::erc20"));
+
+        let selector = ContractSelector::new("pkg::erc?0_*".to_string());
+        assert!(selector.matches("pkg::erc20_token"));
+        assert!(!selector.matches("pkg::erc721_token"));
+
+        let selector = ContractSelector::new("pkg::models::Position".to_string());
+        assert!(selector.matches("pkg::models::position"));
+    }
+
+    #[test]
+    fn test_selects_models() {
+        let selector = ContractSelector::new("pkg::models".to_string())
+            .with_options(SelectorOptions { models: true });
+
+        assert!(selector.selects("pkg::models::position", true));
+        assert!(selector.selects("pkg::models::nested::moves", true));
+        assert!(!selector.selects("pkg::models::helper", false));
+        assert!(!ContractSelector::new("pkg::models".to_string())
+            .selects("pkg::models::position", true));
+    }
+
+    #[test]
+    fn test_select_with_negation() {
+        let selectors = ["oz::*", "!oz::mocks::*", "other::*"]
+            .iter()
+            .map(|s| ContractSelector::new(s.to_string()))
+            .collect::<Vec<_>>();
+
+        let contracts = [("oz::erc20", false), ("oz::mocks::erc20", false)]
+            .iter()
+            .map(|(path, is_model)| (path.to_string(), *is_model))
+            .collect::<Vec<_>>();
+
+        let (selected, matches) = SelectorMatches::select(&selectors, &contracts);
+
+        assert_eq!(selected, vec!["oz::erc20".to_string()]);
+        assert_eq!(matches.matches[1].1, vec!["oz::mocks::erc20".to_string()]);
+        assert_eq!(
+            matches
+                .unmatched()
+                .map(|s| s.full_path())
+                .collect::<Vec<_>>(),
+            vec!["other::*".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_deserialize_selectors() {
+        #[derive(Deserialize)]
+        struct Props {
+            selectors: Vec<ContractSelector>,
+        }
+
+        let props: Props =
+            toml::from_str(r#"selectors = ["pkg::*", { path = "pkg::models", models = true }]"#)
+                .unwrap();
+
+        assert_eq!(
+            props.selectors[0],
+            ContractSelector::new("pkg::*".to_string())
+        );
+        assert!(props.selectors[1].options().models);
+        assert_eq!(props.selectors[1].full_path(), "pkg::models");
+    }
 }
@@ -0,0 +1,416 @@
+//! Static analysis of the models read and written by the systems of the dojo contracts.
+//!
+//! The models are accessed through the `ModelStorage` and `ModelValueStorage` traits
+//! of the dojo core library. Each system of a contract is analyzed by looking at the calls
+//! to those traits in its semantic body, following the calls to the functions of the same
+//! crate (internal functions, generated traits...). The generic functions are followed with
+//! the generic arguments of the call, to resolve the accessed models.
+
+use std::collections::{BTreeMap, HashSet};
+
+use cairo_lang_compiler::db::RootDatabase;
+use cairo_lang_defs::db::DefsGroup;
+use cairo_lang_defs::ids::{
+    FunctionWithBodyId, LanguageElementId, ModuleId, NamedLanguageElementId,
+};
+use cairo_lang_filesystem::ids::CrateId;
+use cairo_lang_semantic::db::SemanticGroup;
+use cairo_lang_semantic::items::functions::{
+    ConcreteFunction, ConcreteFunctionWithBodyId, GenericFunctionId,
+};
+use cairo_lang_semantic::items::generics::GenericArgumentId;
+use cairo_lang_semantic::substitution::{SemanticRewriter, SubstitutionRewriter};
+use cairo_lang_semantic::Expr;
+use cairo_lang_syntax::node::ids::SyntaxStablePtrId;
+use cairo_lang_syntax::node::TypedStablePtr;
+use dojo_types::system::Dependency;
+
+/// Storage trait to access models.
+const MODEL_STORAGE_TRAIT: &str = "ModelStorage";
+/// Storage trait to access model values (the model without its keys).
+const MODEL_VALUE_STORAGE_TRAIT: &str = "ModelValueStorage";
+/// Suffix of the value structs generated for each model.
+const MODEL_VALUE_SUFFIX: &str = "Value";
+
+/// The kind of access of a model storage function.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessKind {
+    Read,
+    Write,
+}
+
+/// The accesses of a system to a model, as the locations of the storage calls.
+#[derive(Debug, Clone, Default)]
+pub struct ModelAccess {
+    pub reads: Vec<SyntaxStablePtrId>,
+    /// Writes and erasures.
+    pub writes: Vec<SyntaxStablePtrId>,
+}
+
+impl ModelAccess {
+    /// Converts the access into a dependency on the given model.
+    pub fn to_dependency(&self, name: &str) -> Dependency {
+        Dependency {
+            name: name.to_string(),
+            read: !self.reads.is_empty(),
+            write: !self.writes.is_empty(),
+        }
+    }
+}
+
+/// The model accesses of a system, by model path.
+pub type SystemAccesses = BTreeMap<String, ModelAccess>;
+
+/// Returns the kind of access of a function of the model storage traits,
+/// `None` if the function doesn't access the models.
+///
+/// # Arguments
+///
+/// * `trait_name` - The name of the trait of the function.
+/// * `function_name` - The name of the function.
+pub fn access_kind(trait_name: &str, function_name: &str) -> Option<AccessKind> {
+    // The test traits bypass the permissions and are not used in the systems.
+    if trait_name != MODEL_STORAGE_TRAIT && trait_name != MODEL_VALUE_STORAGE_TRAIT {
+        return None;
+    }
+
+    if function_name.starts_with("read_") {
+        Some(AccessKind::Read)
+    } else if function_name.starts_with("write_") || function_name.starts_with("erase_") {
+        Some(AccessKind::Write)
+    } else {
+        None
+    }
+}
+
+/// Returns the path of the model from the type used as generic argument
+/// of a model storage trait.
+///
+/// # Arguments
+///
+/// * `trait_name` - The name of the model storage trait.
+/// * `type_path` - The formatted type, with its generic arguments if any.
+pub fn model_path(trait_name: &str, type_path: &str) -> String {
+    let path = type_path.split('<').next().unwrap_or(type_path);
+
+    if trait_name == MODEL_VALUE_STORAGE_TRAIT {
+        path.strip_suffix(MODEL_VALUE_SUFFIX)
+            .unwrap_or(path)
+            .to_string()
+    } else {
+        path.to_string()
+    }
+}
+
+/// Returns the module of a dojo contract, declared in the given parent module.
+///
+/// # Arguments
+///
+/// * `db` - The database to look the module in.
+/// * `parent_module_id` - The module where the contract is declared.
+/// * `name` - The name of the contract module.
+pub fn contract_module(
+    db: &RootDatabase,
+    parent_module_id: ModuleId,
+    name: &str,
+) -> Option<ModuleId> {
+    db.module_submodules_ids(parent_module_id)
+        .ok()?
+        .iter()
+        .find(|submodule_id| submodule_id.name(db) == name)
+        .map(|submodule_id| ModuleId::Submodule(*submodule_id))
+}
+
+/// Analyzes the model accesses of the functions of a dojo contract, systems or not
+/// (like `dojo_init`), by function name.
+///
+/// The functions are the free functions and the functions of the impls of the contract
+/// module. Functions with no access to any model are still present in the result, with
+/// no accesses.
+///
+/// # Arguments
+///
+/// * `db` - The database the contract was compiled from.
+/// * `contract_module_id` - The module of the contract.
+pub fn functions_accesses(
+    db: &RootDatabase,
+    contract_module_id: ModuleId,
+) -> BTreeMap<String, SystemAccesses> {
+    let crate_id = contract_module_id.owning_crate(db);
+
+    let mut functions = db
+        .module_free_functions_ids(contract_module_id)
+        .map(|ids| {
+            ids.iter()
+                .map(|id| (id.name(db), FunctionWithBodyId::Free(*id)))
+                .collect()
+        })
+        .unwrap_or(vec![]);
+
+    for impl_def_id in db
+        .module_impls_ids(contract_module_id)
+        .unwrap_or(std::sync::Arc::new([]))
+        .iter()
+    {
+        if let Ok(impl_functions) = db.impl_functions(*impl_def_id) {
+            functions.extend(
+                impl_functions
+                    .iter()
+                    .map(|(name, id)| (name.clone(), FunctionWithBodyId::Impl(*id))),
+            );
+        }
+    }
+
+    let mut accesses = BTreeMap::new();
+
+    for (name, function_id) in functions {
+        let function_accesses: &mut SystemAccesses = accesses.entry(name.to_string()).or_default();
+        let mut visited = HashSet::new();
+
+        if let Ok(function_id) = ConcreteFunctionWithBodyId::from_generic(db, function_id) {
+            collect_accesses(db, function_id, crate_id, &mut visited, function_accesses);
+        }
+    }
+
+    accesses
+}
+
+/// Returns the write calls of the functions of a contract by written model path, each
+/// call being reported once.
+///
+/// # Arguments
+///
+/// * `accesses` - The model accesses by function of the contract.
+pub fn model_writes(
+    accesses: &BTreeMap<String, SystemAccesses>,
+) -> BTreeMap<String, Vec<SyntaxStablePtrId>> {
+    let mut writes: BTreeMap<String, Vec<SyntaxStablePtrId>> = BTreeMap::new();
+
+    for (path, access) in accesses.values().flatten() {
+        if access.writes.is_empty() {
+            continue;
+        }
+
+        let ptrs = writes.entry(path.clone()).or_default();
+
+        for ptr in &access.writes {
+            if !ptrs.contains(ptr) {
+                ptrs.push(*ptr);
+            }
+        }
+    }
+
+    writes
+}
+
+/// Collects the model accesses of a function, following the calls to the functions
+/// of the given crate.
+///
+/// The calls are concretized with the generic arguments of the function, a generic
+/// function being analyzed once for each set of generic arguments it's called with.
+fn collect_accesses(
+    db: &RootDatabase,
+    function_id: ConcreteFunctionWithBodyId,
+    crate_id: CrateId,
+    visited: &mut HashSet<ConcreteFunctionWithBodyId>,
+    accesses: &mut SystemAccesses,
+) {
+    if !visited.insert(function_id) {
+        return;
+    }
+
+    let Ok(body) = db.function_body(function_id.function_with_body_id(db)) else {
+        return;
+    };
+
+    let Ok(substitution) = function_id.substitution(db) else {
+        return;
+    };
+
+    let mut rewriter = SubstitutionRewriter {
+        db,
+        substitution: &substitution,
+    };
+
+    for (_, expr) in body.arenas.exprs.iter() {
+        let Expr::FunctionCall(call) = expr else {
+            continue;
+        };
+
+        let Ok(function) = rewriter.rewrite(call.function) else {
+            continue;
+        };
+
+        let concrete_function = function.get_concrete(db);
+
+        if let Some((model_path, kind)) = model_access(db, &concrete_function) {
+            let access = accesses.entry(model_path).or_default();
+            let ptr = call.stable_ptr.untyped();
+
+            match kind {
+                AccessKind::Read => access.reads.push(ptr),
+                AccessKind::Write => access.writes.push(ptr),
+            }
+
+            continue;
+        }
+
+        let Ok(Some(callee)) = concrete_function.body(db) else {
+            continue;
+        };
+
+        if callee
+            .function_with_body_id(db)
+            .parent_module(db)
+            .owning_crate(db)
+            == crate_id
+        {
+            collect_accesses(db, callee, crate_id, visited, accesses);
+        }
+    }
+}
+
+/// Returns the accessed model path and the kind of access if the function
+/// is a function of the model storage traits, `None` if the model is not resolved
+/// to a concrete type.
+fn model_access(
+    db: &RootDatabase,
+    concrete_function: &ConcreteFunction,
+) -> Option<(String, AccessKind)> {
+    let GenericFunctionId::Impl(impl_function) = concrete_function.generic_function else {
+        return None;
+    };
+
+    let trait_name = impl_function.function.trait_id(db).name(db);
+    let kind = access_kind(&trait_name, &impl_function.function.name(db))?;
+
+    // The storage traits are generic over the storage and the model: `Trait<S, M>`.
+    let concrete_trait = impl_function.impl_id.concrete_trait(db).ok()?;
+    let GenericArgumentId::Type(model_ty) = concrete_trait.generic_args(db).get(1)?.clone() else {
+        return None;
+    };
+
+    if !model_ty.is_fully_concrete(db) {
+        return None;
+    }
+
+    Some((model_path(&trait_name, &model_ty.format(db)), kind))
+}
+
+/// Converts the accesses of the systems into dependencies, naming the models
+/// with the provided function.
+///
+/// # Arguments
+///
+/// * `accesses` - The model accesses by system.
+/// * `model_name` - Returns the name of a model from its path.
+pub fn to_dependencies(
+    accesses: &BTreeMap<String, SystemAccesses>,
+    model_name: impl Fn(&str) -> String,
+) -> BTreeMap<String, Vec<Dependency>> {
+    accesses
+        .iter()
+        .map(|(system, system_accesses)| {
+            let dependencies = system_accesses
+                .iter()
+                .map(|(path, access)| access.to_dependency(&model_name(path)))
+                .collect();
+
+            (system.clone(), dependencies)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_fs::TempDir;
+    use cairo_lang_filesystem::db::FilesGroup;
+    use cairo_lang_filesystem::ids::CrateLongId;
+
+    use super::*;
+    use crate::compiler::test_utils::build_test_db;
+
+    #[test]
+    fn test_access_kind() {
+        assert_eq!(
+            access_kind("ModelStorage", "read_model"),
+            Some(AccessKind::Read)
+        );
+        assert_eq!(
+            access_kind("ModelValueStorage", "read_values_from_ids"),
+            Some(AccessKind::Read)
+        );
+        assert_eq!(
+            access_kind("ModelStorage", "write_models"),
+            Some(AccessKind::Write)
+        );
+        assert_eq!(
+            access_kind("ModelStorage", "erase_model_ptr"),
+            Some(AccessKind::Write)
+        );
+        assert_eq!(access_kind("ModelStorage", "namespace_hash"), None);
+        assert_eq!(access_kind("ModelStorageTest", "write_model_test"), None);
+        assert_eq!(access_kind("Serde", "serialize"), None);
+    }
+
+    #[test]
+    fn test_model_path() {
+        assert_eq!(
+            model_path("ModelStorage", "pkg::models::Position"),
+            "pkg::models::Position"
+        );
+        assert_eq!(
+            model_path("ModelValueStorage", "pkg::models::PositionValue"),
+            "pkg::models::Position"
+        );
+        assert_eq!(
+            model_path("ModelStorage", "pkg::models::Wrapper<core::felt252>"),
+            "pkg::models::Wrapper"
+        );
+    }
+
+    #[test]
+    fn test_accesses_through_helpers() {
+        let project_dir = TempDir::new().unwrap();
+        let db = build_test_db(
+            project_dir.path(),
+            "dependencies_test",
+            include_str!("test_data/dependencies.cairo"),
+        );
+
+        let crate_id = db.intern_crate(CrateLongId::Real("dependencies_test".into()));
+        let contract_module_id =
+            contract_module(&db, ModuleId::CrateRoot(crate_id), "actions").unwrap();
+
+        let accesses = functions_accesses(&db, contract_module_id);
+        let dependencies = to_dependencies(&accesses, |path| path.to_string());
+
+        // The model written by the generic helper is resolved from the call of the system.
+        assert_eq!(
+            dependencies["spawn"],
+            vec![Dependency {
+                name: "dependencies_test::Position".to_string(),
+                read: false,
+                write: true,
+            }]
+        );
+        assert_eq!(
+            dependencies["remaining"],
+            vec![Dependency {
+                name: "dependencies_test::Moves".to_string(),
+                read: true,
+                write: false,
+            }]
+        );
+
+        // The helper of the world storage doesn't access any model.
+        assert_eq!(dependencies["world_storage"], vec![]);
+
+        let writes = model_writes(&accesses);
+        assert_eq!(
+            writes.keys().collect::<Vec<_>>(),
+            ["dependencies_test::Position"]
+        );
+        assert_eq!(writes["dependencies_test::Position"].len(), 1);
+    }
+}
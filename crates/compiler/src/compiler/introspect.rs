@@ -0,0 +1,632 @@
+//! Static introspection of the Dojo models and events.
+//!
+//! The `Introspect` and `IntrospectPacked` derives generate Cairo code returning the
+//! schema, the layout and the size of a type at runtime. This module computes the same
+//! information from the semantic model of the compiler, to include it in the annotations
+//! without having to deploy the class and call `schema()` or `layout()` on it.
+//!
+//! Only the derived implementations and the ones of the `dojo` core library are
+//! supported, a custom implementation of `Introspect` for a type is not taken into account.
+
+use anyhow::{anyhow, bail, Result};
+use cairo_lang_compiler::db::RootDatabase;
+use cairo_lang_defs::db::DefsGroup;
+use cairo_lang_defs::ids::{ModuleId, NamedLanguageElementId, TopLevelLanguageElementId};
+use cairo_lang_semantic::db::SemanticGroup;
+use cairo_lang_semantic::items::generics::GenericArgumentId;
+use cairo_lang_semantic::types::{
+    ConcreteEnumId, ConcreteStructId, ConcreteStructLongId, ConcreteTypeId, TypeId, TypeLongId,
+};
+use cairo_lang_syntax::node::ast;
+use cairo_lang_syntax::node::helpers::QueryAttrs;
+use cairo_lang_syntax::node::TypedStablePtr;
+use dojo_types::model::{FieldLayout, Layout};
+use dojo_types::primitive::Primitive;
+use dojo_types::schema::{Enum, EnumOption, Member, Struct, Ty};
+use starknet::core::types::Felt;
+use starknet::core::utils::get_selector_from_name;
+
+use crate::plugin::derive_macros::{extract_derive_attr_names, DOJO_PACKED_DERIVE};
+
+/// The maximum number of bits packed into a single felt.
+const PACKING_MAX_BITS: u8 = 251;
+
+/// The schema, layout and sizes of a Dojo model or event.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Introspection {
+    pub schema: Ty,
+    pub layout: Layout,
+    /// Number of felts of the values (keys excluded), `None` if dynamic.
+    pub unpacked_size: Option<usize>,
+    /// Number of felts of the bit-packed values, `None` if the layout is not fixed.
+    pub packed_size: Option<usize>,
+}
+
+/// Computes the introspection of the struct with the given name, defined in the given module.
+///
+/// # Arguments
+///
+/// * `db` - The database the struct is defined in.
+/// * `module_id` - The module defining the struct.
+/// * `name` - The name of the struct.
+pub fn introspect_struct(
+    db: &RootDatabase,
+    module_id: ModuleId,
+    name: &str,
+) -> Result<Introspection> {
+    let struct_id = db
+        .module_structs_ids(module_id)
+        .map_err(|_| {
+            anyhow!(
+                "Failed to get the structs of module `{}`.",
+                module_id.full_path(db)
+            )
+        })?
+        .iter()
+        .copied()
+        .find(|struct_id| struct_id.name(db) == name)
+        .ok_or_else(|| {
+            anyhow!(
+                "Struct `{name}` not found in module `{}`.",
+                module_id.full_path(db)
+            )
+        })?;
+
+    let concrete_struct_id = db.intern_concrete_struct(ConcreteStructLongId {
+        struct_id,
+        generic_args: vec![],
+    });
+    let ty = db.intern_type(TypeLongId::Concrete(ConcreteTypeId::Struct(
+        concrete_struct_id,
+    )));
+
+    let introspector = Introspector { db };
+    let layout = introspector.layout(ty)?;
+
+    Ok(Introspection {
+        schema: introspector.ty(ty)?,
+        unpacked_size: introspector.size(ty)?,
+        packed_size: packed_size(&layout),
+        layout,
+    })
+}
+
+/// Computes the number of felts required to store a bit-packed fixed layout.
+///
+/// Mirrors `dojo::storage::packing::calculate_packed_size`.
+pub fn packed_size(layout: &Layout) -> Option<usize> {
+    let Layout::Fixed(bits) = layout else {
+        return None;
+    };
+
+    let mut size = 1;
+    let mut partial = 0_usize;
+
+    for item_size in bits.iter().map(|b| *b as usize) {
+        partial += item_size;
+
+        if partial > PACKING_MAX_BITS as usize {
+            size += 1;
+            partial = item_size;
+        }
+    }
+
+    Some(size)
+}
+
+/// Returns the primitive and the layout of the corelib type with the given name, if any.
+fn primitive(name: &str) -> Option<(Primitive, Vec<u8>)> {
+    let primitive = match name {
+        "felt252" => (Primitive::Felt252(None), vec![PACKING_MAX_BITS]),
+        "bool" => (Primitive::Bool(None), vec![1]),
+        "u8" => (Primitive::U8(None), vec![8]),
+        "u16" => (Primitive::U16(None), vec![16]),
+        "u32" => (Primitive::U32(None), vec![32]),
+        "u64" => (Primitive::U64(None), vec![64]),
+        "u128" => (Primitive::U128(None), vec![128]),
+        "u256" => (Primitive::U256(None), vec![128, 128]),
+        "i8" => (Primitive::I8(None), vec![PACKING_MAX_BITS]),
+        "i16" => (Primitive::I16(None), vec![PACKING_MAX_BITS]),
+        "i32" => (Primitive::I32(None), vec![PACKING_MAX_BITS]),
+        "i64" => (Primitive::I64(None), vec![PACKING_MAX_BITS]),
+        "i128" => (Primitive::I128(None), vec![PACKING_MAX_BITS]),
+        "ContractAddress" => (Primitive::ContractAddress(None), vec![PACKING_MAX_BITS]),
+        "ClassHash" => (Primitive::ClassHash(None), vec![PACKING_MAX_BITS]),
+        _ => return None,
+    };
+
+    Some(primitive)
+}
+
+/// The kind of a type, as seen by the introspection.
+enum TypeKind {
+    Primitive(Primitive, Vec<u8>),
+    ByteArray,
+    /// `Array<T>` and `Span<T>`, with the type of the items.
+    Array(TypeId),
+    Option(TypeId),
+    Tuple(Vec<TypeId>),
+    Struct(ConcreteStructId),
+    Enum(ConcreteEnumId),
+}
+
+/// A member of a struct, with its `#[key]` attribute.
+struct StructMember {
+    name: String,
+    ty: TypeId,
+    key: bool,
+}
+
+struct Introspector<'a> {
+    db: &'a RootDatabase,
+}
+
+impl Introspector<'_> {
+    fn kind(&self, ty: TypeId) -> Result<TypeKind> {
+        let db = self.db;
+
+        let concrete = match db.lookup_intern_type(ty) {
+            TypeLongId::Tuple(tys) => return Ok(TypeKind::Tuple(tys)),
+            TypeLongId::Concrete(concrete) => concrete,
+            _ => bail!(
+                "Type `{}` is not supported by the introspection.",
+                ty.format(db)
+            ),
+        };
+
+        if ty.format(db).starts_with("core::") {
+            let name = concrete.generic_type(db).name(db);
+
+            if let Some((primitive, bits)) = primitive(&name) {
+                return Ok(TypeKind::Primitive(primitive, bits));
+            }
+
+            match name.as_str() {
+                "ByteArray" => return Ok(TypeKind::ByteArray),
+                "Array" | "Span" => return Ok(TypeKind::Array(self.generic_arg(ty, &concrete)?)),
+                "Option" => return Ok(TypeKind::Option(self.generic_arg(ty, &concrete)?)),
+                _ => {}
+            }
+        }
+
+        match concrete {
+            ConcreteTypeId::Struct(s) => Ok(TypeKind::Struct(s)),
+            ConcreteTypeId::Enum(e) => Ok(TypeKind::Enum(e)),
+            ConcreteTypeId::Extern(_) => bail!(
+                "Extern type `{}` is not supported by the introspection.",
+                ty.format(db)
+            ),
+        }
+    }
+
+    /// Returns the first generic argument of a concrete type, which must be a type.
+    fn generic_arg(&self, ty: TypeId, concrete: &ConcreteTypeId) -> Result<TypeId> {
+        match concrete.generic_args(self.db).first() {
+            Some(GenericArgumentId::Type(arg)) => Ok(*arg),
+            _ => bail!(
+                "Type `{}` is expected to have a generic type argument.",
+                ty.format(self.db)
+            ),
+        }
+    }
+
+    fn struct_members(&self, s: ConcreteStructId) -> Result<Vec<StructMember>> {
+        let db = self.db;
+        let struct_id = s.struct_id(db);
+
+        let members = db.concrete_struct_members(s).map_err(|_| {
+            anyhow!(
+                "Failed to get the members of struct `{}`.",
+                struct_id.full_path(db)
+            )
+        })?;
+
+        Ok(members
+            .iter()
+            .map(|(name, member)| StructMember {
+                name: name.to_string(),
+                ty: member.ty,
+                key: member.id.stable_ptr(db).lookup(db).has_attr(db, "key"),
+            })
+            .collect())
+    }
+
+    fn enum_variants(&self, e: ConcreteEnumId) -> Result<Vec<(String, TypeId)>> {
+        let db = self.db;
+        let enum_id = e.enum_id(db);
+
+        let variants = db.concrete_enum_variants(e).map_err(|_| {
+            anyhow!(
+                "Failed to get the variants of enum `{}`.",
+                enum_id.full_path(db)
+            )
+        })?;
+
+        Ok(variants
+            .iter()
+            .map(|v| (v.id.name(db).to_string(), v.ty))
+            .collect())
+    }
+
+    /// Checks if the item with the given attributes derives `IntrospectPacked`.
+    fn derives_packed(&self, attributes: ast::AttributeList) -> bool {
+        let derives = attributes.query_attr(self.db, "derive");
+
+        extract_derive_attr_names(self.db, &mut vec![], derives)
+            .iter()
+            .any(|name| name == DOJO_PACKED_DERIVE)
+    }
+
+    fn is_unit(&self, ty: TypeId) -> bool {
+        matches!(self.db.lookup_intern_type(ty), TypeLongId::Tuple(tys) if tys.is_empty())
+    }
+
+    /// Mirrors `Introspect::ty()`.
+    fn ty(&self, ty: TypeId) -> Result<Ty> {
+        let db = self.db;
+
+        let ty = match self.kind(ty)? {
+            TypeKind::Primitive(primitive, _) => Ty::Primitive(primitive),
+            TypeKind::ByteArray => Ty::ByteArray(String::new()),
+            TypeKind::Array(item) => Ty::Array(vec![self.ty(item)?]),
+            TypeKind::Option(inner) => Ty::Enum(Enum {
+                name: "Option<T>".to_string(),
+                option: None,
+                options: vec![
+                    EnumOption {
+                        name: "Some(T)".to_string(),
+                        ty: self.ty(inner)?,
+                    },
+                    EnumOption {
+                        name: "None".to_string(),
+                        ty: Ty::Tuple(vec![]),
+                    },
+                ],
+            }),
+            TypeKind::Tuple(tys) => Ty::Tuple(
+                tys.into_iter()
+                    .map(|ty| self.ty(ty))
+                    .collect::<Result<_>>()?,
+            ),
+            TypeKind::Struct(s) => Ty::Struct(Struct {
+                name: s.struct_id(db).name(db).to_string(),
+                children: self
+                    .struct_members(s)?
+                    .into_iter()
+                    .map(|m| {
+                        Ok(Member {
+                            ty: self.ty(m.ty)?,
+                            name: m.name,
+                            key: m.key,
+                        })
+                    })
+                    .collect::<Result<_>>()?,
+            }),
+            TypeKind::Enum(e) => Ty::Enum(Enum {
+                name: e.enum_id(db).name(db).to_string(),
+                option: None,
+                options: self
+                    .enum_variants(e)?
+                    .into_iter()
+                    .map(|(name, ty)| {
+                        Ok(EnumOption {
+                            name,
+                            ty: self.ty(ty)?,
+                        })
+                    })
+                    .collect::<Result<_>>()?,
+            }),
+        };
+
+        Ok(ty)
+    }
+
+    /// Mirrors `Introspect::layout()`, keys being excluded from the struct layouts.
+    fn layout(&self, ty: TypeId) -> Result<Layout> {
+        let db = self.db;
+
+        let layout = match self.kind(ty)? {
+            TypeKind::Primitive(_, bits) => Layout::Fixed(bits),
+            TypeKind::ByteArray => Layout::ByteArray,
+            TypeKind::Array(item) => Layout::Array(vec![self.layout(item)?]),
+            TypeKind::Option(inner) => Layout::Enum(vec![
+                FieldLayout {
+                    selector: Felt::ZERO,
+                    layout: self.layout(inner)?,
+                },
+                FieldLayout {
+                    selector: Felt::ONE,
+                    layout: Layout::Fixed(vec![]),
+                },
+            ]),
+            TypeKind::Tuple(tys) => Layout::Tuple(
+                tys.into_iter()
+                    .map(|ty| self.layout(ty))
+                    .collect::<Result<_>>()?,
+            ),
+            TypeKind::Struct(s) => {
+                let values = self.struct_members(s)?.into_iter().filter(|m| !m.key);
+                let struct_ast = s.struct_id(db).stable_ptr(db).lookup(db);
+
+                if self.derives_packed(struct_ast.attributes(db)) {
+                    let mut bits = vec![];
+                    for m in values {
+                        bits.extend(self.packed_bits(m.ty)?);
+                    }
+
+                    Layout::Fixed(bits)
+                } else {
+                    Layout::Struct(
+                        values
+                            .map(|m| {
+                                Ok(FieldLayout {
+                                    selector: get_selector_from_name(&m.name)?,
+                                    layout: self.layout(m.ty)?,
+                                })
+                            })
+                            .collect::<Result<_>>()?,
+                    )
+                }
+            }
+            TypeKind::Enum(e) => {
+                let variants = self.enum_variants(e)?;
+                let enum_ast = e.enum_id(db).stable_ptr(db).lookup(db);
+
+                if self.derives_packed(enum_ast.attributes(db)) {
+                    // All the variants of a packed enum have the same layout,
+                    // prefixed by the variant index.
+                    let mut bits = vec![8];
+                    if let Some((_, ty)) = variants.first() {
+                        bits.extend(self.packed_bits(*ty)?);
+                    }
+
+                    Layout::Fixed(bits)
+                } else {
+                    Layout::Enum(
+                        variants
+                            .into_iter()
+                            .enumerate()
+                            .map(|(i, (_, ty))| {
+                                let layout = if self.is_unit(ty) {
+                                    Layout::Fixed(vec![])
+                                } else {
+                                    self.layout(ty)?
+                                };
+
+                                Ok(FieldLayout {
+                                    selector: Felt::from(i),
+                                    layout,
+                                })
+                            })
+                            .collect::<Result<_>>()?,
+                    )
+                }
+            }
+        };
+
+        Ok(layout)
+    }
+
+    /// Returns the bits of a type in a packed layout.
+    fn packed_bits(&self, ty: TypeId) -> Result<Vec<u8>> {
+        match self.kind(ty)? {
+            TypeKind::Primitive(_, bits) => Ok(bits),
+            TypeKind::Tuple(tys) => {
+                let mut bits = vec![];
+                for ty in tys {
+                    bits.extend(self.packed_bits(ty)?);
+                }
+
+                Ok(bits)
+            }
+            TypeKind::Struct(_) | TypeKind::Enum(_) => match self.layout(ty)? {
+                Layout::Fixed(bits) => Ok(bits),
+                _ => bail!(
+                    "Type `{}` must derive `{DOJO_PACKED_DERIVE}` to be packed.",
+                    ty.format(self.db)
+                ),
+            },
+            TypeKind::ByteArray | TypeKind::Array(_) | TypeKind::Option(_) => bail!(
+                "Type `{}` has a dynamic size and cannot be packed.",
+                ty.format(self.db)
+            ),
+        }
+    }
+
+    /// Mirrors `Introspect::size()`, keys being excluded from the struct sizes.
+    fn size(&self, ty: TypeId) -> Result<Option<usize>> {
+        let size = match self.kind(ty)? {
+            TypeKind::Primitive(_, bits) => Some(bits.len()),
+            TypeKind::ByteArray | TypeKind::Array(_) | TypeKind::Option(_) => None,
+            TypeKind::Tuple(tys) => self.sum_sizes(tys)?,
+            TypeKind::Struct(s) => {
+                let values = self
+                    .struct_members(s)?
+                    .into_iter()
+                    .filter(|m| !m.key)
+                    .map(|m| m.ty)
+                    .collect();
+
+                // A struct without any value has no size.
+                self.sum_sizes(values)?.filter(|size| *size > 0)
+            }
+            TypeKind::Enum(e) => {
+                let sizes = self
+                    .enum_variants(e)?
+                    .into_iter()
+                    .map(|(_, ty)| self.size(ty))
+                    .collect::<Result<Vec<_>>>()?;
+
+                // The enum has a size only if all the variants have the same one,
+                // plus one felt for the variant index.
+                match sizes.first() {
+                    Some(Some(first)) if sizes.iter().all(|s| *s == Some(*first)) => {
+                        Some(first + 1)
+                    }
+                    _ => None,
+                }
+            }
+        };
+
+        Ok(size)
+    }
+
+    fn sum_sizes(&self, tys: Vec<TypeId>) -> Result<Option<usize>> {
+        let mut total = 0;
+
+        for ty in tys {
+            match self.size(ty)? {
+                Some(size) => total += size,
+                None => return Ok(None),
+            }
+        }
+
+        Ok(Some(total))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_fs::TempDir;
+    use cainome::cairo_serde::CairoSerde;
+    use cairo_lang_filesystem::db::FilesGroup;
+    use cairo_lang_filesystem::ids::CrateLongId;
+    use cairo_lang_runner::{RunResultValue, SierraCasmRunner, StarknetState};
+    use cairo_lang_sierra_generator::db::SierraGenGroup;
+    use cairo_lang_sierra_generator::replace_ids::replace_sierra_ids_in_program;
+    use dojo_types::model;
+    use starknet::core::utils::{cairo_short_string_to_felt, parse_cairo_short_string};
+
+    use super::*;
+    use crate::compiler::test_utils::build_test_db;
+
+    /// Runs a function of the test package panicking with the serialized size, layout and type
+    /// of a type, as computed by its `Introspect` implementation.
+    fn derived_introspection(runner: &SierraCasmRunner, function: &str) -> Introspection {
+        let run = runner
+            .run_function_with_starknet_context(
+                runner.find_function(function).unwrap(),
+                &[],
+                None,
+                StarknetState::default(),
+            )
+            .unwrap();
+
+        let RunResultValue::Panic(values) = run.value else {
+            panic!("`{function}` should panic with the introspection.");
+        };
+
+        let values = values
+            .iter()
+            .map(|v| Felt::from_bytes_be(&v.to_bytes_be()))
+            .collect::<Vec<_>>();
+
+        let size = Option::<u32>::cairo_deserialize(&values, 0).unwrap();
+        let mut offset = Option::<u32>::cairo_serialized_size(&size);
+        let layout = Layout::cairo_deserialize(&values, offset).unwrap();
+        offset += Layout::cairo_serialized_size(&layout);
+        let ty = model::Ty::cairo_deserialize(&values, offset).unwrap();
+
+        Introspection {
+            schema: parse_ty(&ty),
+            unpacked_size: size.map(|size| size as usize),
+            packed_size: packed_size(&layout),
+            layout,
+        }
+    }
+
+    /// Converts the type returned by `Introspect::ty()` to the schema of the annotations.
+    fn parse_ty(ty: &model::Ty) -> Ty {
+        let name = |felt: &Felt| parse_cairo_short_string(felt).unwrap();
+
+        match ty {
+            model::Ty::Primitive(primitive) => Ty::Primitive(name(primitive).parse().unwrap()),
+            model::Ty::Struct(s) => Ty::Struct(Struct {
+                name: name(&s.name),
+                children: s
+                    .children
+                    .iter()
+                    .map(|m| Member {
+                        name: name(&m.name),
+                        ty: parse_ty(&m.ty),
+                        key: m
+                            .attrs
+                            .contains(&cairo_short_string_to_felt("key").unwrap()),
+                    })
+                    .collect(),
+            }),
+            model::Ty::Enum(e) => Ty::Enum(Enum {
+                name: name(&e.name),
+                option: None,
+                options: e
+                    .children
+                    .iter()
+                    .map(|(option, ty)| EnumOption {
+                        name: name(option),
+                        ty: parse_ty(ty),
+                    })
+                    .collect(),
+            }),
+            model::Ty::Tuple(tys) => Ty::Tuple(tys.iter().map(parse_ty).collect()),
+            model::Ty::Array(tys) => Ty::Array(tys.iter().map(parse_ty).collect()),
+            model::Ty::ByteArray => Ty::ByteArray(String::new()),
+        }
+    }
+
+    #[test]
+    fn test_introspection_matches_introspect_derive() {
+        let project_dir = TempDir::new().unwrap();
+        let db = build_test_db(
+            project_dir.path(),
+            "introspect_test",
+            include_str!("test_data/introspect.cairo"),
+        );
+
+        let crate_id = db.intern_crate(CrateLongId::Real("introspect_test".into()));
+        let program = db.get_sierra_program(vec![crate_id]).unwrap();
+        let runner = SierraCasmRunner::new(
+            replace_sierra_ids_in_program(&db, &program.program),
+            None,
+            Default::default(),
+            None,
+        )
+        .unwrap();
+
+        // A model with nested enums, options, arrays and tuples, and a packed struct with a
+        // packed enum.
+        for (name, function) in [
+            ("Position", "introspect_position"),
+            ("Stats", "introspect_stats"),
+        ] {
+            assert_eq!(
+                introspect_struct(&db, ModuleId::CrateRoot(crate_id), name).unwrap(),
+                derived_introspection(&runner, function),
+                "{name}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_packed_size() {
+        assert_eq!(packed_size(&Layout::Fixed(vec![])), Some(1));
+        assert_eq!(packed_size(&Layout::Fixed(vec![8, 16, 32])), Some(1));
+        assert_eq!(packed_size(&Layout::Fixed(vec![128, 128])), Some(2));
+        assert_eq!(packed_size(&Layout::Fixed(vec![251, 1, 250])), Some(2));
+        assert_eq!(packed_size(&Layout::Fixed(vec![251, 251, 251])), Some(3));
+        assert_eq!(packed_size(&Layout::ByteArray), None);
+        assert_eq!(packed_size(&Layout::Struct(vec![])), None);
+    }
+
+    #[test]
+    fn test_primitive() {
+        assert_eq!(
+            primitive("u256"),
+            Some((Primitive::U256(None), vec![128, 128]))
+        );
+        assert_eq!(
+            primitive("ContractAddress"),
+            Some((Primitive::ContractAddress(None), vec![PACKING_MAX_BITS]))
+        );
+        assert_eq!(primitive("ByteArray"), None);
+    }
+}
@@ -0,0 +1,344 @@
+//! Allowed libfuncs policy of the compiled contracts.
+//!
+//! Public Starknet networks only accept classes using the libfuncs of their allowed list.
+//! The list to validate against and what to do on a violation are configured per profile,
+//! in the `[libfuncs]` section of the `dojo_<profile>.toml` file:
+//!
+//! ```toml
+//! [libfuncs]
+//! # "default", "audited", "experimental" or { file = "path/to/allowed_libfuncs.json" }.
+//! list = "audited"
+//! # "warn" or "deny".
+//! mode = "deny"
+//! ```
+
+use anyhow::{Context, Result};
+use cairo_lang_starknet_classes::allowed_libfuncs::{
+    AllowedLibfuncsError, ListSelector, BUILTIN_AUDITED_LIBFUNCS_LIST,
+    BUILTIN_EXPERIMENTAL_LIBFUNCS_LIST,
+};
+use camino::{Utf8Path, Utf8PathBuf};
+use scarb_ui::Ui;
+use serde::Deserialize;
+
+use super::artifact_manager::ArtifactManager;
+use super::messages::{self, DiagnosticMessage};
+use crate::diagnostic_codes;
+
+/// The list of allowed libfuncs to validate the contracts against.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LibfuncsList {
+    /// The default list of the compiler, accepted by public Starknet networks.
+    #[default]
+    Default,
+    Audited,
+    Experimental,
+    /// A custom JSON file, relative to the package root if not absolute.
+    File(Utf8PathBuf),
+}
+
+/// What to do when a contract uses a libfunc that is not allowed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LibfuncsMode {
+    /// Warn about the violations, the contracts still being written.
+    #[default]
+    Warn,
+    /// Fail the build on any violation.
+    Deny,
+}
+
+/// The allowed libfuncs policy of a profile.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LibfuncsConfig {
+    pub list: LibfuncsList,
+    pub mode: LibfuncsMode,
+}
+
+impl LibfuncsConfig {
+    /// Returns the list selector of the compiler for the configured list.
+    ///
+    /// # Arguments
+    ///
+    /// * `package_root` - The root directory of the package, to resolve a relative list file.
+    pub fn list_selector(&self, package_root: &Utf8Path) -> ListSelector {
+        match &self.list {
+            LibfuncsList::Default => ListSelector::DefaultList,
+            LibfuncsList::Audited => ListSelector::ListName(BUILTIN_AUDITED_LIBFUNCS_LIST.into()),
+            LibfuncsList::Experimental => {
+                ListSelector::ListName(BUILTIN_EXPERIMENTAL_LIBFUNCS_LIST.into())
+            }
+            LibfuncsList::File(path) => ListSelector::ListFile(package_root.join(path).to_string()),
+        }
+    }
+
+    /// Validates all the artifacts of the artifact manager against the allowed libfuncs list.
+    ///
+    /// In `warn` mode, each violation is reported as a warning. In `deny` mode, an error
+    /// listing all the violations is returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `artifacts` - The artifacts to validate.
+    /// * `package_root` - The root directory of the package, to resolve a relative list file.
+    /// * `ui` - The UI to report the warnings to.
+    pub fn check(
+        &self,
+        artifacts: &ArtifactManager<'_>,
+        package_root: &Utf8Path,
+        ui: &Ui,
+    ) -> Result<()> {
+        let list_selector = self.list_selector(package_root);
+
+        let mut artifacts = artifacts.iter().collect::<Vec<_>>();
+        artifacts.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        let mut violations = vec![];
+
+        for (qualified_path, artifact) in artifacts {
+            match artifact
+                .contract_class
+                .validate_version_compatible(list_selector.clone())
+            {
+                Ok(()) => {}
+                Err(AllowedLibfuncsError::UnsupportedLibfunc {
+                    invalid_libfunc,
+                    allowed_libfuncs_list_name,
+                }) => {
+                    violations.push((qualified_path, invalid_libfunc, allowed_libfuncs_list_name))
+                }
+                Err(e) => {
+                    return Err(e).with_context(|| {
+                        format!("Failed to check allowed libfuncs for contract `{qualified_path}`.")
+                    });
+                }
+            }
+        }
+
+        match self.mode {
+            LibfuncsMode::Warn => {
+                for (qualified_path, invalid_libfunc, list_name) in violations {
+                    let diagnostic = format! {r#"
+                    Contract `{qualified_path}` includes `{invalid_libfunc}` function that is not allowed in the `{list_name}` libfuncs list.
+                    It will work on Katana, but don't forget to remove it before deploying on a public Starknet network.
+                "#};
+
+                    messages::emit(
+                        ui,
+                        DiagnosticMessage::warning(
+                            diagnostic_codes::UNSUPPORTED_LIBFUNC.code,
+                            diagnostic,
+                        ),
+                    );
+                }
+            }
+            LibfuncsMode::Deny if !violations.is_empty() => {
+                let violations = violations
+                    .iter()
+                    .map(|(qualified_path, invalid_libfunc, list_name)| {
+                        format!(
+                            "  `{qualified_path}` uses `{invalid_libfunc}` (not in `{list_name}`)"
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n");
+
+                anyhow::bail!("Contracts use libfuncs that are not allowed:\n{violations}");
+            }
+            LibfuncsMode::Deny => {}
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::rc::Rc;
+
+    use cairo_lang_sierra::ProgramParser;
+    use cairo_lang_starknet_classes::contract_class::{ContractClass, ContractEntryPoints};
+    use cairo_lang_utils::ordered_hash_map::OrderedHashMap;
+    use scarb::core::Config;
+    use scarb_ui::{OutputFormat, Verbosity};
+    use starknet::core::types::Felt;
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::compiler::artifact_manager::CompiledArtifact;
+
+    /// Builds a contract class whose program only declares the given libfuncs.
+    fn contract_class(libfuncs: &[&str]) -> ContractClass {
+        let declarations = libfuncs
+            .iter()
+            .enumerate()
+            .map(|(id, libfunc)| format!("libfunc [{id}] = {libfunc};\n"))
+            .collect::<String>();
+
+        let program = ProgramParser::new()
+            .parse(&format!("type [0] = felt252;\n{declarations}"))
+            .unwrap();
+
+        ContractClass::new(
+            &program,
+            ContractEntryPoints::default(),
+            None,
+            OrderedHashMap::default(),
+        )
+        .unwrap()
+    }
+
+    /// Checks a contract declaring the given libfuncs against the libfuncs config, in a
+    /// package providing a custom `libfuncs.json` list which only allows `store_temp`.
+    fn check(config: &str, libfuncs: &[&str]) -> Result<()> {
+        let temp_dir = TempDir::new().unwrap();
+        let package_root = Utf8PathBuf::from_path_buf(temp_dir.path().to_path_buf()).unwrap();
+
+        fs::write(
+            package_root.join("Scarb.toml"),
+            "[package]\nname = \"test_package\"\nversion = \"0.1.0\"\n",
+        )
+        .unwrap();
+        fs::write(
+            package_root.join("libfuncs.json"),
+            r#"{ "allowed_libfuncs": ["store_temp"] }"#,
+        )
+        .unwrap();
+
+        let scarb_config = Config::builder(package_root.join("Scarb.toml"))
+            .build()
+            .unwrap();
+        let workspace =
+            scarb::ops::read_workspace(scarb_config.manifest_path(), &scarb_config).unwrap();
+        let package = workspace.members().next().unwrap();
+
+        let mut artifacts = ArtifactManager::new(&workspace, &package);
+        artifacts.add_artifact(
+            "game::systems::actions".to_string(),
+            CompiledArtifact {
+                class_hash: Felt::ZERO,
+                contract_class: Rc::new(contract_class(libfuncs)),
+                debug_info: None,
+                compiled_class_hash: None,
+                casm_class: None,
+                size: None,
+            },
+        );
+
+        let config: LibfuncsConfig = toml::from_str(config).unwrap();
+        let ui = Ui::new(Verbosity::Quiet, OutputFormat::Text);
+
+        config.check(&artifacts, &package_root, &ui)
+    }
+
+    #[test]
+    fn test_libfuncs_config_default() {
+        let config: LibfuncsConfig = toml::from_str("").unwrap();
+
+        assert_eq!(config.list, LibfuncsList::Default);
+        assert_eq!(config.mode, LibfuncsMode::Warn);
+        assert!(matches!(
+            config.list_selector(Utf8Path::new("/pkg")),
+            ListSelector::DefaultList
+        ));
+    }
+
+    #[test]
+    fn test_libfuncs_config_named_list() {
+        let config: LibfuncsConfig = toml::from_str(
+            r#"
+list = "experimental"
+mode = "deny"
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(config.mode, LibfuncsMode::Deny);
+        assert!(matches!(
+            config.list_selector(Utf8Path::new("/pkg")),
+            ListSelector::ListName(name) if name == BUILTIN_EXPERIMENTAL_LIBFUNCS_LIST
+        ));
+    }
+
+    #[test]
+    fn test_libfuncs_config_file() {
+        let config: LibfuncsConfig = toml::from_str(
+            r#"
+list = { file = "libfuncs.json" }
+"#,
+        )
+        .unwrap();
+
+        assert!(matches!(
+            config.list_selector(Utf8Path::new("/pkg")),
+            ListSelector::ListFile(path) if path == "/pkg/libfuncs.json"
+        ));
+    }
+
+    #[test]
+    fn test_libfuncs_config_invalid_list() {
+        assert!(toml::from_str::<LibfuncsConfig>(r#"list = "unknown""#).is_err());
+    }
+
+    #[test]
+    fn test_check_warn() {
+        // `redeposit_gas` is only in the experimental list.
+        assert!(check(r#"list = "audited""#, &["redeposit_gas"]).is_ok());
+    }
+
+    #[test]
+    fn test_check_deny() {
+        let err = check(
+            r#"
+list = "audited"
+mode = "deny"
+"#,
+            &["store_temp<[0]>", "redeposit_gas"],
+        )
+        .unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "Contracts use libfuncs that are not allowed:\n  `game::systems::actions` uses \
+             `redeposit_gas` (not in `audited`)"
+        );
+
+        assert!(check(
+            r#"
+list = "experimental"
+mode = "deny"
+"#,
+            &["store_temp<[0]>", "redeposit_gas"],
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn test_check_file() {
+        let config = r#"
+list = { file = "libfuncs.json" }
+mode = "deny"
+"#;
+
+        assert!(check(config, &["store_temp<[0]>"]).is_ok());
+
+        let err = check(config, &["store_temp<[0]>", "redeposit_gas"]).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("`game::systems::actions` uses `redeposit_gas`"));
+        assert!(err.to_string().contains("libfuncs.json`)"));
+    }
+
+    #[test]
+    fn test_check_missing_file() {
+        let err = check(r#"list = { file = "missing.json" }"#, &["store_temp<[0]>"]).unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "Failed to check allowed libfuncs for contract `game::systems::actions`."
+        );
+    }
+}
@@ -0,0 +1,320 @@
+//! Build lock of the Dojo resources.
+//!
+//! The build lock records the class hash of each resource compiled for a profile,
+//! along with the versions of the compiler. Committing the lock file next to the
+//! `Scarb.toml` manifest allows to build with `--locked` to ensure that the classes
+//! being deployed are the exact same as the ones recorded in the lock.
+
+use std::fmt;
+
+use anyhow::{Context, Result};
+use camino::{Utf8Path, Utf8PathBuf};
+use serde::{Deserialize, Serialize};
+use starknet::core::types::Felt;
+
+use super::artifact_manager::ArtifactManager;
+use super::version::CompilerVersion;
+
+/// A resource recorded in the build lock.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockedResource {
+    /// The tag of the resource, `None` for the starknet contracts without namespace.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+    pub qualified_path: String,
+    pub class_hash: Felt,
+}
+
+impl fmt::Display for LockedResource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.tag {
+            Some(tag) => write!(f, "{} ({})", tag, self.qualified_path),
+            None => write!(f, "{}", self.qualified_path),
+        }
+    }
+}
+
+/// The build lock of a package for a given profile.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BuildLock {
+    pub version: CompilerVersion,
+    /// The resources, sorted by qualified path.
+    #[serde(default, rename = "resource")]
+    pub resources: Vec<LockedResource>,
+}
+
+/// A difference between a build lock and a fresh build, for a single resource.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LockDiff {
+    Added(LockedResource),
+    Removed(LockedResource),
+    Changed {
+        locked: LockedResource,
+        built: LockedResource,
+    },
+}
+
+impl fmt::Display for LockDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LockDiff::Added(r) => write!(f, "+ {r}: {:#x}", r.class_hash),
+            LockDiff::Removed(r) => write!(f, "- {r}: {:#x}", r.class_hash),
+            LockDiff::Changed { locked, built } => write!(
+                f,
+                "~ {built}: {:#x} -> {:#x}",
+                locked.class_hash, built.class_hash
+            ),
+        }
+    }
+}
+
+impl BuildLock {
+    /// Builds the lock from the artifacts and the dojo annotation of the artifact manager.
+    pub fn from_artifacts(artifacts: &ArtifactManager<'_>) -> Result<Self> {
+        let annotation = artifacts.dojo_annotation();
+
+        let tagged = std::iter::once((
+            Some(annotation.world.tag.clone()),
+            &annotation.world.qualified_path,
+        ))
+        .chain(
+            annotation
+                .contracts
+                .iter()
+                .map(|c| (Some(c.tag.clone()), &c.qualified_path)),
+        )
+        .chain(
+            annotation
+                .models
+                .iter()
+                .map(|m| (Some(m.tag.clone()), &m.qualified_path)),
+        )
+        .chain(
+            annotation
+                .events
+                .iter()
+                .map(|e| (Some(e.tag.clone()), &e.qualified_path)),
+        )
+        .chain(
+            annotation
+                .sn_contracts
+                .iter()
+                .map(|c| (c.tag.clone(), &c.qualified_path)),
+        );
+
+        let mut resources = tagged
+            .map(|(tag, qualified_path)| {
+                Ok(LockedResource {
+                    tag,
+                    qualified_path: qualified_path.clone(),
+                    class_hash: artifacts.get_class_hash(qualified_path)?,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        resources.sort_by(|a, b| a.qualified_path.cmp(&b.qualified_path));
+
+        Ok(Self {
+            version: CompilerVersion::current(),
+            resources,
+        })
+    }
+
+    /// Returns the path of the lock file for the given package manifest and profile.
+    pub fn path(manifest_path: &Utf8Path, profile: &str) -> Utf8PathBuf {
+        manifest_path
+            .parent()
+            .expect("Manifest path must have a parent directory.")
+            .join(format!("dojo_{profile}.lock"))
+    }
+
+    /// Reads the lock file at the given path, if it exists.
+    pub fn read(path: &Utf8Path) -> Result<Option<Self>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read lock file `{path}`."))?;
+
+        let lock = toml::from_str(&content)
+            .with_context(|| format!("Failed to parse lock file `{path}`."))?;
+
+        Ok(Some(lock))
+    }
+
+    /// Writes the lock file at the given path.
+    pub fn write(&self, path: &Utf8Path) -> Result<()> {
+        let content = toml::to_string(&self)?;
+
+        std::fs::write(path, content)
+            .with_context(|| format!("Failed to write lock file `{path}`."))
+    }
+
+    /// Computes the differences of the resources between this lock and a fresh build.
+    ///
+    /// Resources are matched by qualified path, and the differences are sorted by
+    /// qualified path.
+    pub fn diff(&self, built: &BuildLock) -> Vec<LockDiff> {
+        let mut diffs = vec![];
+
+        for locked in &self.resources {
+            match built
+                .resources
+                .iter()
+                .find(|r| r.qualified_path == locked.qualified_path)
+            {
+                Some(r) if r.class_hash != locked.class_hash || r.tag != locked.tag => {
+                    diffs.push(LockDiff::Changed {
+                        locked: locked.clone(),
+                        built: r.clone(),
+                    });
+                }
+                Some(_) => {}
+                None => diffs.push(LockDiff::Removed(locked.clone())),
+            }
+        }
+
+        for r in &built.resources {
+            if !self
+                .resources
+                .iter()
+                .any(|locked| locked.qualified_path == r.qualified_path)
+            {
+                diffs.push(LockDiff::Added(r.clone()));
+            }
+        }
+
+        diffs.sort_by(|a, b| a.qualified_path().cmp(b.qualified_path()));
+        diffs
+    }
+}
+
+impl LockDiff {
+    /// Returns the qualified path of the resource concerned by the difference.
+    pub fn qualified_path(&self) -> &str {
+        match self {
+            LockDiff::Added(r) | LockDiff::Removed(r) => &r.qualified_path,
+            LockDiff::Changed { built, .. } => &built.qualified_path,
+        }
+    }
+}
+
+/// Verifies that the freshly built resources match the lock file.
+///
+/// # Arguments
+///
+/// * `lock_path` - The path to the lock file.
+/// * `built` - The lock computed from the fresh build.
+///
+/// # Returns
+///
+/// The warnings to display if the lock matches but was produced by another compiler version,
+/// or an error listing the differences if the lock doesn't match.
+pub fn verify_locked(lock_path: &Utf8Path, built: &BuildLock) -> Result<Vec<String>> {
+    let Some(locked) = BuildLock::read(lock_path)? else {
+        anyhow::bail!(
+            "Lock file `{lock_path}` not found, build without `--locked` to generate it."
+        );
+    };
+
+    let diffs = locked.diff(built);
+
+    if !diffs.is_empty() {
+        let diffs = diffs
+            .iter()
+            .map(|d| format!("  {d}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        anyhow::bail!(
+            "Class hashes differ from the lock file `{lock_path}`:\n{diffs}\nBuild without \
+             `--locked` to update the lock file."
+        );
+    }
+
+    let mut warnings = vec![];
+
+    if locked.version != built.version {
+        warnings.push(format!(
+            "Lock file `{lock_path}` was generated with another compiler version:\n{}\ncurrent \
+             version:\n{}",
+            locked.version, built.version
+        ));
+    }
+
+    Ok(warnings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resource(tag: &str, qualified_path: &str, class_hash: u64) -> LockedResource {
+        LockedResource {
+            tag: Some(tag.to_string()),
+            qualified_path: qualified_path.to_string(),
+            class_hash: Felt::from(class_hash),
+        }
+    }
+
+    fn lock(resources: Vec<LockedResource>) -> BuildLock {
+        BuildLock {
+            version: CompilerVersion {
+                dojo: "1.0.0".to_string(),
+                scarb: "2.8.4".to_string(),
+                cairo: "2.8.4".to_string(),
+                sierra: "1.6.0".to_string(),
+            },
+            resources,
+        }
+    }
+
+    #[test]
+    fn test_diff_same() {
+        let locked = lock(vec![resource("ns-c1", "pkg::c1", 1)]);
+        assert!(locked.diff(&locked.clone()).is_empty());
+    }
+
+    #[test]
+    fn test_diff() {
+        let locked = lock(vec![
+            resource("ns-c1", "pkg::c1", 1),
+            resource("ns-c2", "pkg::c2", 2),
+            resource("ns-M", "pkg::m", 3),
+        ]);
+
+        let built = lock(vec![
+            resource("ns-c1", "pkg::c1", 1),
+            resource("ns-c2", "pkg::c2", 22),
+            resource("ns-E", "pkg::e", 4),
+        ]);
+
+        assert_eq!(
+            locked.diff(&built),
+            vec![
+                LockDiff::Changed {
+                    locked: resource("ns-c2", "pkg::c2", 2),
+                    built: resource("ns-c2", "pkg::c2", 22),
+                },
+                LockDiff::Added(resource("ns-E", "pkg::e", 4)),
+                LockDiff::Removed(resource("ns-M", "pkg::m", 3)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lock_serde() {
+        let locked = lock(vec![
+            resource("ns-c1", "pkg::c1", 1),
+            LockedResource {
+                tag: None,
+                qualified_path: "pkg::erc20".to_string(),
+                class_hash: Felt::from(2),
+            },
+        ]);
+
+        let content = toml::to_string(&locked).unwrap();
+        assert_eq!(toml::from_str::<BuildLock>(&content).unwrap(), locked);
+    }
+}
@@ -0,0 +1,403 @@
+//! Build manifest of the Dojo resources.
+//!
+//! The build manifest is a local and deterministic description of the resources
+//! of a build. Contrary to the manifests generated during a migration, it doesn't
+//! contain any chain related information (like addresses), which makes it suitable
+//! to be committed and diffed in code review.
+
+use std::collections::BTreeMap;
+use std::io::Write;
+
+use anyhow::{Context, Result};
+use cairo_lang_starknet_classes::abi::Contract as ContractAbi;
+use dojo_types::naming;
+use dojo_types::system::Dependency;
+use scarb::core::{Package, Workspace};
+use serde::{Deserialize, Serialize};
+use starknet::core::types::Felt;
+
+use crate::scarb_extensions::WorkspaceExt;
+
+use super::annotation::{DojoAnnotation, Member};
+use super::artifact_manager::ArtifactManager;
+
+const BUILD_MANIFEST_FILE_NAME: &str = "manifest";
+
+/// Represents the world contract in the build manifest.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WorldManifest {
+    pub tag: String,
+    pub qualified_path: String,
+    pub class_hash: Felt,
+    pub selector: Felt,
+    pub abi: Option<ContractAbi>,
+}
+
+/// Represents a dojo contract in the build manifest.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ContractManifest {
+    pub tag: String,
+    pub qualified_path: String,
+    pub class_hash: Felt,
+    pub selector: Felt,
+    pub abi: Option<ContractAbi>,
+    pub systems: Vec<String>,
+    /// The models read and written by each system.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub dependencies: BTreeMap<String, Vec<Dependency>>,
+}
+
+/// Represents a dojo model or event in the build manifest.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StructResourceManifest {
+    pub tag: String,
+    pub qualified_path: String,
+    pub class_hash: Felt,
+    pub selector: Felt,
+    pub abi: Option<ContractAbi>,
+    pub members: Vec<Member>,
+}
+
+/// Represents a starknet contract in the build manifest.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StarknetContractManifest {
+    pub name: String,
+    /// The tag of the contract, if a namespace mapping assigned it a namespace.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+    /// The selector of the tag, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub selector: Option<Felt>,
+    pub qualified_path: String,
+    pub class_hash: Felt,
+    pub abi: Option<ContractAbi>,
+}
+
+/// The build manifest, listing all the resources of a build sorted by tag
+/// (or by name for starknet contracts).
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BuildManifest {
+    pub world: WorldManifest,
+    pub contracts: Vec<ContractManifest>,
+    pub models: Vec<StructResourceManifest>,
+    pub events: Vec<StructResourceManifest>,
+    pub sn_contracts: Vec<StarknetContractManifest>,
+}
+
+impl BuildManifest {
+    /// Builds the manifest from the artifacts and the dojo annotation of the
+    /// artifact manager.
+    pub fn from_artifacts(artifacts: &ArtifactManager<'_>) -> Result<Self> {
+        Self::new(artifacts.dojo_annotation(), |qualified_path| {
+            class_info(artifacts, qualified_path)
+        })
+    }
+
+    /// Builds the manifest from a dojo annotation.
+    ///
+    /// # Arguments
+    ///
+    /// * `annotation` - The dojo annotation of the resources.
+    /// * `class_info` - Returns the class hash and the ABI of the class at a qualified path.
+    pub fn new(
+        annotation: &DojoAnnotation,
+        class_info: impl Fn(&str) -> Result<(Felt, Option<ContractAbi>)>,
+    ) -> Result<Self> {
+        let (class_hash, abi) = class_info(&annotation.world.qualified_path)?;
+        let world = WorldManifest {
+            tag: annotation.world.tag.clone(),
+            qualified_path: annotation.world.qualified_path.clone(),
+            class_hash,
+            selector: naming::compute_selector_from_tag(&annotation.world.tag),
+            abi,
+        };
+
+        let mut contracts = annotation
+            .contracts
+            .iter()
+            .map(|c| {
+                let (class_hash, abi) = class_info(&c.qualified_path)?;
+
+                Ok(ContractManifest {
+                    tag: c.tag.clone(),
+                    qualified_path: c.qualified_path.clone(),
+                    class_hash,
+                    selector: naming::compute_selector_from_tag(&c.tag),
+                    abi,
+                    systems: c.systems.clone(),
+                    dependencies: c.dependencies.clone(),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut models = annotation
+            .models
+            .iter()
+            .map(|m| {
+                let (class_hash, abi) = class_info(&m.qualified_path)?;
+                Ok(struct_resource(
+                    &m.tag,
+                    &m.qualified_path,
+                    m.selector,
+                    class_hash,
+                    abi,
+                    &m.members,
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut events = annotation
+            .events
+            .iter()
+            .map(|e| {
+                let (class_hash, abi) = class_info(&e.qualified_path)?;
+                Ok(struct_resource(
+                    &e.tag,
+                    &e.qualified_path,
+                    e.selector,
+                    class_hash,
+                    abi,
+                    &e.members,
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut sn_contracts = annotation
+            .sn_contracts
+            .iter()
+            .map(|c| {
+                let (class_hash, abi) = class_info(&c.qualified_path)?;
+
+                Ok(StarknetContractManifest {
+                    name: c.name.clone(),
+                    tag: c.tag.clone(),
+                    selector: c.tag.as_deref().map(naming::compute_selector_from_tag),
+                    qualified_path: c.qualified_path.clone(),
+                    class_hash,
+                    abi,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        contracts.sort_by(|a, b| a.tag.cmp(&b.tag));
+        models.sort_by(|a, b| a.tag.cmp(&b.tag));
+        events.sort_by(|a, b| a.tag.cmp(&b.tag));
+        sn_contracts.sort_by(|a, b| {
+            a.name
+                .cmp(&b.name)
+                .then_with(|| a.qualified_path.cmp(&b.qualified_path))
+        });
+
+        Ok(Self {
+            world,
+            contracts,
+            models,
+            events,
+            sn_contracts,
+        })
+    }
+
+    /// Writes the manifest to the target directory of the provided dojo package,
+    /// for the current profile.
+    ///
+    /// # Arguments
+    ///
+    /// * `workspace` - The workspace to write the manifest to.
+    /// * `package` - The dojo package of the artifacts.
+    pub fn write(&self, workspace: &Workspace<'_>, package: &Package) -> Result<()> {
+        let target_dir = workspace.dojo_target_dir(package);
+
+        let mut file = target_dir.create_rw(
+            format!("{}.json", BUILD_MANIFEST_FILE_NAME),
+            "Dojo build manifest",
+            workspace.config(),
+        )?;
+
+        let content = serde_json::to_string_pretty(&self)?;
+        file.write_all(content.as_bytes())?;
+
+        Ok(())
+    }
+}
+
+/// Returns the class hash and the ABI of the artifact at the given qualified path.
+fn class_info(
+    artifacts: &ArtifactManager<'_>,
+    qualified_path: &str,
+) -> Result<(Felt, Option<ContractAbi>)> {
+    let artifact = artifacts
+        .get_artifact(qualified_path)
+        .with_context(|| format!("Artifact for `{qualified_path}` not found."))?;
+
+    Ok((artifact.class_hash, artifact.contract_class.abi.clone()))
+}
+
+/// Builds the manifest of a model or an event.
+///
+/// The selector is the one of the annotation, as models of version 0 don't use the selector
+/// of their tag.
+fn struct_resource(
+    tag: &str,
+    qualified_path: &str,
+    selector: Felt,
+    class_hash: Felt,
+    abi: Option<ContractAbi>,
+    members: &[Member],
+) -> StructResourceManifest {
+    StructResourceManifest {
+        tag: tag.to_string(),
+        qualified_path: qualified_path.to_string(),
+        class_hash,
+        selector,
+        abi,
+        members: members.to_vec(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use starknet::core::utils::cairo_short_string_to_felt;
+
+    use super::*;
+    use crate::compiler::annotation::{
+        ContractAnnotation, EventAnnotation, ModelAnnotation, StarknetContractAnnotation,
+    };
+
+    fn annotation() -> DojoAnnotation {
+        DojoAnnotation {
+            contracts: vec![
+                ContractAnnotation {
+                    qualified_path: "pkg::c2".to_string(),
+                    tag: "ns-c2".to_string(),
+                    ..Default::default()
+                },
+                ContractAnnotation {
+                    qualified_path: "pkg::c1".to_string(),
+                    tag: "ns-c1".to_string(),
+                    systems: vec!["spawn".to_string()],
+                    ..Default::default()
+                },
+            ],
+            models: vec![
+                ModelAnnotation {
+                    qualified_path: "pkg::position".to_string(),
+                    tag: "ns-Position".to_string(),
+                    selector: naming::compute_selector_from_tag("ns-Position"),
+                    version: 1,
+                    ..Default::default()
+                },
+                ModelAnnotation {
+                    qualified_path: "pkg::legacy".to_string(),
+                    tag: "ns-Legacy".to_string(),
+                    selector: cairo_short_string_to_felt("Legacy").unwrap(),
+                    version: 0,
+                    ..Default::default()
+                },
+            ],
+            events: vec![EventAnnotation {
+                qualified_path: "pkg::moved".to_string(),
+                tag: "ns-Moved".to_string(),
+                selector: naming::compute_selector_from_tag("ns-Moved"),
+                version: 1,
+                ..Default::default()
+            }],
+            sn_contracts: vec![
+                StarknetContractAnnotation {
+                    qualified_path: "pkg::token::erc20".to_string(),
+                    name: "erc20".to_string(),
+                    ..Default::default()
+                },
+                StarknetContractAnnotation {
+                    qualified_path: "pkg::account".to_string(),
+                    name: "account".to_string(),
+                    tag: Some("ns-account".to_string()),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        }
+    }
+
+    /// A class hash derived from the qualified path, to check the classes are matched.
+    fn class_info(qualified_path: &str) -> Result<(Felt, Option<ContractAbi>)> {
+        Ok((naming::compute_bytearray_hash(qualified_path), None))
+    }
+
+    #[test]
+    fn test_build_manifest_order() {
+        let manifest = BuildManifest::new(&annotation(), class_info).unwrap();
+
+        let tags = |resources: &[StructResourceManifest]| {
+            resources.iter().map(|r| r.tag.clone()).collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            manifest
+                .contracts
+                .iter()
+                .map(|c| &c.tag)
+                .collect::<Vec<_>>(),
+            vec!["ns-c1", "ns-c2"]
+        );
+        assert_eq!(tags(&manifest.models), vec!["ns-Legacy", "ns-Position"]);
+        assert_eq!(tags(&manifest.events), vec!["ns-Moved"]);
+        assert_eq!(
+            manifest
+                .sn_contracts
+                .iter()
+                .map(|c| &c.name)
+                .collect::<Vec<_>>(),
+            vec!["account", "erc20"]
+        );
+
+        assert_eq!(
+            manifest.contracts[0].class_hash,
+            naming::compute_bytearray_hash("pkg::c1")
+        );
+        assert_eq!(manifest.contracts[0].systems, vec!["spawn".to_string()]);
+        assert_eq!(
+            manifest.world.selector,
+            naming::compute_selector_from_tag(crate::WORLD_CONTRACT_TAG)
+        );
+        assert_eq!(
+            manifest.sn_contracts[0].selector,
+            Some(naming::compute_selector_from_tag("ns-account"))
+        );
+        assert_eq!(manifest.sn_contracts[1].selector, None);
+    }
+
+    #[test]
+    fn test_build_manifest_deterministic() {
+        let mut reversed = annotation();
+        reversed.contracts.reverse();
+        reversed.models.reverse();
+        reversed.sn_contracts.reverse();
+
+        let manifest = BuildManifest::new(&annotation(), class_info).unwrap();
+        let reversed = BuildManifest::new(&reversed, class_info).unwrap();
+
+        assert_eq!(
+            serde_json::to_string_pretty(&manifest).unwrap(),
+            serde_json::to_string_pretty(&reversed).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_build_manifest_model_version_0_selector() {
+        let manifest = BuildManifest::new(&annotation(), class_info).unwrap();
+
+        // Models of version 0 use their name as selector, not the selector of their tag.
+        assert_eq!(
+            manifest.models[0].selector,
+            cairo_short_string_to_felt("Legacy").unwrap()
+        );
+        assert_ne!(
+            manifest.models[0].selector,
+            naming::compute_selector_from_tag("ns-Legacy")
+        );
+        assert_eq!(
+            manifest.models[1].selector,
+            naming::compute_selector_from_tag("ns-Position")
+        );
+    }
+}
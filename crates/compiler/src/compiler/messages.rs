@@ -0,0 +1,320 @@
+//! Messages of the compiler, structured for the JSON output mode.
+//!
+//! In the text mode, the messages are printed as usual through the [`Ui`], the diagnostics
+//! being prefixed with their code. In the JSON mode, each message is printed as a JSON line,
+//! tagged with its `type`:
+//!
+//! ```json
+//! {"type":"diagnostic","code":"DOJO103","severity":"warning","message":"...","file":"src/actions.cairo","span":{"line":12,"column":9}}
+//! {"type":"artifact","kind":"sierra","path":"target/dev/dojo_examples/contracts/ns-actions.json","qualified_path":"ns::actions::actions"}
+//! {"type":"status","status":"written","message":"coverage written to target/coverage/lcov.info (12/15 lines)","path":"target/coverage/lcov.info"}
+//! ```
+//!
+//! The codes of the diagnostics are stable, to be used by editors and CI annotators. They are
+//! documented in the [`crate::diagnostic_codes`] registry.
+
+use cairo_lang_compiler::diagnostics::DiagnosticsReporter;
+use cairo_lang_diagnostics::Severity as CairoSeverity;
+use cairo_lang_filesystem::ids::CrateId;
+use scarb_ui::{Message, OutputFormat, Ui};
+use serde::{Serialize, Serializer};
+
+use crate::diagnostic_codes;
+
+/// A Cairo diagnostic without error code, neither from the Dojo plugin.
+pub const CAIRO_DIAGNOSTIC: &str = "CAIRO";
+
+/// Prefix of the location lines in the diagnostics.
+const LOCATION_PREFIX: &str = "--> ";
+
+/// The severity of a diagnostic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// A position in a file, 1 based.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Span {
+    pub line: usize,
+    pub column: usize,
+}
+
+/// A diagnostic of the compiler, or of the Cairo compiler.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename = "diagnostic")]
+pub struct DiagnosticMessage {
+    pub code: String,
+    pub severity: Severity,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub span: Option<Span>,
+}
+
+impl DiagnosticMessage {
+    /// Creates a diagnostic, the file and the span being taken from the first
+    /// ` --> file:line:column` line of the message, if any.
+    pub fn new(code: &str, severity: Severity, message: impl Into<String>) -> Self {
+        let message = message.into();
+        let (file, span) = parse_location(&message).unzip();
+
+        Self {
+            code: code.to_string(),
+            severity,
+            message,
+            file,
+            span,
+        }
+    }
+
+    pub fn warning(code: &str, message: impl Into<String>) -> Self {
+        Self::new(code, Severity::Warning, message)
+    }
+
+    pub fn error(code: &str, message: impl Into<String>) -> Self {
+        Self::new(code, Severity::Error, message)
+    }
+}
+
+impl Message for DiagnosticMessage {
+    /// The message, prefixed with its Dojo code like the diagnostics of the plugin, which
+    /// already carry it.
+    fn text(self) -> String {
+        match diagnostic_codes::find(&self.code) {
+            Some(code) if diagnostic_codes::code_of(&self.message).is_none() => {
+                code.message(self.message)
+            }
+            _ => self.message,
+        }
+    }
+
+    fn structured<S: Serializer>(self, ser: S) -> Result<S::Ok, S::Error> {
+        self.serialize(ser)
+    }
+}
+
+/// An artifact written by the compiler.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename = "artifact")]
+pub struct ArtifactMessage {
+    /// The kind of artifact: `sierra`, `casm` or `debug`.
+    pub kind: String,
+    pub path: String,
+    pub qualified_path: String,
+}
+
+impl Message for ArtifactMessage {
+    fn text(self) -> String {
+        format!("wrote {} ({})", self.path, self.qualified_path)
+    }
+
+    fn structured<S: Serializer>(self, ser: S) -> Result<S::Ok, S::Error> {
+        self.serialize(ser)
+    }
+}
+
+/// A step or an outcome of a command, which is neither a diagnostic nor an artifact.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename = "status")]
+pub struct StatusMessage {
+    /// The kind of status, like `testing`, `written` or `removed`.
+    pub status: String,
+    pub message: String,
+    /// The file the status is about, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+}
+
+impl StatusMessage {
+    pub fn new(status: &str, message: impl Into<String>) -> Self {
+        Self {
+            status: status.to_string(),
+            message: message.into(),
+            path: None,
+        }
+    }
+
+    /// Sets the file the status is about.
+    pub fn with_path(mut self, path: impl ToString) -> Self {
+        self.path = Some(path.to_string());
+        self
+    }
+}
+
+impl Message for StatusMessage {
+    fn text(self) -> String {
+        self.message
+    }
+
+    fn structured<S: Serializer>(self, ser: S) -> Result<S::Ok, S::Error> {
+        self.serialize(ser)
+    }
+}
+
+/// Checks if the UI prints the messages as JSON lines.
+pub fn is_json(ui: &Ui) -> bool {
+    matches!(ui.output_format(), OutputFormat::Json)
+}
+
+/// Emits a diagnostic, as a JSON line in the JSON mode, or as a warning or an error otherwise.
+pub fn emit(ui: &Ui, diagnostic: DiagnosticMessage) {
+    if is_json(ui) {
+        ui.print(diagnostic);
+        return;
+    }
+
+    match diagnostic.severity {
+        Severity::Warning => ui.warn(diagnostic.text()),
+        Severity::Error => ui.error(diagnostic.text()),
+    }
+}
+
+/// Emits an artifact written to the filesystem, only in the JSON mode.
+pub fn emit_artifact(ui: &Ui, artifact: ArtifactMessage) {
+    if is_json(ui) {
+        ui.print(artifact);
+    } else {
+        ui.verbose(artifact);
+    }
+}
+
+/// Returns a reporter emitting the Cairo diagnostics as [`DiagnosticMessage`].
+///
+/// # Arguments
+///
+/// * `ui` - The UI to emit the diagnostics to.
+/// * `ignore_warnings_crate_ids` - The crates whose warnings are not reported.
+/// * `allow_warnings` - Whether the warnings don't fail the compilation.
+pub fn diagnostics_reporter<'a>(
+    ui: Ui,
+    ignore_warnings_crate_ids: &[CrateId],
+    allow_warnings: bool,
+) -> DiagnosticsReporter<'a> {
+    let reporter = DiagnosticsReporter::callback(move |entry| {
+        let message = entry.message().trim_end().to_string();
+
+        if message.is_empty() {
+            return;
+        }
+
+        let severity = match entry.severity() {
+            CairoSeverity::Error => Severity::Error,
+            CairoSeverity::Warning => Severity::Warning,
+        };
+
+        // The Dojo plugin diagnostics carry their code in the message.
+        let code = match entry.error_code() {
+            Some(code) => code.as_str().to_string(),
+            None => diagnostic_codes::code_of(&message)
+                .map_or(CAIRO_DIAGNOSTIC, |c| c.code)
+                .to_string(),
+        };
+
+        emit(&ui, DiagnosticMessage::new(&code, severity, message));
+    })
+    .with_ignore_warnings_crates(ignore_warnings_crate_ids);
+
+    if allow_warnings {
+        reporter.allow_warnings()
+    } else {
+        reporter
+    }
+}
+
+/// Parses the first ` --> file:line:column` line of a message.
+fn parse_location(message: &str) -> Option<(String, Span)> {
+    message.lines().find_map(|line| {
+        let location = line.trim_start().strip_prefix(LOCATION_PREFIX)?;
+        let mut parts = location.trim_end().rsplitn(3, ':');
+
+        let column = parts.next()?.parse().ok()?;
+        let line = parts.next()?.parse().ok()?;
+        let file = parts.next()?.to_string();
+
+        Some((file, Span { line, column }))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diagnostic_location() {
+        let diagnostic = DiagnosticMessage::warning(
+            diagnostic_codes::MISSING_WRITER_PERMISSION.code,
+            "Contract `ns-actions` writes model `ns-Position` without writer permission.\n --> \
+             src/actions.cairo:12:9",
+        );
+
+        assert_eq!(diagnostic.file.as_deref(), Some("src/actions.cairo"));
+        assert_eq!(
+            diagnostic.span,
+            Some(Span {
+                line: 12,
+                column: 9
+            })
+        );
+
+        let diagnostic = DiagnosticMessage::error(
+            diagnostic_codes::COMMAND_FAILED.code,
+            "No location --> here",
+        );
+        assert_eq!(diagnostic.file, None);
+        assert_eq!(diagnostic.span, None);
+    }
+
+    #[test]
+    fn test_diagnostic_json() {
+        let diagnostic = DiagnosticMessage::warning(
+            diagnostic_codes::CONTRACT_NOT_FOUND.code,
+            "No contract found for path `pkg::*`.",
+        );
+
+        assert_eq!(
+            serde_json::to_string(&diagnostic).unwrap(),
+            r#"{"type":"diagnostic","code":"DOJO101","severity":"warning","message":"No contract found for path `pkg::*`."}"#
+        );
+    }
+
+    #[test]
+    fn test_diagnostic_text() {
+        let diagnostic = DiagnosticMessage::warning(
+            diagnostic_codes::BUDGET_EXCEEDED.code,
+            "Contract `ns-actions` exceeds its size budget.",
+        );
+        assert_eq!(
+            diagnostic.text(),
+            "[DOJO104] Contract `ns-actions` exceeds its size budget."
+        );
+
+        // The plugin diagnostics already carry their code.
+        let diagnostic = DiagnosticMessage::error(
+            diagnostic_codes::MODEL_WITHOUT_KEY.code,
+            "Plugin diagnostic: [DOJO006] Model must define at least one #[key] attribute",
+        );
+        assert_eq!(
+            diagnostic.text(),
+            "Plugin diagnostic: [DOJO006] Model must define at least one #[key] attribute"
+        );
+
+        let diagnostic = DiagnosticMessage::error(CAIRO_DIAGNOSTIC, "Missing semicolon.");
+        assert_eq!(diagnostic.text(), "Missing semicolon.");
+    }
+
+    #[test]
+    fn test_status_json() {
+        let status = StatusMessage::new("written", "gas snapshot written to gas_snapshot.toml")
+            .with_path("gas_snapshot.toml");
+
+        assert_eq!(
+            serde_json::to_string(&status).unwrap(),
+            r#"{"type":"status","status":"written","message":"gas snapshot written to gas_snapshot.toml","path":"gas_snapshot.toml"}"#
+        );
+        assert_eq!(status.text(), "gas snapshot written to gas_snapshot.toml");
+    }
+}
@@ -1,12 +1,24 @@
 pub mod annotation;
 pub mod artifact_manager;
+pub mod backtrace;
+pub mod build_cache;
 pub mod cairo_compiler;
 pub mod config;
 pub mod contract_selector;
+pub mod dependencies;
+pub mod introspect;
+pub mod libfuncs;
+pub mod lock;
+pub mod manifest;
+pub mod messages;
+pub mod permissions;
+pub mod profile_config;
 pub mod scarb_internal;
+pub mod size_report;
+pub mod toml_location;
 pub mod version;
 
-pub use cairo_compiler::DojoCompiler;
+pub use cairo_compiler::{DojoCompiler, DojoCompilerOptions};
 
 #[cfg(test)]
 pub mod test_utils;
@@ -0,0 +1,241 @@
+//! Writer permissions linter.
+//!
+//! A contract writing a model must be granted the writer (or owner) permission on the model
+//! or on its namespace, otherwise the transaction reverts with `not_writer`. The permissions
+//! are granted at migration from the `[writers]` and `[owners]` sections of the
+//! `dojo_<profile>.toml` file:
+//!
+//! ```toml
+//! [writers]
+//! # Resource tag or namespace = [contract tags].
+//! "ns" = ["ns-actions"]
+//! "ns-Position" = ["ns-other_actions"]
+//! ```
+//!
+//! The linter compares the models written by each contract, found by the static analysis
+//! of the [`super::dependencies`] module when computing the annotations, with those
+//! permissions.
+
+use std::collections::HashMap;
+
+use cairo_lang_compiler::db::RootDatabase;
+use cairo_lang_filesystem::db::get_originating_location;
+use cairo_lang_filesystem::ids::CrateId;
+use cairo_lang_syntax::node::ids::SyntaxStablePtrId;
+use dojo_types::naming;
+
+use super::annotation::DojoAnnotation;
+use super::profile_config::ProfileConfig;
+use crate::namespace_config::NamespaceConfig;
+use crate::CAIRO_PATH_SEPARATOR;
+
+/// Checks if the contract is granted the writer permission on the model.
+///
+/// Owners are writers too, and a permission on a namespace applies to all
+/// the resources of the namespace.
+///
+/// # Arguments
+///
+/// * `writers` - The contract tags by resource tag or namespace, from the `[writers]` section.
+/// * `owners` - The contract tags by resource tag or namespace, from the `[owners]` section.
+/// * `contract_tag` - The tag of the contract writing the model.
+/// * `model_tag` - The tag of the written model.
+pub fn can_write(
+    writers: &HashMap<String, Vec<String>>,
+    owners: &HashMap<String, Vec<String>>,
+    contract_tag: &str,
+    model_tag: &str,
+) -> bool {
+    let namespace = naming::get_namespace_from_tag(model_tag);
+
+    [model_tag, namespace.as_str()].iter().any(|resource| {
+        [writers, owners].iter().any(|grants| {
+            grants
+                .get(*resource)
+                .is_some_and(|contracts| contracts.iter().any(|c| c == contract_tag))
+        })
+    })
+}
+
+/// Returns the tag of a model from the Cairo path of its struct.
+///
+/// Models compiled with the contracts are found in the annotations. Other models
+/// (from dependencies) are resolved with the namespace configuration, as models
+/// declared without explicit namespace.
+///
+/// # Arguments
+///
+/// * `model_tags` - The model tags by Cairo path of the model struct.
+/// * `namespace_config` - The namespace configuration of the profile.
+/// * `model_path` - The Cairo path of the model struct.
+pub fn model_tag(
+    model_tags: &HashMap<String, String>,
+    namespace_config: &NamespaceConfig,
+    model_path: &str,
+) -> String {
+    if let Some(tag) = model_tags.get(model_path) {
+        return tag.clone();
+    }
+
+    let name = model_path
+        .rsplit(CAIRO_PATH_SEPARATOR)
+        .next()
+        .unwrap_or(model_path);
+
+    let namespace = namespace_config.get_mapping(&naming::get_tag(&namespace_config.default, name));
+
+    naming::get_tag(&namespace, name)
+}
+
+/// Returns the model tags by Cairo path of the model struct, for the models of the annotations.
+fn model_tags(annotation: &DojoAnnotation) -> HashMap<String, String> {
+    annotation
+        .models
+        .iter()
+        .filter_map(|m| {
+            // The model contract is declared alongside the model struct.
+            let (module_path, _) = m.qualified_path.rsplit_once(CAIRO_PATH_SEPARATOR)?;
+            let name = naming::get_name_from_tag(&m.tag);
+
+            Some((
+                format!("{module_path}{CAIRO_PATH_SEPARATOR}{name}"),
+                m.tag.clone(),
+            ))
+        })
+        .collect()
+}
+
+/// Checks that each model written by the dojo contracts is granted to the contract
+/// in the profile configuration.
+///
+/// # Returns
+///
+/// A warning for each write call without the writer permission, sorted by contract
+/// and model.
+///
+/// # Arguments
+///
+/// * `db` - The database the contracts were compiled from.
+/// * `crate_ids` - The crates of the contracts to check.
+/// * `annotation` - The dojo annotations of the compiled resources, with the model writes
+///   of the contracts.
+/// * `config` - The dojo configuration of the profile.
+pub fn check_writers(
+    db: &RootDatabase,
+    crate_ids: &[CrateId],
+    annotation: &DojoAnnotation,
+    config: &ProfileConfig,
+) -> Vec<String> {
+    let model_tags = model_tags(annotation);
+    let crate_names = crate_ids.iter().map(|id| id.name(db)).collect::<Vec<_>>();
+    let mut warnings = vec![];
+
+    let mut contracts = annotation
+        .contracts
+        .iter()
+        .filter(|c| {
+            c.qualified_path
+                .split(CAIRO_PATH_SEPARATOR)
+                .next()
+                .is_some_and(|crate_name| crate_names.iter().any(|n| n == crate_name))
+        })
+        .collect::<Vec<_>>();
+    contracts.sort_by(|a, b| a.qualified_path.cmp(&b.qualified_path));
+
+    for contract in contracts {
+        for (model_path, ptrs) in &contract.model_writes {
+            let tag = model_tag(&model_tags, &config.namespace, model_path);
+
+            if can_write(&config.writers, &config.owners, &contract.tag, &tag) {
+                continue;
+            }
+
+            let namespace = naming::get_namespace_from_tag(&tag);
+
+            for ptr in ptrs {
+                let location =
+                    location(db, *ptr).unwrap_or_else(|| contract.qualified_path.clone());
+
+                warnings.push(format!(
+                    "Contract `{}` writes model `{tag}` without writer permission. Grant it in \
+                     the `[writers]` section, on `{tag}` or `{namespace}`.\n --> {location}",
+                    contract.tag
+                ));
+            }
+        }
+    }
+
+    warnings
+}
+
+/// Returns the `file:line:column` location of a syntax node, in the user code
+/// if the node was generated by a plugin.
+fn location(db: &RootDatabase, ptr: SyntaxStablePtrId) -> Option<String> {
+    let syntax_node = ptr.lookup(db);
+    let span = syntax_node.span_without_trivia(db);
+    let (file_id, span) = get_originating_location(db, ptr.file_id(db), span);
+    let position = span.start.position_in_file(db, file_id)?;
+
+    Some(format!(
+        "{}:{}:{}",
+        file_id.full_path(db),
+        position.line + 1,
+        position.col + 1
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grants(entries: &[(&str, &str)]) -> HashMap<String, Vec<String>> {
+        let mut grants: HashMap<String, Vec<String>> = HashMap::new();
+
+        for (resource, contract) in entries {
+            grants
+                .entry(resource.to_string())
+                .or_default()
+                .push(contract.to_string());
+        }
+
+        grants
+    }
+
+    #[test]
+    fn test_can_write() {
+        let writers = grants(&[("ns", "ns-c1"), ("ns-M", "ns-c2")]);
+        let owners = grants(&[("other-M", "ns-c3")]);
+
+        assert!(can_write(&writers, &owners, "ns-c1", "ns-M"));
+        assert!(can_write(&writers, &owners, "ns-c1", "ns-N"));
+        assert!(can_write(&writers, &owners, "ns-c2", "ns-M"));
+        assert!(!can_write(&writers, &owners, "ns-c2", "ns-N"));
+        assert!(can_write(&writers, &owners, "ns-c3", "other-M"));
+        assert!(!can_write(&writers, &owners, "ns-c3", "other-N"));
+    }
+
+    #[test]
+    fn test_model_tag() {
+        let model_tags = HashMap::from([(
+            "pkg::models::Position".to_string(),
+            "ns-Position".to_string(),
+        )]);
+        let namespace_config = NamespaceConfig::new("dojo").with_mappings(HashMap::from([(
+            "dojo-Moves".to_string(),
+            "mapped".to_string(),
+        )]));
+
+        assert_eq!(
+            model_tag(&model_tags, &namespace_config, "pkg::models::Position"),
+            "ns-Position"
+        );
+        assert_eq!(
+            model_tag(&model_tags, &namespace_config, "dep::models::Moves"),
+            "mapped-Moves"
+        );
+        assert_eq!(
+            model_tag(&model_tags, &namespace_config, "dep::models::Other"),
+            "dojo-Other"
+        );
+    }
+}
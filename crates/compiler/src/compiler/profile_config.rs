@@ -0,0 +1,544 @@
+//! Typed Dojo profile configuration.
+//!
+//! The `dojo_<profile>.toml` file configures the world, the namespaces, the environment
+//! and the migration of a Dojo project, along with the checks of the compiler. Unlike
+//! [`super::config::CompilerConfig`], unknown keys are rejected to catch typos early.
+//!
+//! Once the build annotations are known, [`ProfileConfig::validate`] checks the tags and
+//! the calldata of the file, reporting each error at its line in the file.
+
+use std::collections::{HashMap, HashSet};
+
+use anyhow::{Context, Result};
+use camino::Utf8PathBuf;
+use dojo_types::naming;
+use serde::Deserialize;
+use starknet::core::types::Felt;
+
+use super::annotation::DojoAnnotation;
+use super::config::display_paths;
+use super::libfuncs::LibfuncsConfig;
+use super::size_report::BudgetConfig;
+use super::toml_location::TomlLocator;
+use crate::namespace_config::NamespaceConfig;
+
+/// Maximum length of a Cairo short string.
+const SHORT_STRING_MAX_LEN: usize = 31;
+
+/// The `[world]` section: metadata of the world.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WorldConfig {
+    pub name: String,
+    pub seed: String,
+    pub description: Option<String>,
+    pub cover_uri: Option<String>,
+    pub icon_uri: Option<String>,
+    pub website: Option<String>,
+    pub socials: Option<HashMap<String, String>>,
+}
+
+/// The `[env]` section: the network and the account to migrate with.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EnvConfig {
+    pub rpc_url: Option<String>,
+    pub account_address: Option<String>,
+    pub private_key: Option<String>,
+    pub keystore_path: Option<String>,
+    pub keystore_password: Option<String>,
+    pub world_address: Option<String>,
+    pub world_block: Option<u64>,
+}
+
+/// The `[migration]` section.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MigrationConfig {
+    /// Contract tags to initialize first, in this order.
+    #[serde(default)]
+    pub order_inits: Vec<String>,
+    /// Contract tags to skip during the migration.
+    #[serde(default)]
+    pub skip_contracts: Vec<String>,
+    pub disable_multicall: Option<bool>,
+}
+
+/// The contents of a `dojo_<profile>.toml` file.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProfileConfig {
+    pub world: WorldConfig,
+    pub namespace: NamespaceConfig,
+    pub env: Option<EnvConfig>,
+    /// Calldata of the `dojo_init` function, by contract tag.
+    #[serde(default)]
+    pub init_call_args: HashMap<String, Vec<String>>,
+    /// Contract tags granted the writer permission, by resource tag or namespace.
+    #[serde(default)]
+    pub writers: HashMap<String, Vec<String>>,
+    /// Contract tags granted the owner permission, by resource tag or namespace.
+    #[serde(default)]
+    pub owners: HashMap<String, Vec<String>>,
+    pub migration: Option<MigrationConfig>,
+    /// Size budgets of the contracts.
+    #[serde(default)]
+    pub budget: BudgetConfig,
+    /// Allowed libfuncs policy of the contracts.
+    #[serde(default)]
+    pub libfuncs: LibfuncsConfig,
+}
+
+/// The known resources of a build, to validate the references of the configuration.
+struct KnownResources {
+    contracts: HashSet<String>,
+    resources: HashSet<String>,
+    namespaces: HashSet<String>,
+}
+
+impl KnownResources {
+    fn new(annotation: &DojoAnnotation, namespace: &NamespaceConfig) -> Self {
+        let contracts = annotation
+            .contracts
+            .iter()
+            .map(|c| c.tag.clone())
+            .collect::<HashSet<_>>();
+
+        let resources = contracts
+            .iter()
+            .cloned()
+            .chain(annotation.models.iter().map(|m| m.tag.clone()))
+            .chain(annotation.events.iter().map(|e| e.tag.clone()))
+            .collect::<HashSet<_>>();
+
+        let namespaces = resources
+            .iter()
+            .map(|tag| naming::get_namespace_from_tag(tag))
+            .chain(std::iter::once(namespace.default.clone()))
+            .chain(
+                namespace
+                    .mappings()
+                    .into_iter()
+                    .flat_map(|m| m.values().cloned()),
+            )
+            .collect();
+
+        Self {
+            contracts,
+            resources,
+            namespaces,
+        }
+    }
+
+    /// Checks a reference to a contract.
+    fn check_contract(&self, tag: &str) -> Result<(), String> {
+        if !naming::is_valid_tag(tag) {
+            return Err(format!("Invalid contract tag `{tag}`."));
+        }
+
+        if !self.contracts.contains(tag) {
+            return Err(format!("Contract `{tag}` not found in the build."));
+        }
+
+        Ok(())
+    }
+
+    /// Checks a reference to a resource or a namespace.
+    fn check_resource_or_namespace(&self, tag_or_namespace: &str) -> Result<(), String> {
+        if tag_or_namespace.contains(naming::TAG_SEPARATOR) {
+            if !naming::is_valid_tag(tag_or_namespace) {
+                return Err(format!("Invalid resource tag `{tag_or_namespace}`."));
+            }
+
+            if !self.resources.contains(tag_or_namespace) {
+                return Err(format!(
+                    "Resource `{tag_or_namespace}` not found in the build."
+                ));
+            }
+        } else {
+            if !NamespaceConfig::is_name_valid(tag_or_namespace) {
+                return Err(format!("Invalid namespace `{tag_or_namespace}`."));
+            }
+
+            if !self.namespaces.contains(tag_or_namespace) {
+                return Err(format!(
+                    "Namespace `{tag_or_namespace}` not used by any resource of the build."
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl ProfileConfig {
+    /// Validates the configuration against the resources of the build.
+    ///
+    /// The namespace mappings must be valid, the tags must be valid and reference resources
+    /// of the build, the contracts to initialize must be listed at most once, and the calldata
+    /// literals must be valid.
+    /// All the errors are reported at once, each one at its line in the file.
+    ///
+    /// # Arguments
+    ///
+    /// * `paths` - The configuration files the configuration was merged from, in merge order.
+    /// * `annotation` - The dojo annotations of the build.
+    pub fn validate(&self, paths: &[Utf8PathBuf], annotation: &DojoAnnotation) -> Result<()> {
+        let errors = self.errors(annotation);
+
+        if errors.is_empty() {
+            return Ok(());
+        }
+
+        let contents = paths
+            .iter()
+            .map(|path| {
+                std::fs::read_to_string(path)
+                    .map(|content| (path, content))
+                    .with_context(|| format!("Failed to read Dojo configuration `{path}`."))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let locators = contents
+            .iter()
+            .filter_map(|(path, content)| Some((path, TomlLocator::parse(content)?)))
+            .collect::<Vec<_>>();
+
+        let errors = errors
+            .iter()
+            .map(|(section, needle, error)| {
+                // The value comes from the last layer defining it.
+                let location = locators.iter().rev().find_map(|(path, locator)| {
+                    line_of(locator, section, needle).map(|line| format!("{path}:{line}"))
+                });
+
+                match location {
+                    Some(location) => format!("{location}: {error}"),
+                    None => format!("{}: {error}", display_paths(paths)),
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        anyhow::bail!("Invalid Dojo configuration:\n{errors}");
+    }
+
+    /// Returns the errors of the configuration, with the section and the text to locate
+    /// each one in the file.
+    fn errors(&self, annotation: &DojoAnnotation) -> Vec<(&'static str, String, String)> {
+        let known = KnownResources::new(annotation, &self.namespace);
+        let mut errors = vec![];
+
+        let mut push = |section: &'static str, needle: &str, result: Result<(), String>| {
+            if let Err(e) = result {
+                errors.push((section, needle.to_string(), e));
+            }
+        };
+
+        let mut mappings = self
+            .namespace
+            .mappings()
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>();
+        mappings.sort();
+
+        for (key, namespace) in mappings {
+            push(
+                "namespace.mappings",
+                key,
+                NamespaceConfig::validate_mapping(key, namespace).map_err(|e| e.to_string()),
+            );
+        }
+
+        for (tag, args) in sorted(&self.init_call_args) {
+            push("init_call_args", tag, known.check_contract(tag));
+
+            for arg in args {
+                push(
+                    "init_call_args",
+                    arg,
+                    validate_calldata_literal(arg)
+                        .map_err(|e| format!("Invalid init call argument of `{tag}`: {e}")),
+                );
+            }
+        }
+
+        for (section, grants) in [("writers", &self.writers), ("owners", &self.owners)] {
+            for (tag_or_namespace, contracts) in sorted(grants) {
+                push(
+                    section,
+                    tag_or_namespace,
+                    known.check_resource_or_namespace(tag_or_namespace),
+                );
+
+                for contract in contracts {
+                    push(section, contract, known.check_contract(contract));
+                }
+            }
+        }
+
+        if let Some(migration) = &self.migration {
+            let mut seen = HashSet::new();
+
+            for tag in &migration.order_inits {
+                push("migration", tag, known.check_contract(tag));
+
+                if !seen.insert(tag) {
+                    push(
+                        "migration",
+                        tag,
+                        Err(format!(
+                            "Contract `{tag}` is listed twice in `order_inits`."
+                        )),
+                    );
+                }
+            }
+
+            for tag in &migration.skip_contracts {
+                push("migration", tag, known.check_contract(tag));
+            }
+        }
+
+        errors
+    }
+}
+
+/// Returns the entries of a map sorted by key, to report the errors in a stable order.
+fn sorted(map: &HashMap<String, Vec<String>>) -> Vec<(&String, &Vec<String>)> {
+    let mut entries = map.iter().collect::<Vec<_>>();
+    entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+    entries
+}
+
+/// Returns the line (1-based) of the given string in a section of the configuration, as a
+/// key or as a string value.
+fn line_of(locator: &TomlLocator<'_>, section: &str, needle: &str) -> Option<usize> {
+    let table = section.split('.').collect::<Vec<_>>();
+
+    locator
+        .key_line(&table, needle)
+        .or_else(|| locator.value_line(&table, needle))
+}
+
+/// Validates the syntax of a calldata literal.
+///
+/// The accepted literals are a felt (decimal or hexadecimal) or a prefixed value:
+/// `u256:<number>`, `str:<string>`, `sstr:<short string>`, `int:<signed integer>`,
+/// `arr:<felts>` and `u256arr:<numbers>`, the array items being separated by commas.
+///
+/// # Arguments
+///
+/// * `literal` - The calldata literal.
+pub fn validate_calldata_literal(literal: &str) -> Result<(), String> {
+    let Some((prefix, value)) = literal.split_once(':') else {
+        return if is_felt(literal) {
+            Ok(())
+        } else {
+            Err(format!("`{literal}` is not a valid felt."))
+        };
+    };
+
+    let valid = match prefix {
+        "u256" => is_number(value),
+        "str" => true,
+        "sstr" => value.is_ascii() && value.len() <= SHORT_STRING_MAX_LEN,
+        "int" => {
+            let digits = value.strip_prefix('-').unwrap_or(value);
+            !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit())
+        }
+        "arr" => value.is_empty() || value.split(',').all(|v| is_felt(v.trim())),
+        "u256arr" => value.is_empty() || value.split(',').all(|v| is_number(v.trim())),
+        _ => {
+            return Err(format!(
+                "Unknown calldata prefix `{prefix}` in `{literal}`."
+            ))
+        }
+    };
+
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("`{value}` is not a valid `{prefix}` value."))
+    }
+}
+
+/// Checks if the value is a valid felt, decimal or hexadecimal.
+fn is_felt(value: &str) -> bool {
+    is_number(value) && value.parse::<Felt>().is_ok()
+}
+
+/// Checks if the value is a decimal or hexadecimal (`0x` prefixed) number.
+fn is_number(value: &str) -> bool {
+    let (digits, radix) = match value.strip_prefix("0x") {
+        Some(hex) => (hex, 16),
+        None => (value, 10),
+    };
+
+    !digits.is_empty() && digits.chars().all(|c| c.is_digit(radix))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compiler::annotation::{ContractAnnotation, ModelAnnotation};
+
+    const CONFIG: &str = r#"
+[world]
+description = "Simple world."
+name = "simple"
+seed = "simple"
+
+[namespace]
+default = "ns"
+
+[env]
+rpc_url = "http://localhost:5050/"
+
+[init_call_args]
+"ns-c1" = ["0xfffe", "str:hello", "u256:1"]
+
+[writers]
+"ns" = ["ns-c1"]
+"ns-M" = ["ns-c2"]
+
+[migration]
+order_inits = ["ns-c2", "ns-c1"]
+"#;
+
+    fn annotation() -> DojoAnnotation {
+        DojoAnnotation {
+            contracts: ["ns-c1", "ns-c2"]
+                .iter()
+                .map(|tag| ContractAnnotation {
+                    tag: tag.to_string(),
+                    ..Default::default()
+                })
+                .collect(),
+            models: vec![ModelAnnotation {
+                tag: "ns-M".to_string(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_profile_config_deserialize() {
+        let config: ProfileConfig = toml::from_str(CONFIG).unwrap();
+
+        assert_eq!(config.world.name, "simple");
+        assert_eq!(config.namespace.default, "ns");
+        assert_eq!(config.init_call_args["ns-c1"].len(), 3);
+        assert_eq!(
+            config.migration.unwrap().order_inits,
+            vec!["ns-c2".to_string(), "ns-c1".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_profile_config_unknown_key() {
+        let content = CONFIG.replace("rpc_url", "rpc_uri");
+        let err = toml::from_str::<ProfileConfig>(&content).unwrap_err();
+
+        assert!(err.to_string().contains("rpc_uri"));
+    }
+
+    #[test]
+    fn test_profile_config_validate() {
+        let config: ProfileConfig = toml::from_str(CONFIG).unwrap();
+        assert!(config.errors(&annotation()).is_empty());
+
+        let content = CONFIG
+            .replace("\"ns-M\" =", "\"ns-Unknown\" =")
+            .replace("[\"ns-c2\", \"ns-c1\"]", "[\"ns-c1\", \"ns-c1\"]")
+            .replace("str:hello", "sstr:this short string is way too long");
+        let config: ProfileConfig = toml::from_str(&content).unwrap();
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = Utf8PathBuf::from_path_buf(temp_dir.path().join("dojo_dev.toml")).unwrap();
+        std::fs::write(&path, &content).unwrap();
+
+        let err = config
+            .validate(&[path.clone()], &annotation())
+            .unwrap_err()
+            .to_string();
+
+        assert!(err.contains(&format!("{path}:14: Invalid init call argument of `ns-c1`")));
+        assert!(err.contains(&format!("{path}:18: Resource `ns-Unknown` not found")));
+        assert!(err.contains(&format!(
+            "{path}:21: Contract `ns-c1` is listed twice in `order_inits`."
+        )));
+    }
+
+    #[test]
+    fn test_profile_config_validate_namespace_mappings() {
+        let content = CONFIG.replace(
+            "default = \"ns\"",
+            "default = \"ns\"\n\n[namespace.mappings]\n\"name:M*\" = \"ns\"\n\"re:^ns-\" = \"invalid-ns\"",
+        );
+        let config: ProfileConfig = toml::from_str(&content).unwrap();
+        let errors = config.errors(&annotation());
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].0, "namespace.mappings");
+        assert_eq!(errors[0].1, "re:^ns-");
+        assert_eq!(
+            line_of(
+                &TomlLocator::parse(&content).unwrap(),
+                errors[0].0,
+                &errors[0].1
+            ),
+            Some(12)
+        );
+
+        // The invalid patterns are reported with the other errors of the file.
+        let content = content.replace("re:^ns-\" = \"invalid-ns", "re:(\" = \"ns");
+        let config: ProfileConfig = toml::from_str(&content).unwrap();
+        let errors = config.errors(&annotation());
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].1, "re:(");
+        assert!(errors[0].2.contains("Invalid namespace mapping `re:(`"));
+    }
+
+    #[test]
+    fn test_line_of() {
+        let content = "[writers]\n\"ns-M\" = [\"ns-c1\"]\nns = [\"ns-c2\"]\n";
+        let locator = TomlLocator::parse(content).unwrap();
+
+        // The keys are matched exactly, not by prefix.
+        assert_eq!(line_of(&locator, "writers", "ns"), Some(3));
+        assert_eq!(line_of(&locator, "writers", "ns-M"), Some(2));
+        assert_eq!(line_of(&locator, "writers", "ns-c2"), Some(3));
+        assert_eq!(line_of(&locator, "owners", "ns"), None);
+    }
+
+    #[test]
+    fn test_validate_calldata_literal() {
+        for valid in [
+            "0x1",
+            "123",
+            "u256:0xffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+            "str:hello world",
+            "sstr:hello",
+            "int:-12",
+            "arr:1,0x2, 3",
+            "u256arr:",
+        ] {
+            assert!(validate_calldata_literal(valid).is_ok(), "{valid}");
+        }
+
+        for invalid in [
+            "hello",
+            "0xzz",
+            "u256:abc",
+            "sstr:this short string is way too long",
+            "int:-",
+            "arr:1,a",
+            "unknown:1",
+        ] {
+            assert!(validate_calldata_literal(invalid).is_err(), "{invalid}");
+        }
+    }
+}
@@ -0,0 +1,152 @@
+//! Starknet contracts compilation with debug info.
+//!
+//! Adapted from <https://github.com/starkware-libs/cairo/blob/main/crates/cairo-lang-starknet/src/compile.rs>
+//! to return the Sierra debug info along with the contract class, instead of
+//! compiling the contracts a second time to get it.
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use cairo_lang_compiler::db::RootDatabase;
+use cairo_lang_compiler::CompilerConfig;
+use cairo_lang_diagnostics::ToOption;
+use cairo_lang_lowering::ids::ConcreteFunctionWithBodyId;
+use cairo_lang_sierra::debug_info::Annotations;
+use cairo_lang_sierra_generator::canonical_id_replacer::CanonicalReplacer;
+use cairo_lang_sierra_generator::db::SierraGenGroup;
+use cairo_lang_sierra_generator::program_generator::{
+    SierraProgramDebugInfo, SierraProgramWithDebug,
+};
+use cairo_lang_sierra_generator::replace_ids::replace_sierra_ids_in_program;
+use cairo_lang_starknet::abi::AbiBuilder;
+use cairo_lang_starknet::aliased::Aliased;
+use cairo_lang_starknet::compile::{extract_semantic_entrypoints, SemanticEntryPoints};
+use cairo_lang_starknet::contract::{get_selector_and_sierra_function, ContractDeclaration};
+use cairo_lang_starknet_classes::contract_class::{
+    ContractClass, ContractEntryPoint, ContractEntryPoints,
+};
+use itertools::{chain, Itertools};
+
+/// A compiled contract class with the debug info of its Sierra program.
+#[derive(Debug)]
+pub struct ContractClassWithDebugInfo {
+    pub contract_class: ContractClass,
+    pub debug_info: SierraProgramDebugInfo,
+}
+
+/// Compiles the given contracts, checking the diagnostics of the database first.
+///
+/// Each contract is compiled once, the debug info being a by-product of
+/// the Sierra generation.
+pub fn compile_prepared_db_with_debug_info(
+    db: &RootDatabase,
+    contracts: &[&ContractDeclaration],
+    mut compiler_config: CompilerConfig<'_>,
+) -> Result<Vec<ContractClassWithDebugInfo>> {
+    compiler_config.diagnostics_reporter.ensure(db)?;
+
+    contracts
+        .iter()
+        .map(|contract| {
+            compile_contract_with_prepared_and_checked_db(db, contract, &compiler_config)
+        })
+        .try_collect()
+}
+
+/// Compile declared Starknet contract.
+///
+/// The `contract` value **must** come from `db`, for example as a result of calling
+/// `find_contracts`. Does not check diagnostics, it is expected that they are checked by caller
+/// of this function.
+fn compile_contract_with_prepared_and_checked_db(
+    db: &RootDatabase,
+    contract: &ContractDeclaration,
+    compiler_config: &CompilerConfig<'_>,
+) -> Result<ContractClassWithDebugInfo> {
+    let SemanticEntryPoints {
+        external,
+        l1_handler,
+        constructor,
+    } = extract_semantic_entrypoints(db, contract)?;
+    let SierraProgramWithDebug {
+        program: mut sierra_program,
+        debug_info,
+    } = Arc::unwrap_or_clone(
+        db.get_sierra_program_for_functions(
+            chain!(&external, &l1_handler, &constructor)
+                .map(|f| f.value)
+                .collect(),
+        )
+        .to_option()
+        .with_context(|| "Compilation failed without any diagnostics.")?,
+    );
+
+    if compiler_config.replace_ids {
+        sierra_program = replace_sierra_ids_in_program(db, &sierra_program);
+    }
+    let replacer = CanonicalReplacer::from_program(&sierra_program);
+    let sierra_program = replacer.apply(&sierra_program);
+
+    let entry_points_by_type = ContractEntryPoints {
+        external: get_entry_points(db, &external, &replacer),
+        l1_handler: get_entry_points(db, &l1_handler, &replacer),
+        // Later generation of ABI verifies that there is up to one constructor.
+        constructor: get_entry_points(db, &constructor, &replacer),
+    };
+
+    let mut annotations = Annotations::default();
+
+    if compiler_config.add_statements_functions {
+        annotations.extend(Annotations::from(
+            debug_info
+                .statements_locations
+                .extract_statements_functions(db),
+        ));
+    }
+
+    if compiler_config.add_statements_code_locations {
+        annotations.extend(Annotations::from(
+            debug_info
+                .statements_locations
+                .extract_statements_source_code_locations(db),
+        ));
+    }
+
+    let contract_class = ContractClass::new(
+        &sierra_program,
+        entry_points_by_type,
+        Some(
+            AbiBuilder::from_submodule(db, contract.submodule_id, Default::default())
+                .ok()
+                .with_context(|| "Unexpected error while generating ABI.")?
+                .finalize()
+                .with_context(|| "Could not create ABI from contract submodule")?,
+        ),
+        annotations,
+    )?;
+    contract_class.sanity_check();
+
+    Ok(ContractClassWithDebugInfo {
+        contract_class,
+        debug_info,
+    })
+}
+
+/// Returns the entry points given their IDs sorted by selectors.
+fn get_entry_points(
+    db: &RootDatabase,
+    entry_point_functions: &[Aliased<ConcreteFunctionWithBodyId>],
+    replacer: &CanonicalReplacer,
+) -> Vec<ContractEntryPoint> {
+    let mut entry_points = vec![];
+    for function_with_body_id in entry_point_functions {
+        let (selector, sierra_id) =
+            get_selector_and_sierra_function(db, function_with_body_id, replacer);
+
+        entry_points.push(ContractEntryPoint {
+            selector: selector.to_biguint(),
+            function_idx: sierra_id.id as usize,
+        });
+    }
+    entry_points.sort_by(|a, b| a.selector.cmp(&b.selector));
+    entry_points
+}
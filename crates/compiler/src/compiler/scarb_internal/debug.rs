@@ -1,64 +1,17 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::env;
-use std::sync::Arc;
 
-use anyhow::{Context, Result};
 use cairo_lang_compiler::db::RootDatabase;
-use cairo_lang_diagnostics::ToOption;
+use cairo_lang_defs::db::DefsGroup;
 use cairo_lang_filesystem::db::{get_originating_location, FilesGroup};
-use cairo_lang_filesystem::ids::{FileId, FileLongId};
+use cairo_lang_filesystem::ids::{CodeOrigin, CrateId, FileId, FileLongId};
 use cairo_lang_filesystem::span::TextSpan;
-use cairo_lang_sierra_generator::db::SierraGenGroup;
-use cairo_lang_sierra_generator::program_generator::{
-    SierraProgramDebugInfo, SierraProgramWithDebug,
-};
-use cairo_lang_starknet::compile::{extract_semantic_entrypoints, SemanticEntryPoints};
-use cairo_lang_starknet::contract::ContractDeclaration;
-use itertools::{chain, Itertools};
+use cairo_lang_sierra_generator::program_generator::SierraProgramDebugInfo;
+use cairo_lang_syntax::node::kind::SyntaxKind;
+use cairo_lang_syntax::node::{ast, SyntaxNode, Terminal, TypedSyntaxNode};
 use serde::{Deserialize, Serialize};
 
-pub fn compile_prepared_db_to_debug_info(
-    db: &RootDatabase,
-    contracts: &[&ContractDeclaration],
-    // mut compiler_config: CompilerConfig<'_>,
-) -> Result<Vec<SierraProgramDebugInfo>> {
-    // compiler_config.diagnostics_reporter.ensure(db)?;
-
-    contracts
-        .iter()
-        .map(|contract| compile_contract_with_prepared_and_checked_db_to_debug_info(db, contract))
-        .try_collect()
-}
-
-/// Compile declared Starknet contract.
-///
-/// The `contract` value **must** come from `db`, for example as a result of calling
-/// `find_contracts`. Does not check diagnostics, it is expected that they are checked by caller
-/// of this function.
-fn compile_contract_with_prepared_and_checked_db_to_debug_info(
-    db: &RootDatabase,
-    contract: &ContractDeclaration,
-) -> Result<SierraProgramDebugInfo> {
-    let SemanticEntryPoints {
-        external,
-        l1_handler,
-        constructor,
-    } = extract_semantic_entrypoints(db, contract)?;
-    let SierraProgramWithDebug {
-        program: _sierra_program,
-        debug_info,
-    } = Arc::unwrap_or_clone(
-        db.get_sierra_program_for_functions(
-            chain!(&external, &l1_handler, &constructor)
-                .map(|f| f.value)
-                .collect(),
-        )
-        .to_option()
-        .with_context(|| "Compilation failed without any diagnostics.")?,
-    );
-
-    Ok(debug_info)
-}
+use crate::CAIRO_PATH_SEPARATOR;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SierraToCairoDebugInfo {
@@ -66,7 +19,7 @@ pub struct SierraToCairoDebugInfo {
 }
 
 /// Human readable position inside a file, in lines and characters.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct TextPosition {
     /// Line index, 0 based.
     pub line: usize,
@@ -74,11 +27,14 @@ pub struct TextPosition {
     pub col: usize,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Location {
     pub start: TextPosition,
     pub end: TextPosition,
     pub file_path: String,
+    /// The Cairo path of the function containing the location, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub function: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -113,7 +69,10 @@ pub fn get_sierra_to_cairo_debug_info(
                 originating_file_id,
                 compiler_db,
             );
-            if let Some(cl) = cairo_location {
+            if let Some(mut cl) = cairo_location {
+                // The function is looked up in the compiled code, where the functions of the
+                // user keep their name once patched by the plugins.
+                cl.function = get_function_path(&syntax_node, file_id, compiler_db);
                 cairo_locations.push(cl);
             }
         }
@@ -179,5 +138,239 @@ pub fn get_location_from_text_span(
         start,
         end,
         file_path,
+        function: None,
     })
 }
+
+/// Returns the file generated by a plugin which contains the code at the given span, or `None`
+/// if the code is copied from a file on disk.
+///
+/// The virtual files are walked up like in [`get_originating_location`], until a span of
+/// generated code, only mapped to the item it is generated from, or without mapping.
+///
+/// # Arguments
+///
+/// * `compiler_db` - The database the code was compiled from.
+/// * `file_id` - The file of the code, maybe generated by a plugin.
+/// * `span` - The span of the code in the file.
+pub fn get_generating_file(
+    compiler_db: &RootDatabase,
+    mut file_id: FileId,
+    mut span: TextSpan,
+) -> Option<FileId> {
+    loop {
+        let file = match compiler_db.lookup_intern_file(file_id) {
+            FileLongId::OnDisk(_) => return None,
+            FileLongId::Virtual(file) => file,
+            FileLongId::External(_) => return Some(file_id),
+        };
+
+        let Some(parent) = file.parent else {
+            return Some(file_id);
+        };
+
+        match file
+            .code_mappings
+            .iter()
+            .find(|mapping| mapping.span.contains(span))
+        {
+            Some(mapping) if matches!(mapping.origin, CodeOrigin::Start(_)) => {
+                span = mapping
+                    .translate(span)
+                    .expect("The mapping should contain the span.");
+                file_id = parent;
+            }
+            _ => return Some(file_id),
+        }
+    }
+}
+
+/// Returns the locations the code generated by the plugins is attributed to, when it has no
+/// direct mapping to the user code.
+///
+/// The plugins map their generated code to the span of the item it is generated from (like the
+/// `#[dojo::model]` struct or the `#[dojo::contract]` module), which is then the originating
+/// location of all its statements. The locations are in the originating files, with their full
+/// path, as in the statements code locations of the debug info.
+///
+/// # Arguments
+///
+/// * `compiler_db` - The database the crates were compiled from.
+/// * `crate_ids` - The crates whose plugins generated code is located.
+pub fn get_generated_code_locations(
+    compiler_db: &RootDatabase,
+    crate_ids: &[CrateId],
+) -> HashSet<Location> {
+    let mut locations = HashSet::new();
+
+    for crate_id in crate_ids {
+        for module_id in compiler_db.crate_modules(*crate_id).iter() {
+            let Ok(file_ids) = compiler_db.module_files(*module_id) else {
+                continue;
+            };
+
+            for file_id in file_ids.iter() {
+                let FileLongId::Virtual(file) = compiler_db.lookup_intern_file(*file_id) else {
+                    continue;
+                };
+
+                let Some(parent) = file.parent else {
+                    continue;
+                };
+
+                for mapping in file.code_mappings.iter() {
+                    let CodeOrigin::Span(origin) = mapping.origin else {
+                        continue;
+                    };
+
+                    let (file_id, span) = get_originating_location(compiler_db, parent, origin);
+
+                    let start = span.start.position_in_file(compiler_db, file_id);
+                    let end = span.end.position_in_file(compiler_db, file_id);
+
+                    if let Some((start, end)) = start.zip(end) {
+                        locations.insert(Location {
+                            start: TextPosition {
+                                line: start.line,
+                                col: start.col,
+                            },
+                            end: TextPosition {
+                                line: end.line,
+                                col: end.col,
+                            },
+                            file_path: file_id.full_path(compiler_db),
+                            function: None,
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    locations
+}
+
+/// Returns the Cairo path of the function containing the syntax node, with the
+/// modules, traits and impls declared in the file of the node.
+///
+/// # Arguments
+///
+/// * `syntax_node` - The syntax node to get the function of.
+/// * `file_id` - The file of the syntax node, maybe generated by a plugin.
+/// * `compiler_db` - The database the node was compiled from.
+pub fn get_function_path(
+    syntax_node: &SyntaxNode,
+    file_id: FileId,
+    compiler_db: &RootDatabase,
+) -> Option<String> {
+    let mut segments = vec![];
+    let mut in_function = false;
+    let mut current = Some(syntax_node.clone());
+
+    while let Some(node) = current {
+        let name = match node.kind(compiler_db) {
+            SyntaxKind::FunctionWithBody => {
+                in_function = true;
+                ast::FunctionWithBody::from_syntax_node(compiler_db, node.clone())
+                    .declaration(compiler_db)
+                    .name(compiler_db)
+                    .text(compiler_db)
+            }
+            SyntaxKind::TraitItemFunction => {
+                in_function = true;
+                ast::TraitItemFunction::from_syntax_node(compiler_db, node.clone())
+                    .declaration(compiler_db)
+                    .name(compiler_db)
+                    .text(compiler_db)
+            }
+            SyntaxKind::ItemImpl => ast::ItemImpl::from_syntax_node(compiler_db, node.clone())
+                .name(compiler_db)
+                .text(compiler_db),
+            SyntaxKind::ItemTrait => ast::ItemTrait::from_syntax_node(compiler_db, node.clone())
+                .name(compiler_db)
+                .text(compiler_db),
+            SyntaxKind::ItemModule => ast::ItemModule::from_syntax_node(compiler_db, node.clone())
+                .name(compiler_db)
+                .text(compiler_db),
+            _ => {
+                current = node.parent();
+                continue;
+            }
+        };
+
+        if in_function {
+            segments.push(name.to_string());
+        }
+
+        current = node.parent();
+    }
+
+    if !in_function {
+        return None;
+    }
+
+    // The module of the file, the patches of the plugins being in the module of the
+    // patched item.
+    if let Some(module_id) = compiler_db
+        .file_modules(file_id)
+        .ok()
+        .and_then(|modules| modules.first().copied())
+    {
+        segments.push(module_id.full_path(compiler_db));
+    }
+
+    segments.reverse();
+
+    Some(segments.join(CAIRO_PATH_SEPARATOR))
+}
+
+#[cfg(test)]
+mod tests {
+    use cairo_lang_filesystem::ids::CrateLongId;
+    use cairo_lang_sierra_generator::db::SierraGenGroup;
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::compiler::test_utils::build_test_db;
+
+    const CODE: &str = include_str!("../test_data/debug_info.cairo");
+
+    #[test]
+    fn test_debug_info_of_contract_system() {
+        // Only the files of the current directory are located.
+        let current_dir = env::current_dir().unwrap();
+        let project_dir = TempDir::new_in(&current_dir).unwrap();
+        let db = build_test_db(project_dir.path(), "debug_info_test", CODE);
+
+        let crate_id = db.intern_crate(CrateLongId::Real("debug_info_test".into()));
+        let program = db.get_sierra_program(vec![crate_id]).unwrap();
+        let debug_info = get_sierra_to_cairo_debug_info(&program.debug_info, &db);
+
+        let locations = debug_info
+            .sierra_statements_to_cairo_info
+            .values()
+            .flat_map(|info| &info.cairo_locations)
+            .filter(|l| {
+                l.function.as_deref() == Some("debug_info_test::actions::ActionsImpl::spawn")
+            })
+            .collect::<Vec<_>>();
+
+        assert!(!locations.is_empty());
+
+        // The statements of the system, patched by the contract plugin, are located in its
+        // body in the user file.
+        let file_path = project_dir.path().join("src/lib.cairo");
+        let file_path = file_path
+            .strip_prefix(&current_dir)
+            .unwrap()
+            .to_str()
+            .unwrap();
+        let line = |pattern: &str| CODE.lines().position(|l| l.contains(pattern)).unwrap();
+        let body = line("fn spawn")..=line("world.write_model");
+
+        assert!(locations
+            .iter()
+            .all(|l| l.file_path == file_path && body.contains(&l.start.line)));
+        assert!(locations.iter().any(|l| l.start.line == line("x: x + 1")));
+    }
+}
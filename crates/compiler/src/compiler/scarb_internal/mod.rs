@@ -17,13 +17,15 @@ use regex::Regex;
 use scarb::compiler::{
     CairoCompilationUnit, CompilationUnit, CompilationUnitAttributes, CompilationUnitComponent,
 };
-use scarb::core::{Config, PackageId, Workspace};
+use scarb::core::{Config, Package, PackageId, Workspace};
 use scarb::ops::CompileOpts;
 use scarb_ui::Ui;
 use smol_str::SmolStr;
 use tracing::trace;
 
 use crate::compiler::config::{CompilerConfig, DojoConfigLoader};
+use crate::compiler::messages::{self, DiagnosticMessage};
+use crate::diagnostic_codes;
 use crate::namespace_config::{
     NamespaceConfig, DEFAULT_NAMESPACE_CFG_KEY, DOJO_ANNOTATIONS_DIR_CFG_KEY, NAMESPACE_CFG_PREFIX,
     WORKSPACE_CURRENT_PROFILE_CFG_KEY,
@@ -31,6 +33,7 @@ use crate::namespace_config::{
 use crate::plugin::cairo_plugin::dojo_plugin_suite;
 use crate::scarb_extensions::WorkspaceExt;
 
+pub mod compile;
 pub mod debug;
 
 /// Compilation information of all the units found in the workspace.
@@ -93,6 +96,36 @@ pub fn build_scarb_root_database(unit: &CairoCompilationUnit) -> Result<RootData
     b.build()
 }
 
+/// Builds the database of a dojo compilation unit.
+///
+/// For each component in the compilation unit (namely, the dependencies being compiled) we
+/// inject into the `CfgSet` the component name and namespace configuration. Doing this here
+/// ensures the parsing of the manifest is done once at compile time, and not everytime the
+/// plugin is called.
+pub fn build_dojo_root_database(
+    unit: &mut CairoCompilationUnit,
+    ws: &Workspace<'_>,
+) -> Result<RootDatabase> {
+    let ui = ws.config().ui();
+
+    let root_package = unit.components[0].package.clone();
+    let compiler_config: CompilerConfig = DojoConfigLoader::from_package(&root_package, ws)?;
+
+    ui.verbose(compiler_config.namespace.display_mappings());
+
+    for c in unit.components.iter_mut() {
+        c.cfg_set = Some(cfg_set_from_component(
+            c,
+            &root_package,
+            &compiler_config.namespace,
+            &ui,
+            ws,
+        )?);
+    }
+
+    build_scarb_root_database(unit)
+}
+
 /// This function is an alternative to `ops::compile`, it's doing the same job.
 /// However, we can control the injection of the plugins, required to have dojo plugin present
 /// for each compilation.
@@ -139,35 +172,19 @@ pub fn compile_workspace(
                 unit.main_component().target_kind()
             ));
 
-            let compiler_config: CompilerConfig =
-                DojoConfigLoader::from_package(&unit.components[0].package, &ws).unwrap();
-
-            // let root_package_data =
-            // PackageData::from_scarb_package(&unit.components[0].package)?;
-
-            ui.verbose(compiler_config.namespace.display_mappings());
-
-            // For each component in the compilation unit (namely, the dependencies being
-            // compiled) we inject into the `CfgSet` the component name and
-            // namespace configuration. Doing this here ensures the parsing of
-            // of the manifest is done once at compile time, and not everytime
-            // the plugin is called.
-            for c in unit.components.iter_mut() {
-                c.cfg_set = Some(cfg_set_from_component(
-                    c,
-                    &compiler_config.namespace,
-                    &ui,
-                    &ws,
-                )?);
-            }
-
-            let mut db = build_scarb_root_database(&unit).unwrap();
+            let mut db = build_dojo_root_database(&mut unit, &ws)?;
             if let Err(err) = ws
                 .config()
                 .compilers()
                 .compile(unit.clone(), &mut (db), &ws)
             {
-                ws.config().ui().anyhow(&err);
+                messages::emit(
+                    &ws.config().ui(),
+                    DiagnosticMessage::error(
+                        diagnostic_codes::COMMAND_FAILED.code,
+                        format!("{err:?}"),
+                    ),
+                );
                 compile_error_units.push(unit.name());
             }
         }
@@ -236,8 +253,11 @@ fn build_project_config(unit: &CairoCompilationUnit) -> Result<ProjectConfig> {
     Ok(project_config)
 }
 
+/// Builds the `CfgSet` of a component of a compilation unit, injecting the dojo configuration
+/// of the package being compiled (`root_package`) for the plugin.
 pub fn cfg_set_from_component(
     c: &CompilationUnitComponent,
+    root_package: &Package,
     root_package_namespace: &NamespaceConfig,
     ui: &Ui,
     ws: &Workspace<'_>,
@@ -272,7 +292,7 @@ pub fn cfg_set_from_component(
 
     cfg_set.insert(Cfg {
         key: DOJO_ANNOTATIONS_DIR_CFG_KEY.into(),
-        value: Some(ws.target_dir_profile().to_string().into()),
+        value: Some(ws.dojo_target_dir(root_package).to_string().into()),
     });
 
     cfg_set.insert(Cfg {
@@ -295,7 +315,7 @@ pub fn cfg_set_from_component(
 
     // Inject the mapping from the root package with [[target.dojo]] to
     // all dependencies to ensure correct namespace mappings.
-    if let Some(mappings) = &root_package_namespace.mappings {
+    if let Some(mappings) = root_package_namespace.mappings() {
         for (k, v) in mappings.iter() {
             cfg_set.insert(Cfg {
                 key: format!("{}{}", NAMESPACE_CFG_PREFIX, k).into(),
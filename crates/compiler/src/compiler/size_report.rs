@@ -0,0 +1,501 @@
+//! Size report of the compiled contracts.
+//!
+//! Public Starknet networks limit the size of the declared classes. The size report
+//! gives, for each compiled contract, the metrics used by those limits, and where the
+//! code is coming from, to detect the contracts getting close to the limits before
+//! declaring them.
+//!
+//! The budgets are configured per profile, in the `[budget]` section of the
+//! `dojo_<profile>.toml` file.
+//!
+//! The CASM bytecode size is only computed when the CASM is compiled, which is the case
+//! if the CASM is output, a CASM budget is configured or the size report is requested.
+
+use std::io::{Read, Write};
+
+use anyhow::Result;
+use cairo_lang_compiler::db::RootDatabase;
+use cairo_lang_defs::db::DefsGroup;
+use cairo_lang_filesystem::db::{get_originating_location, FilesGroup};
+use cairo_lang_filesystem::ids::{FileId, FileLongId};
+use cairo_lang_sierra_generator::program_generator::SierraProgramDebugInfo;
+use cairo_lang_starknet_classes::casm_contract_class::CasmContractClass;
+use cairo_lang_starknet_classes::contract_class::ContractClass;
+use camino::Utf8Path;
+use scarb::core::{Package, Workspace};
+use serde::{Deserialize, Serialize};
+
+use super::scarb_internal::debug::get_generating_file;
+use crate::aux_data::is_dojo_aux_data;
+use crate::plugin::inline_macros;
+use crate::scarb_extensions::WorkspaceExt;
+
+const SIZE_REPORT_FILE_NAME: &str = "size_report";
+
+/// The maximum CASM bytecode size accepted by public Starknet networks, in felts.
+///
+/// Not enforced by default, since it requires to compile the CASM of every contract.
+pub const DEFAULT_MAX_CASM_BYTECODE_SIZE: usize = 81_920;
+
+/// Number of Sierra statements by origin of the Cairo code they are generated from.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StatementsOrigin {
+    /// Code written in the package being compiled.
+    pub user: usize,
+    /// Code generated by the Dojo plugin, for the models, events, contracts and derives.
+    pub dojo: usize,
+    /// Code generated by the Starknet plugin, and the other Cairo plugins like the derives of
+    /// the corelib.
+    pub starknet: usize,
+    /// Code of the dependencies, including the corelib.
+    pub dependencies: usize,
+    /// Statements without any code location.
+    pub unknown: usize,
+}
+
+/// The size metrics of a compiled contract.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContractSize {
+    pub qualified_path: String,
+    /// Length of the Sierra program, in felts.
+    pub sierra_program_length: usize,
+    /// Size of the CASM bytecode, in felts, if the CASM was compiled.
+    pub casm_bytecode_size: Option<usize>,
+    /// Number of entrypoints (external, l1 handlers and constructor).
+    pub entrypoints: usize,
+    pub statements: StatementsOrigin,
+}
+
+impl ContractSize {
+    /// Computes the size metrics of a compiled contract.
+    ///
+    /// # Arguments
+    ///
+    /// * `qualified_path` - The qualified path of the contract.
+    /// * `contract_class` - The Sierra class of the contract.
+    /// * `casm_class` - The CASM class compiled from the Sierra class, if compiled.
+    /// * `debug_info` - The debug info of the Sierra program.
+    /// * `package_root` - The root directory of the package being compiled.
+    /// * `db` - The database the contract was compiled from.
+    pub fn new(
+        qualified_path: &str,
+        contract_class: &ContractClass,
+        casm_class: Option<&CasmContractClass>,
+        debug_info: &SierraProgramDebugInfo,
+        package_root: &Utf8Path,
+        db: &RootDatabase,
+    ) -> Self {
+        let entry_points = &contract_class.entry_points_by_type;
+
+        Self {
+            qualified_path: qualified_path.to_string(),
+            sierra_program_length: contract_class.sierra_program.len(),
+            casm_bytecode_size: casm_class.map(|casm_class| casm_class.bytecode.len()),
+            entrypoints: entry_points.external.len()
+                + entry_points.l1_handler.len()
+                + entry_points.constructor.len(),
+            statements: statements_origin(debug_info, package_root, db),
+        }
+    }
+}
+
+/// Counts the Sierra statements by origin of their Cairo code.
+///
+/// Only the innermost location of each statement is considered. The code generated by a
+/// plugin is attributed to the plugin, even if mapped to the item it is generated from, the
+/// code of the user being only the one copied from its sources (like the body of a system).
+fn statements_origin(
+    debug_info: &SierraProgramDebugInfo,
+    package_root: &Utf8Path,
+    db: &RootDatabase,
+) -> StatementsOrigin {
+    let mut origin = StatementsOrigin::default();
+
+    for (_, locations) in debug_info.statements_locations.locations.iter_sorted() {
+        let Some(location) = locations.first() else {
+            origin.unknown += 1;
+            continue;
+        };
+
+        let syntax_node = location.syntax_node(db);
+        let file_id = syntax_node.stable_ptr().file_id(db);
+        let span = syntax_node.span_without_trivia(db);
+        let (originating_file_id, _) = get_originating_location(db, file_id, span);
+
+        // The code of the dependencies is counted as such, even if generated.
+        let is_package_code = match db.lookup_intern_file(originating_file_id) {
+            FileLongId::OnDisk(path) => path.starts_with(package_root),
+            FileLongId::Virtual(_) | FileLongId::External(_) => true,
+        };
+
+        match get_generating_file(db, file_id, span) {
+            _ if !is_package_code => origin.dependencies += 1,
+            None => origin.user += 1,
+            Some(file_id) if is_dojo_generated_file(db, file_id) => origin.dojo += 1,
+            Some(_) => origin.starknet += 1,
+        }
+    }
+
+    origin
+}
+
+/// Checks if a file was generated by the Dojo plugin, from the aux data of the generated module
+/// files, or from the name of the files generated by the inline macros.
+fn is_dojo_generated_file(db: &RootDatabase, file_id: FileId) -> bool {
+    let FileLongId::Virtual(file) = db.lookup_intern_file(file_id) else {
+        return false;
+    };
+
+    if inline_macros::GENERATED_FILE_NAMES.contains(&file.name.as_str()) {
+        return true;
+    }
+
+    let Ok(module_ids) = db.file_modules(file_id) else {
+        return false;
+    };
+
+    module_ids.iter().any(|module_id| {
+        let (Ok(file_ids), Ok(aux_data)) = (
+            db.module_files(*module_id),
+            db.module_generated_file_aux_data(*module_id),
+        ) else {
+            return false;
+        };
+
+        file_ids
+            .iter()
+            .position(|id| *id == file_id)
+            .and_then(|index| aux_data.get(index))
+            .and_then(|aux_data| aux_data.as_ref())
+            .is_some_and(is_dojo_aux_data)
+    })
+}
+
+/// The level at which a budget violation is reported.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BudgetLevel {
+    #[default]
+    Warn,
+    Error,
+}
+
+/// Size budgets of the contracts, configured per profile.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BudgetConfig {
+    pub max_sierra_program_length: Option<usize>,
+    pub max_casm_bytecode_size: Option<usize>,
+    pub max_entrypoints: Option<usize>,
+    pub level: BudgetLevel,
+}
+
+impl BudgetConfig {
+    /// Checks if the budgets require the CASM of the contracts to be compiled.
+    pub fn requires_casm(&self) -> bool {
+        self.max_casm_bytecode_size.is_some()
+    }
+
+    /// Returns a description of each budget exceeded by the given contract.
+    pub fn violations(&self, size: &ContractSize) -> Vec<String> {
+        let budgets = [
+            (
+                "Sierra program length",
+                Some(size.sierra_program_length),
+                self.max_sierra_program_length,
+            ),
+            (
+                "CASM bytecode size",
+                size.casm_bytecode_size,
+                self.max_casm_bytecode_size,
+            ),
+            ("entrypoints", Some(size.entrypoints), self.max_entrypoints),
+        ];
+
+        budgets
+            .into_iter()
+            .filter_map(|(name, value, max)| match (value, max) {
+                (Some(value), Some(max)) if value > max => Some(format!(
+                    "Contract `{}` exceeds the {name} budget: {value} > {max}.",
+                    size.qualified_path
+                )),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// The size report of all the compiled contracts, sorted by qualified path.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SizeReport {
+    pub contracts: Vec<ContractSize>,
+}
+
+impl SizeReport {
+    /// Creates a new size report, sorting the contracts by qualified path.
+    pub fn new(mut contracts: Vec<ContractSize>) -> Self {
+        contracts.sort_by(|a, b| a.qualified_path.cmp(&b.qualified_path));
+        Self { contracts }
+    }
+
+    /// Checks the contracts against the budgets.
+    ///
+    /// # Returns
+    ///
+    /// The violations to report as warnings if the budget level is `warn`,
+    /// or an error listing the violations if the budget level is `error`.
+    pub fn check_budgets(&self, budgets: &BudgetConfig) -> Result<Vec<String>> {
+        let violations = self
+            .contracts
+            .iter()
+            .flat_map(|c| budgets.violations(c))
+            .collect::<Vec<_>>();
+
+        if budgets.level == BudgetLevel::Error && !violations.is_empty() {
+            anyhow::bail!("Contracts budgets exceeded:\n{}", violations.join("\n"));
+        }
+
+        Ok(violations)
+    }
+
+    /// Reads the size report from the target directory of the provided dojo package,
+    /// for the current profile. Returns an empty report if no report is found.
+    ///
+    /// # Arguments
+    ///
+    /// * `workspace` - The workspace to read the size report from.
+    /// * `package` - The dojo package of the artifacts.
+    pub fn read(workspace: &Workspace<'_>, package: &Package) -> Result<Self> {
+        let target_dir = workspace.dojo_target_dir(package);
+        let file_name = format!("{}.json", SIZE_REPORT_FILE_NAME);
+
+        if !target_dir.child(&file_name).exists() {
+            return Ok(Self::default());
+        }
+
+        let mut file = target_dir.open_ro(file_name, "Dojo size report", workspace.config())?;
+
+        let mut content = String::new();
+        file.read_to_string(&mut content)?;
+
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    /// Writes the size report to the target directory of the provided dojo package,
+    /// for the current profile.
+    ///
+    /// # Arguments
+    ///
+    /// * `workspace` - The workspace to write the size report to.
+    /// * `package` - The dojo package of the artifacts.
+    pub fn write(&self, workspace: &Workspace<'_>, package: &Package) -> Result<()> {
+        let target_dir = workspace.dojo_target_dir(package);
+        let content = serde_json::to_string_pretty(&self)?;
+
+        let mut file = target_dir.create_rw(
+            format!("{}.json", SIZE_REPORT_FILE_NAME),
+            "Dojo size report",
+            workspace.config(),
+        )?;
+
+        file.write_all(content.as_bytes())?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_fs::fixture::{FileWriteStr, PathChild};
+    use assert_fs::TempDir;
+    use indoc::{formatdoc, indoc};
+    use scarb::compiler::CompilerRepository;
+    use scarb::core::Config;
+    use scarb_ui::args::FeaturesSpec;
+
+    use super::*;
+    use crate::compiler::{DojoCompiler, DojoCompilerOptions};
+    use crate::plugin::CairoPluginRepository;
+
+    fn contract_size(sierra: usize, casm: usize, entrypoints: usize) -> ContractSize {
+        ContractSize {
+            qualified_path: "pkg::c1".to_string(),
+            sierra_program_length: sierra,
+            casm_bytecode_size: Some(casm),
+            entrypoints,
+            statements: StatementsOrigin::default(),
+        }
+    }
+
+    #[test]
+    fn test_budget_violations() {
+        let budgets = BudgetConfig {
+            max_sierra_program_length: Some(100),
+            max_casm_bytecode_size: Some(200),
+            max_entrypoints: None,
+            level: BudgetLevel::Warn,
+        };
+
+        assert!(budgets.violations(&contract_size(100, 200, 50)).is_empty());
+        assert_eq!(budgets.violations(&contract_size(101, 200, 50)).len(), 1);
+        assert_eq!(budgets.violations(&contract_size(101, 201, 50)).len(), 2);
+    }
+
+    #[test]
+    fn test_check_budgets_level() {
+        let report = SizeReport::new(vec![contract_size(
+            0,
+            DEFAULT_MAX_CASM_BYTECODE_SIZE + 1,
+            1,
+        )]);
+
+        assert!(report
+            .check_budgets(&BudgetConfig::default())
+            .unwrap()
+            .is_empty());
+
+        let warn = BudgetConfig {
+            max_casm_bytecode_size: Some(DEFAULT_MAX_CASM_BYTECODE_SIZE),
+            ..Default::default()
+        };
+        assert_eq!(report.check_budgets(&warn).unwrap().len(), 1);
+
+        let error = BudgetConfig {
+            level: BudgetLevel::Error,
+            ..warn
+        };
+        assert!(report.check_budgets(&error).is_err());
+    }
+
+    #[test]
+    fn test_budget_config_deserialize() {
+        let budgets: BudgetConfig = toml::from_str(
+            r#"
+max_entrypoints = 10
+level = "error"
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(budgets.max_entrypoints, Some(10));
+        assert_eq!(budgets.max_casm_bytecode_size, None);
+        assert!(!budgets.requires_casm());
+        assert_eq!(budgets.level, BudgetLevel::Error);
+    }
+
+    #[test]
+    fn test_budget_without_casm_size() {
+        let budgets = BudgetConfig {
+            max_casm_bytecode_size: Some(1),
+            ..Default::default()
+        };
+
+        let size = ContractSize {
+            casm_bytecode_size: None,
+            ..contract_size(0, 0, 0)
+        };
+
+        assert!(budgets.requires_casm());
+        assert!(budgets.violations(&size).is_empty());
+    }
+
+    #[test]
+    fn test_statements_origin() {
+        let dojo_dir = Utf8Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("../contracts")
+            .canonicalize_utf8()
+            .unwrap();
+
+        let project_dir = TempDir::new().unwrap();
+        let manifest = project_dir.child("Scarb.toml");
+        manifest
+            .write_str(&formatdoc! {r#"
+                [package]
+                cairo-version = "=2.8.4"
+                name = "size_report_test"
+                version = "0.1.0"
+                edition = "2024_07"
+
+                [[target.dojo]]
+                build-external-contracts = ["dojo::world::world_contract::world"]
+
+                [dependencies]
+                dojo = {{ path = "{dojo_dir}" }}
+            "#})
+            .unwrap();
+        project_dir
+            .child("dojo_dev.toml")
+            .write_str(indoc! {r#"
+                [world]
+                name = "size_report_test"
+                seed = "size_report_test"
+
+                [namespace]
+                default = "ns"
+            "#})
+            .unwrap();
+        project_dir
+            .child("src/lib.cairo")
+            .write_str(include_str!("test_data/size_report.cairo"))
+            .unwrap();
+
+        let mut compilers = CompilerRepository::empty();
+        compilers
+            .add(Box::new(DojoCompiler::new(DojoCompilerOptions {
+                size_report: true,
+                ..Default::default()
+            })))
+            .unwrap();
+
+        let cache_dir = TempDir::new().unwrap();
+        let manifest_path = Utf8Path::from_path(manifest.path()).unwrap();
+
+        let config = Config::builder(manifest_path)
+            .global_cache_dir_override(Some(Utf8Path::from_path(cache_dir.path()).unwrap()))
+            .compilers(compilers)
+            .cairo_plugins(CairoPluginRepository::default().into())
+            .build()
+            .unwrap();
+
+        let features = FeaturesSpec {
+            features: vec![],
+            all_features: false,
+            no_default_features: false,
+        };
+
+        DojoCompiler::compile_workspace(&config, None, features, true, false).unwrap();
+
+        let ws = scarb::ops::read_workspace(config.manifest_path(), &config).unwrap();
+        let package = ws.members().next().unwrap();
+        let report = SizeReport::read(&ws, &package).unwrap();
+
+        let statements = |qualified_path: &str| {
+            report
+                .contracts
+                .iter()
+                .find(|c| c.qualified_path == qualified_path)
+                .map(|c| c.statements.clone())
+                .unwrap_or_else(|| panic!("{qualified_path} should be in the report"))
+        };
+
+        // The systems are copied from the user code, around the code of the Dojo plugin
+        // and the ABI wrappers of the Starknet plugin.
+        let c1 = statements("size_report_test::c1");
+        assert!(c1.user > 0);
+        assert!(c1.dojo > 0);
+        assert!(c1.starknet > 0);
+        assert!(c1.dependencies > 0);
+
+        // The code generated for a contract without any code of the user isn't counted as user
+        // code, even if mapped to the `#[dojo::contract]` attribute.
+        let c2 = statements("size_report_test::c2");
+        assert_eq!(c2.user, 0);
+        assert!(c2.dojo > 0);
+
+        // The world is built from the dojo crate, its generated code included.
+        let world = statements("dojo::world::world_contract::world");
+        assert_eq!(world.user + world.dojo + world.starknet, 0);
+        assert!(world.dependencies > 0);
+    }
+}
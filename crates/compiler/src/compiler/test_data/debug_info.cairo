@@ -0,0 +1,28 @@
+#[starknet::interface]
+pub trait IActions<T> {
+    fn spawn(ref self: T, x: u32);
+}
+
+#[derive(Copy, Drop, Serde)]
+#[dojo::model]
+pub struct Position {
+    #[key]
+    pub player: starknet::ContractAddress,
+    pub x: u32,
+}
+
+#[dojo::contract]
+pub mod actions {
+    use dojo::model::ModelStorage;
+    use dojo::world::WorldStorageTrait;
+    use super::{IActions, Position};
+
+    #[abi(embed_v0)]
+    impl ActionsImpl of IActions<ContractState> {
+        fn spawn(ref self: ContractState, x: u32) {
+            let mut world = WorldStorageTrait::new(self.world_dispatcher(), @"ns");
+            let position = Position { player: starknet::get_caller_address(), x: x + 1 };
+            world.write_model(@position);
+        }
+    }
+}
@@ -0,0 +1,58 @@
+use dojo::model::{Model, ModelStorage};
+use dojo::world::WorldStorage;
+
+#[derive(Copy, Drop, Serde)]
+#[dojo::model]
+pub struct Position {
+    #[key]
+    pub player: starknet::ContractAddress,
+    pub x: u32,
+}
+
+#[derive(Copy, Drop, Serde)]
+#[dojo::model]
+pub struct Moves {
+    #[key]
+    pub player: starknet::ContractAddress,
+    pub remaining: u8,
+}
+
+#[starknet::interface]
+pub trait IActions<T> {
+    fn spawn(ref self: T);
+    fn remaining(self: @T) -> u8;
+}
+
+/// Writes any model, the model being resolved from the generic arguments of the call.
+fn save<M, +Model<M>, +Drop<M>>(ref world: WorldStorage, model: @M) {
+    world.write_model(model);
+}
+
+#[dojo::contract]
+pub mod actions {
+    use dojo::model::ModelStorage;
+    use dojo::world::{WorldStorage, WorldStorageTrait};
+    use super::{IActions, Moves, Position};
+
+    #[abi(embed_v0)]
+    impl ActionsImpl of IActions<ContractState> {
+        fn spawn(ref self: ContractState) {
+            let mut world = self.world_storage();
+            let player = starknet::get_caller_address();
+            super::save(ref world, @Position { player, x: 0 });
+        }
+
+        fn remaining(self: @ContractState) -> u8 {
+            let world = self.world_storage();
+            let moves: Moves = world.read_model(starknet::get_caller_address());
+            moves.remaining
+        }
+    }
+
+    #[generate_trait]
+    impl InternalImpl of InternalTrait {
+        fn world_storage(self: @ContractState) -> WorldStorage {
+            WorldStorageTrait::new(self.world_dispatcher(), @"ns")
+        }
+    }
+}
@@ -0,0 +1,53 @@
+use dojo::meta::introspect::Introspect;
+
+#[derive(Introspect, Drop, Serde)]
+#[dojo::model]
+pub struct Position {
+    #[key]
+    pub player: starknet::ContractAddress,
+    pub name: ByteArray,
+    pub coords: (u32, u32),
+    pub direction: Direction,
+    pub target: Option<u64>,
+    pub path: Array<Direction>,
+    pub stats: Stats,
+}
+
+#[derive(Introspect, Copy, Drop, Serde)]
+pub enum Direction {
+    None,
+    Left: u8,
+    Right: (u16, u16),
+}
+
+#[derive(IntrospectPacked, Copy, Drop, Serde)]
+pub enum Mood {
+    Calm,
+    Angry,
+}
+
+#[derive(IntrospectPacked, Copy, Drop, Serde)]
+pub struct Stats {
+    pub level: u8,
+    pub experience: u256,
+    pub alive: bool,
+    pub mood: Mood,
+}
+
+/// Panics with the serialized size, layout and type of `T`, the panic data being returned
+/// by the runner.
+fn introspect<T, +Introspect<T>>() {
+    let mut values = array![];
+    Introspect::<T>::size().serialize(ref values);
+    Introspect::<T>::layout().serialize(ref values);
+    Introspect::<T>::ty().serialize(ref values);
+    panic(values);
+}
+
+fn introspect_position() {
+    introspect::<Position>();
+}
+
+fn introspect_stats() {
+    introspect::<Stats>();
+}
@@ -0,0 +1,30 @@
+#[derive(Copy, Drop, Serde)]
+#[dojo::model]
+pub struct M {
+    #[key]
+    pub a: felt252,
+    pub b: felt252,
+}
+
+#[starknet::interface]
+pub trait MyInterface<T> {
+    fn system_1(ref self: T, a: felt252, b: felt252);
+}
+
+#[dojo::contract]
+pub mod c1 {
+    use dojo::model::ModelStorage;
+    use dojo::world::WorldStorageTrait;
+    use super::{MyInterface, M};
+
+    #[abi(embed_v0)]
+    impl MyInterfaceImpl of MyInterface<ContractState> {
+        fn system_1(ref self: ContractState, a: felt252, b: felt252) {
+            let mut world = WorldStorageTrait::new(self.world_dispatcher(), @"ns");
+            world.write_model(@M { a, b });
+        }
+    }
+}
+
+#[dojo::contract]
+pub mod c2 {}
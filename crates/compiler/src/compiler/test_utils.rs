@@ -4,17 +4,20 @@
 //! Important to note that if tests are run in parallel, the cache_dir and output directories
 //! may clash. In such cases, consider copying the project or overriding the directories.
 
-use std::env;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::{env, fs};
 
 use assert_fs::TempDir;
+use cairo_lang_compiler::db::RootDatabase;
 use camino::{Utf8Path, Utf8PathBuf};
+use indoc::formatdoc;
 use scarb::compiler::{CompilationUnit, CompilerRepository, Profile};
-use scarb::core::Config;
+use scarb::core::{Config, TargetKind};
 use scarb::ops;
 use scarb::ops::{FeaturesOpts, FeaturesSelector};
 use scarb_ui::Verbosity;
 
+use super::scarb_internal::build_scarb_root_database;
 use super::DojoCompiler;
 use crate::plugin::CairoPluginRepository;
 
@@ -74,3 +77,58 @@ pub fn corelib(config: &Config) -> PathBuf {
         panic!("should have cairo compilation unit")
     }
 }
+
+/// Builds the database of the library of a single file package depending on the dojo crate.
+///
+/// # Arguments
+///
+/// * `project_dir` - The directory to write the package to.
+/// * `name` - The name of the package.
+/// * `code` - The code of the `src/lib.cairo` file of the package.
+pub fn build_test_db(project_dir: &Path, name: &str, code: &str) -> RootDatabase {
+    let dojo_dir = Utf8Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../contracts")
+        .canonicalize_utf8()
+        .unwrap();
+
+    let manifest = project_dir.join("Scarb.toml");
+    fs::write(
+        &manifest,
+        formatdoc! {r#"
+            [package]
+            cairo-version = "=2.8.4"
+            name = "{name}"
+            version = "0.1.0"
+            edition = "2024_07"
+
+            [dependencies]
+            dojo = {{ path = "{dojo_dir}" }}
+        "#},
+    )
+    .unwrap();
+    fs::create_dir_all(project_dir.join("src")).unwrap();
+    fs::write(project_dir.join("src/lib.cairo"), code).unwrap();
+
+    let config = build_test_config(manifest.to_str().unwrap(), Profile::DEV).unwrap();
+    let ws = ops::read_workspace(config.manifest_path(), &config).unwrap();
+    let resolve = ops::resolve_workspace(&ws).unwrap();
+    let features = FeaturesOpts {
+        features: FeaturesSelector::Features(vec![]),
+        no_default_features: false,
+    };
+
+    let unit = ops::generate_compilation_units(&resolve, &features, &ws)
+        .unwrap()
+        .into_iter()
+        .find_map(|unit| match unit {
+            CompilationUnit::Cairo(unit)
+                if unit.main_component().target_kind() == TargetKind::LIB =>
+            {
+                Some(unit)
+            }
+            _ => None,
+        })
+        .unwrap();
+
+    build_scarb_root_database(&unit).unwrap()
+}
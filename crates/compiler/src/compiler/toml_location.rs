@@ -0,0 +1,158 @@
+//! Location of the keys and the values of a TOML document.
+//!
+//! The manifests and the configuration files are deserialized without their spans, this
+//! module parses them again to report the line of a declaration or of an error.
+
+use toml_edit::{ImDocument, Item, TableLike, Value};
+
+/// A TOML document parsed with the spans of its keys and values.
+#[derive(Debug)]
+pub struct TomlLocator<'a> {
+    content: &'a str,
+    document: ImDocument<&'a str>,
+}
+
+impl<'a> TomlLocator<'a> {
+    /// Parses the content of a TOML document, `None` if it's invalid.
+    pub fn parse(content: &'a str) -> Option<Self> {
+        let document = ImDocument::parse(content).ok()?;
+        Some(Self { content, document })
+    }
+
+    /// Returns the line (1-based) of a key of a table, declared as a key of the table, as a
+    /// dotted key or as the header of a sub-table.
+    ///
+    /// # Arguments
+    ///
+    /// * `table` - The path of the table, like `["namespace", "mappings"]`.
+    /// * `key` - The key to locate.
+    pub fn key_line(&self, table: &[&str], key: &str) -> Option<usize> {
+        let (key, _) = self.table(table)?.get_key_value(key)?;
+        Some(self.line(key.span()?.start))
+    }
+
+    /// Returns the line (1-based) of the first string equal to the given value in a table,
+    /// searched in the values of its keys, its arrays and its sub-tables.
+    ///
+    /// # Arguments
+    ///
+    /// * `table` - The path of the table, like `["migration"]`.
+    /// * `value` - The string to locate.
+    pub fn value_line(&self, table: &[&str], value: &str) -> Option<usize> {
+        let offset = find_in_table(self.table(table)?, value)?;
+        Some(self.line(offset))
+    }
+
+    /// Returns the text of a line (1-based), trimmed.
+    pub fn line_text(&self, line: usize) -> Option<&'a str> {
+        self.content
+            .lines()
+            .nth(line.checked_sub(1)?)
+            .map(str::trim)
+    }
+
+    /// Returns the table at the given path, the root table if the path is empty.
+    fn table(&self, path: &[&str]) -> Option<&dyn TableLike> {
+        path.iter()
+            .try_fold(self.document.as_table() as &dyn TableLike, |table, key| {
+                table.get(key)?.as_table_like()
+            })
+    }
+
+    /// Returns the line (1-based) of an offset of the content.
+    fn line(&self, offset: usize) -> usize {
+        self.content[..offset].matches('\n').count() + 1
+    }
+}
+
+/// Returns the offset of the first string equal to the given value in a table.
+fn find_in_table(table: &dyn TableLike, value: &str) -> Option<usize> {
+    table.iter().find_map(|(_, item)| find_in_item(item, value))
+}
+
+/// Returns the offset of the first string equal to the given value in an item.
+fn find_in_item(item: &Item, value: &str) -> Option<usize> {
+    match item {
+        Item::Value(v) => find_in_value(v, value),
+        Item::Table(table) => find_in_table(table, value),
+        Item::ArrayOfTables(tables) => tables.iter().find_map(|t| find_in_table(t, value)),
+        Item::None => None,
+    }
+}
+
+/// Returns the offset of the first string equal to the given value in a value.
+fn find_in_value(v: &Value, value: &str) -> Option<usize> {
+    match v {
+        Value::String(s) if s.value() == value => Some(s.span()?.start),
+        Value::Array(array) => array.iter().find_map(|v| find_in_value(v, value)),
+        Value::InlineTable(table) => find_in_table(table, value),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONTENT: &str = r#"
+[namespace]
+default = "ns"
+mappings = { "ns-M" = ["ns-M-model"], ns = ["ns-model"] }
+
+[writers]
+"ns-Position" = [
+    "ns-actions",
+    "ns-other",
+]
+
+[dependencies]
+dojo.path = "../dojo"
+
+[workspace.dependencies.dojo]
+path = "../dojo"
+"#;
+
+    #[test]
+    fn test_key_line() {
+        let locator = TomlLocator::parse(CONTENT).unwrap();
+
+        assert_eq!(locator.key_line(&["namespace"], "default"), Some(3));
+        assert_eq!(locator.key_line(&["namespace", "mappings"], "ns"), Some(4));
+        assert_eq!(locator.key_line(&["writers"], "ns-Position"), Some(7));
+        assert_eq!(locator.key_line(&["dependencies"], "dojo"), Some(13));
+        assert_eq!(
+            locator.key_line(&["workspace", "dependencies"], "dojo"),
+            Some(15)
+        );
+
+        assert_eq!(locator.key_line(&["writers"], "ns"), None);
+        assert_eq!(locator.key_line(&["owners"], "ns-Position"), None);
+    }
+
+    #[test]
+    fn test_value_line() {
+        let locator = TomlLocator::parse(CONTENT).unwrap();
+
+        assert_eq!(locator.value_line(&["writers"], "ns-other"), Some(9));
+        assert_eq!(
+            locator.value_line(&["namespace", "mappings"], "ns-model"),
+            Some(4)
+        );
+        assert_eq!(locator.value_line(&["namespace"], "ns-M-model"), Some(4));
+
+        assert_eq!(locator.value_line(&["writers"], "ns"), None);
+    }
+
+    #[test]
+    fn test_line_text() {
+        let locator = TomlLocator::parse(CONTENT).unwrap();
+
+        assert_eq!(locator.line_text(15), Some("[workspace.dependencies.dojo]"));
+        assert_eq!(locator.line_text(0), None);
+    }
+
+    #[test]
+    fn test_invalid_document() {
+        assert!(TomlLocator::parse("[namespace").is_none());
+    }
+}
@@ -1,25 +1,56 @@
 //! Version information for the Dojo compiler.
 
+use std::fmt;
 use std::str::FromStr;
 
 use anyhow::Result;
 use camino::Utf8PathBuf;
 use scarb::core::{Package, TomlManifest, Workspace};
+use scarb::ops::WorkspaceResolve;
 use semver::Version;
+use serde::{Deserialize, Serialize};
 use tracing::trace;
 
+use super::messages::{self, DiagnosticMessage};
+use super::toml_location::TomlLocator;
+use crate::diagnostic_codes;
+
+/// Versions of the Dojo compiler and of the Cairo toolchain it's built with.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompilerVersion {
+    pub dojo: String,
+    pub scarb: String,
+    pub cairo: String,
+    pub sierra: String,
+}
+
+impl CompilerVersion {
+    /// Returns the versions of the current compiler.
+    pub fn current() -> Self {
+        let scarb_version = scarb::version::get();
+
+        Self {
+            dojo: env!("CARGO_PKG_VERSION").to_string(),
+            scarb: scarb_version.version.to_string(),
+            cairo: scarb_version.cairo.version.to_string(),
+            sierra: scarb_version.sierra.version.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for CompilerVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}\nscarb: {}\ncairo: {}\nsierra: {}",
+            self.dojo, self.scarb, self.cairo, self.sierra
+        )
+    }
+}
+
 /// Generates the version string for the Dojo compiler.
 pub fn generate_version() -> String {
-    const DOJO_VERSION: &str = env!("CARGO_PKG_VERSION");
-    let scarb_version = scarb::version::get().version;
-    let scarb_sierra_version = scarb::version::get().sierra.version;
-    let scarb_cairo_version = scarb::version::get().cairo.version;
-
-    let version_string = format!(
-        "{}\nscarb: {}\ncairo: {}\nsierra: {}",
-        DOJO_VERSION, scarb_version, scarb_cairo_version, scarb_sierra_version,
-    );
-    version_string
+    CompilerVersion::current().to_string()
 }
 
 /// Verifies that the Cairo version specified in the manifest file is compatible with the current
@@ -52,52 +83,243 @@ pub fn verify_cairo_version_compatibility(manifest_path: &Utf8PathBuf) -> Result
     Ok(())
 }
 
-/// Checks if the package has a compatible version of dojo-core.
+/// Name of the dojo core package.
+pub const DOJO_PACKAGE_NAME: &str = "dojo";
+
+/// The range of dojo core versions supported by the compiler: the versions with the same major
+/// and minor versions as the compiler, whatever the patch and pre-release.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DojoCompatibility {
+    pub major: u64,
+    pub minor: u64,
+}
+
+impl DojoCompatibility {
+    /// Returns the compatibility range of the current compiler.
+    pub fn current() -> Self {
+        let version =
+            Version::parse(env!("CARGO_PKG_VERSION")).expect("Compiler version is valid semver.");
+
+        Self::for_compiler(&version)
+    }
+
+    /// Returns the compatibility range of the compiler with the given version.
+    pub fn for_compiler(version: &Version) -> Self {
+        Self {
+            major: version.major,
+            minor: version.minor,
+        }
+    }
+
+    /// Checks if the dojo core version is supported.
+    pub fn matches(&self, version: &Version) -> bool {
+        version.major == self.major && version.minor == self.minor
+    }
+}
+
+impl fmt::Display for DojoCompatibility {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.x", self.major, self.minor)
+    }
+}
+
+/// Checks if the package depends on a version of dojo core supported by the compiler.
+///
+/// The version is the one declared by the resolved dojo package, whatever the kind of
+/// the dependency (path, git or registry). On mismatch, the dependency declarations are
+/// reported with their manifest, in the package manifest and in the workspace manifest
+/// for workspace dependencies.
 ///
-/// In case of a workspace with multiple packages, each package is individually checked
-/// and the workspace manifest path is returned in case of virtual workspace.
-pub fn check_package_dojo_version(ws: &Workspace<'_>, package: &Package) -> anyhow::Result<()> {
-    if let Some(dojo_dep) = package
+/// # Arguments
+///
+/// * `ws` - The workspace of the package.
+/// * `package` - The package to check.
+/// * `resolve` - The resolution of the workspace.
+/// * `allow_incompatible` - Only warns on mismatch, instead of failing.
+pub fn check_package_dojo_version(
+    ws: &Workspace<'_>,
+    package: &Package,
+    resolve: &WorkspaceResolve,
+    allow_incompatible: bool,
+) -> Result<()> {
+    if !package
         .manifest
         .summary
         .dependencies
         .iter()
-        .find(|dep| dep.name.as_str() == "dojo")
+        .any(|dep| dep.name.as_str() == DOJO_PACKAGE_NAME)
     {
-        let dojo_version = env!("CARGO_PKG_VERSION");
+        return Ok(());
+    }
+
+    let Some(dojo_package) = resolve
+        .packages
+        .values()
+        .find(|p| p.id.name.as_str() == DOJO_PACKAGE_NAME)
+    else {
+        return Ok(());
+    };
+
+    let dojo_version = &dojo_package.id.version;
+    let compatibility = DojoCompatibility::current();
 
-        let dojo_dep_str = dojo_dep.to_string();
+    trace!(package = %package.id, %dojo_version, %compatibility, "Dojo version compatibility.");
+
+    if compatibility.matches(dojo_version) {
+        return Ok(());
+    }
+
+    let mut message = format!(
+        "Package `{}` depends on dojo `{dojo_version}` ({}), but the compiler `{}` only \
+         supports dojo `{compatibility}`. Please verify your dojo dependency:",
+        package.id.name,
+        dojo_package.id.source_id,
+        env!("CARGO_PKG_VERSION"),
+    );
 
-        // Only in case of git dependency with an explicit tag, we check if the tag is the same as
-        // the current version.
-        if dojo_dep_str.contains("git+")
-            && dojo_dep_str.contains("tag=v")
-            && !dojo_dep_str.contains(dojo_version)
+    for (manifest_path, line, declaration) in dojo_dependency_declarations(ws, package) {
+        message.push_str(&format!("\n --> {manifest_path}:{line}: {declaration}"));
+    }
+
+    if allow_incompatible {
+        messages::emit(
+            &ws.config().ui(),
+            DiagnosticMessage::warning(diagnostic_codes::INCOMPATIBLE_DOJO_VERSION.code, message),
+        );
+        return Ok(());
+    }
+
+    anyhow::bail!("{message}\nUse `--allow-incompatible-dojo` to build anyway, at your own risk.")
+}
+
+/// Returns the declarations of the dojo dependency of the package, with their manifest
+/// and line, following the workspace inheritance.
+fn dojo_dependency_declarations(
+    ws: &Workspace<'_>,
+    package: &Package,
+) -> Vec<(Utf8PathBuf, usize, String)> {
+    let mut declarations = vec![];
+
+    let package_manifest = package.manifest_path().to_path_buf();
+
+    let Some((line, declaration)) =
+        std::fs::read_to_string(&package_manifest)
+            .ok()
+            .and_then(|content| {
+                find_dependency_declaration(&content, &DEPENDENCIES_SECTIONS, DOJO_PACKAGE_NAME)
+            })
+    else {
+        return declarations;
+    };
+
+    let inherited = declaration.contains("workspace");
+    declarations.push((package_manifest.clone(), line, declaration));
+
+    let ws_manifest = ws.manifest_path().to_path_buf();
+
+    if inherited && ws_manifest != package_manifest {
+        if let Some((line, declaration)) =
+            std::fs::read_to_string(&ws_manifest)
+                .ok()
+                .and_then(|content| {
+                    find_dependency_declaration(
+                        &content,
+                        &[WORKSPACE_DEPENDENCIES_SECTION],
+                        DOJO_PACKAGE_NAME,
+                    )
+                })
         {
-            if let Ok(cp) = ws.current_package() {
-                let path = if cp.id == package.id {
-                    package.manifest_path()
-                } else {
-                    ws.manifest_path()
-                };
-
-                anyhow::bail!(
-                    "Found dojo-core version mismatch: expected {}. Please verify your dojo \
-                     dependency in {}",
-                    dojo_version,
-                    path
-                )
-            } else {
-                // Virtual workspace.
-                anyhow::bail!(
-                    "Found dojo-core version mismatch: expected {}. Please verify your dojo \
-                     dependency in {}",
-                    dojo_version,
-                    ws.manifest_path()
-                )
-            }
+            declarations.push((ws_manifest, line, declaration));
         }
     }
 
-    Ok(())
+    declarations
+}
+
+/// Sections of a package manifest declaring dependencies.
+const DEPENDENCIES_SECTIONS: [&str; 2] = ["dependencies", "dev-dependencies"];
+/// Section of a workspace manifest declaring the dependencies inherited by the members.
+const WORKSPACE_DEPENDENCIES_SECTION: &str = "workspace.dependencies";
+
+/// Returns the line (1-based) and the text of the declaration of a dependency in the given
+/// sections of a manifest, either as a key of the section or as a `[section.name]` table.
+fn find_dependency_declaration(
+    content: &str,
+    sections: &[&str],
+    name: &str,
+) -> Option<(usize, String)> {
+    let locator = TomlLocator::parse(content)?;
+
+    sections.iter().find_map(|section| {
+        let table = section.split('.').collect::<Vec<_>>();
+        let line = locator.key_line(&table, name)?;
+
+        Some((line, locator.line_text(line)?.to_string()))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dojo_compatibility() {
+        let compatibility = DojoCompatibility::for_compiler(&Version::parse("1.0.0-rc.1").unwrap());
+
+        assert_eq!(compatibility.to_string(), "1.0.x");
+
+        for supported in ["1.0.0-rc.0", "1.0.0", "1.0.9"] {
+            assert!(
+                compatibility.matches(&Version::parse(supported).unwrap()),
+                "{supported}"
+            );
+        }
+
+        for unsupported in ["0.7.4", "1.1.0", "2.0.0"] {
+            assert!(
+                !compatibility.matches(&Version::parse(unsupported).unwrap()),
+                "{unsupported}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_find_dependency_declaration() {
+        let manifest = r#"
+[package]
+name = "game"
+version = "0.1.0"
+
+[dependencies]
+starknet = "2.8.4"
+dojo_utils = { path = "../utils" }
+dojo = { git = "https://github.com/dojoengine/dojo", tag = "v1.0.0" }
+
+[dev-dependencies]
+"dojo" = "1.0.0"
+"#;
+
+        assert_eq!(
+            find_dependency_declaration(manifest, &DEPENDENCIES_SECTIONS, "dojo"),
+            Some((
+                9,
+                r#"dojo = { git = "https://github.com/dojoengine/dojo", tag = "v1.0.0" }"#
+                    .to_string()
+            ))
+        );
+
+        let workspace = r#"
+[workspace.dependencies.dojo]
+path = "../dojo"
+"#;
+
+        assert_eq!(
+            find_dependency_declaration(workspace, &[WORKSPACE_DEPENDENCIES_SECTION], "dojo"),
+            Some((2, "[workspace.dependencies.dojo]".to_string()))
+        );
+        assert_eq!(
+            find_dependency_declaration("dojo = \"1.0.0\"", &DEPENDENCIES_SECTIONS, "dojo"),
+            None
+        );
+    }
 }
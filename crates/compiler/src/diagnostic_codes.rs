@@ -0,0 +1,660 @@
+//! Registry of the stable codes of the Dojo diagnostics.
+//!
+//! Each diagnostic emitted by the Dojo plugin or by the compiler has a code (`DOJO012`), which
+//! doesn't change across versions. The codes `DOJO0xx` are emitted by the Cairo plugin while
+//! expanding the Dojo attributes, derives and macros, the codes `DOJO1xx` by the compiler.
+//!
+//! As the plugin diagnostics of Cairo don't carry a code, the code is the prefix of the message:
+//!
+//! ```text
+//! error: [DOJO006] Model must define at least one #[key] attribute
+//! ```
+//!
+//! The long-form description of a code is printed by the `explain` command.
+
+use std::fmt;
+
+/// A stable diagnostic code, with its documentation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DiagnosticCode {
+    /// The code, like `DOJO012`.
+    pub code: &'static str,
+    /// A one line summary of the diagnostic.
+    pub title: &'static str,
+    /// The long-form description of the diagnostic.
+    pub explanation: &'static str,
+    /// An example of code raising the diagnostic.
+    pub wrong: &'static str,
+    /// The same example, fixed.
+    pub right: &'static str,
+}
+
+impl DiagnosticCode {
+    /// Formats a diagnostic message, prefixed with the code.
+    pub fn message(&self, message: impl fmt::Display) -> String {
+        format!("[{}] {message}", self.code)
+    }
+}
+
+impl fmt::Display for DiagnosticCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}: {}", self.code, self.title)?;
+        writeln!(f)?;
+        writeln!(f, "{}", self.explanation)?;
+        writeln!(f)?;
+        writeln!(f, "Wrong:")?;
+        writeln!(f)?;
+        writeln!(f, "{}", indent(self.wrong))?;
+        writeln!(f)?;
+        writeln!(f, "Right:")?;
+        writeln!(f)?;
+        write!(f, "{}", indent(self.right))
+    }
+}
+
+fn indent(code: &str) -> String {
+    code.lines()
+        .map(|line| {
+            if line.is_empty() {
+                String::new()
+            } else {
+                format!("    {line}")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+pub const INVALID_RESOURCE_NAME: DiagnosticCode = DiagnosticCode {
+    code: "DOJO001",
+    title: "Invalid name or namespace of a resource",
+    explanation: "The name and the namespace of a model, an event or a contract are part of \
+                  its tag (`namespace-name`), from which its selector is computed. They can \
+                  only contain characters (a-z/A-Z), digits (0-9) and underscore (_).",
+    wrong: "#[dojo::contract(namespace: \"my-namespace\")]\nmod actions {}",
+    right: "#[dojo::contract(namespace: \"my_namespace\")]\nmod actions {}",
+};
+
+pub const DUPLICATE_ATTRIBUTE_ARGUMENT: DiagnosticCode = DiagnosticCode {
+    code: "DOJO002",
+    title: "Duplicate argument of a Dojo attribute",
+    explanation: "An argument of `dojo::model`, `dojo::event` or `dojo::contract` is given \
+                  more than once. Each argument can only be given once.",
+    wrong: "#[dojo::model(version: 1, version: 1)]\nstruct Position {\n    #[key]\n    \
+            player: ContractAddress,\n    x: u32,\n}",
+    right: "#[dojo::model(version: 1)]\nstruct Position {\n    #[key]\n    player: \
+            ContractAddress,\n    x: u32,\n}",
+};
+
+pub const UNEXPECTED_ATTRIBUTE_ARGUMENT: DiagnosticCode = DiagnosticCode {
+    code: "DOJO003",
+    title: "Unexpected argument of a Dojo attribute",
+    explanation: "The arguments of the Dojo attributes are named (`name: value`) and limited \
+                  to the ones supported by the attribute: `version` and `namespace` for \
+                  `dojo::model`, `version`, `namespace` and `historical` for `dojo::event`, \
+                  `namespace` for `dojo::contract`.",
+    wrong: "#[dojo::model(my_arg: 1)]\nstruct Position {\n    #[key]\n    player: \
+            ContractAddress,\n    x: u32,\n}",
+    right: "#[dojo::model(namespace: \"my_namespace\")]\nstruct Position {\n    #[key]\n    \
+            player: ContractAddress,\n    x: u32,\n}",
+};
+
+pub const INVALID_ATTRIBUTE_ARGUMENT: DiagnosticCode = DiagnosticCode {
+    code: "DOJO004",
+    title: "Invalid value of a Dojo attribute argument",
+    explanation: "The value of an argument of a Dojo attribute has not the expected type: \
+                  `version` is an integer, `namespace` a string and `historical` a boolean.",
+    wrong: "#[dojo::model(version: \"1\")]\nstruct Position {\n    #[key]\n    player: \
+            ContractAddress,\n    x: u32,\n}",
+    right: "#[dojo::model(version: 1)]\nstruct Position {\n    #[key]\n    player: \
+            ContractAddress,\n    x: u32,\n}",
+};
+
+pub const UNSUPPORTED_VERSION: DiagnosticCode = DiagnosticCode {
+    code: "DOJO005",
+    title: "Unsupported version of a model or an event",
+    explanation: "The `version` argument selects the storage layout of a model or an event. \
+                  Only the versions known by the compiler are supported.",
+    wrong: "#[dojo::model(version: 2)]\nstruct Position {\n    #[key]\n    player: \
+            ContractAddress,\n    x: u32,\n}",
+    right: "#[dojo::model(version: 1)]\nstruct Position {\n    #[key]\n    player: \
+            ContractAddress,\n    x: u32,\n}",
+};
+
+pub const MODEL_WITHOUT_KEY: DiagnosticCode = DiagnosticCode {
+    code: "DOJO006",
+    title: "Model without key",
+    explanation: "The entity of a model is identified by its keys, the members annotated with \
+                  `#[key]`. A model must define at least one key.",
+    wrong: "#[dojo::model]\nstruct Position {\n    player: ContractAddress,\n    x: u32,\n}",
+    right: "#[dojo::model]\nstruct Position {\n    #[key]\n    player: ContractAddress,\n    \
+            x: u32,\n}",
+};
+
+pub const MODEL_WITHOUT_VALUE: DiagnosticCode = DiagnosticCode {
+    code: "DOJO007",
+    title: "Model without value",
+    explanation: "Only the members of a model which are not keys are stored. A model must \
+                  define at least one member that is not a key.",
+    wrong: "#[dojo::model]\nstruct Position {\n    #[key]\n    player: ContractAddress,\n}",
+    right: "#[dojo::model]\nstruct Position {\n    #[key]\n    player: ContractAddress,\n    \
+            x: u32,\n}",
+};
+
+pub const EVENT_WITHOUT_KEY: DiagnosticCode = DiagnosticCode {
+    code: "DOJO008",
+    title: "Event without key",
+    explanation: "The keys of an event, the members annotated with `#[key]`, are emitted as \
+                  the keys of the Starknet event. An event must define at least one key.",
+    wrong: "#[dojo::event]\nstruct Moved {\n    player: ContractAddress,\n    x: u32,\n}",
+    right: "#[dojo::event]\nstruct Moved {\n    #[key]\n    player: ContractAddress,\n    x: \
+            u32,\n}",
+};
+
+pub const EVENT_WITHOUT_VALUE: DiagnosticCode = DiagnosticCode {
+    code: "DOJO009",
+    title: "Event without value",
+    explanation: "An event must define at least one member that is not a key, emitted as the \
+                  data of the Starknet event.",
+    wrong: "#[dojo::event]\nstruct Moved {\n    #[key]\n    player: ContractAddress,\n}",
+    right: "#[dojo::event]\nstruct Moved {\n    #[key]\n    player: ContractAddress,\n    x: \
+            u32,\n}",
+};
+
+pub const UNSUPPORTED_KEY_TYPE: DiagnosticCode = DiagnosticCode {
+    code: "DOJO010",
+    title: "Unsupported type of key",
+    explanation: "The keys of a model or an event of a primitive type must be serialized into \
+                  one felt. `u256` is a struct of 2 `u128`, hence not supported as a key.",
+    wrong: "#[dojo::model]\nstruct Balance {\n    #[key]\n    token_id: u256,\n    amount: \
+            u128,\n}",
+    right: "#[dojo::model]\nstruct Balance {\n    #[key]\n    token_id: felt252,\n    amount: \
+            u128,\n}",
+};
+
+pub const PACKED_EVENT: DiagnosticCode = DiagnosticCode {
+    code: "DOJO011",
+    title: "Event deriving IntrospectPacked",
+    explanation: "The layout of an event is always introspected. An event must derive \
+                  `Introspect`, not `IntrospectPacked`.",
+    wrong: "#[derive(Drop, Serde, IntrospectPacked)]\n#[dojo::event]\nstruct Moved {\n    \
+            #[key]\n    player: ContractAddress,\n    x: u32,\n}",
+    right: "#[derive(Drop, Serde, Introspect)]\n#[dojo::event]\nstruct Moved {\n    #[key]\n    \
+            player: ContractAddress,\n    x: u32,\n}",
+};
+
+pub const MODEL_AND_EVENT: DiagnosticCode = DiagnosticCode {
+    code: "DOJO012",
+    title: "Struct both model and event",
+    explanation: "A struct is either a model or an event. It can only have one of the \
+                  `dojo::model` or `dojo::event` attributes. Define two structs to store and \
+                  emit the same data.",
+    wrong: "#[dojo::model]\n#[dojo::event]\nstruct Position {\n    #[key]\n    player: \
+            ContractAddress,\n    x: u32,\n}",
+    right: "#[dojo::model]\nstruct Position {\n    #[key]\n    player: ContractAddress,\n    \
+            x: u32,\n}\n\n#[dojo::event]\nstruct Moved {\n    #[key]\n    player: \
+            ContractAddress,\n    x: u32,\n}",
+};
+
+pub const INTERFACE_NON_FUNCTION: DiagnosticCode = DiagnosticCode {
+    code: "DOJO013",
+    title: "Non function item in a dojo::interface",
+    explanation: "A `dojo::interface` is expanded into a `starknet::interface`, where the \
+                  `world` parameter of each function is injected. It can only contain \
+                  functions.",
+    wrong: "#[dojo::interface]\ntrait IActions {\n    const MAX: u32;\n    fn spawn(ref \
+            world: IWorldDispatcher);\n}",
+    right: "#[dojo::interface]\ntrait IActions {\n    fn spawn(ref world: \
+            IWorldDispatcher);\n}",
+};
+
+pub const SELF_AND_WORLD_PARAMS: DiagnosticCode = DiagnosticCode {
+    code: "DOJO014",
+    title: "`self` and `world` parameters together",
+    explanation: "The `world` parameter is injected by the Dojo plugin, replaced by `self` \
+                  and a `world` variable read from the contract state. A function can use \
+                  either `self` or `world`, not both.",
+    wrong: "fn spawn(ref world: IWorldDispatcher, self: @ContractState) {}",
+    right: "fn spawn(ref world: IWorldDispatcher) {}",
+};
+
+pub const MULTIPLE_WORLD_PARAMS: DiagnosticCode = DiagnosticCode {
+    code: "DOJO015",
+    title: "Several `world` parameters",
+    explanation: "A function can only have one `world` parameter, injected by the Dojo \
+                  plugin.",
+    wrong: "fn spawn(ref world: IWorldDispatcher, other: @IWorldDispatcher) {}",
+    right: "fn spawn(ref world: IWorldDispatcher) {}",
+};
+
+pub const WORLD_PARAM_NOT_FIRST: DiagnosticCode = DiagnosticCode {
+    code: "DOJO016",
+    title: "`world` parameter not first",
+    explanation: "The `world` parameter replaces the `self` parameter of the contract \
+                  functions, it must be the first parameter.",
+    wrong: "fn move(direction: Direction, ref world: IWorldDispatcher) {}",
+    right: "fn move(ref world: IWorldDispatcher, direction: Direction) {}",
+};
+
+pub const WORLD_PARAM_NOT_SNAPSHOT: DiagnosticCode = DiagnosticCode {
+    code: "DOJO017",
+    title: "`world` parameter neither `ref` nor snapshot",
+    explanation: "The `world` parameter is either `ref world: IWorldDispatcher` for the \
+                  external functions, or a snapshot `world: @IWorldDispatcher` for the view \
+                  functions.",
+    wrong: "fn position(world: IWorldDispatcher) -> Position;",
+    right: "fn position(world: @IWorldDispatcher) -> Position;",
+};
+
+pub const INIT_RETURN_TYPE: DiagnosticCode = DiagnosticCode {
+    code: "DOJO018",
+    title: "`dojo_init` function with a return type",
+    explanation: "The `dojo_init` function of a contract is called by the world when the \
+                  contract is initialized, and its return value is ignored. It cannot have a \
+                  return type.",
+    wrong: "fn dojo_init(ref world: IWorldDispatcher) -> bool {\n    true\n}",
+    right: "fn dojo_init(ref world: IWorldDispatcher) {}",
+};
+
+pub const WORLD_IN_GENERATE_TRAIT: DiagnosticCode = DiagnosticCode {
+    code: "DOJO019",
+    title: "`world` parameter in a generated trait",
+    explanation: "The `world` parameter can't be injected in the functions of an impl with \
+                  `#[generate_trait]`, as the trait is generated before the injection. Use \
+                  `self` and `self.world()` instead.",
+    wrong: "#[generate_trait]\nimpl InternalImpl of InternalTrait {\n    fn spawn(ref world: \
+            IWorldDispatcher) {}\n}",
+    right: "#[generate_trait]\nimpl InternalImpl of InternalTrait {\n    fn spawn(ref self: \
+            ContractState) {\n        let world = self.world();\n    }\n}",
+};
+
+pub const UNSUPPORTED_DERIVE_ITEM: DiagnosticCode = DiagnosticCode {
+    code: "DOJO020",
+    title: "Dojo derive on an item other than a struct or an enum",
+    explanation: "The Dojo derives (`Introspect`, `IntrospectPacked` and `Print`) are only \
+                  supported on structs and enums.",
+    wrong: "#[derive(Introspect)]\ntype Position = (u32, u32);",
+    right: "#[derive(Introspect)]\nstruct Position {\n    x: u32,\n    y: u32,\n}",
+};
+
+pub const MISSING_DERIVE_ARGS: DiagnosticCode = DiagnosticCode {
+    code: "DOJO021",
+    title: "`derive` attribute without arguments",
+    explanation: "A `derive` attribute must list the traits to derive.",
+    wrong: "#[derive]\nstruct Position {\n    x: u32,\n}",
+    right: "#[derive(Drop, Serde)]\nstruct Position {\n    x: u32,\n}",
+};
+
+pub const INTROSPECT_AND_PACKED: DiagnosticCode = DiagnosticCode {
+    code: "DOJO022",
+    title: "Introspect and IntrospectPacked derived together",
+    explanation: "`Introspect` and `IntrospectPacked` both implement the `Introspect` trait, \
+                  with a different layout. Only one of them can be derived.",
+    wrong: "#[derive(Introspect, IntrospectPacked)]\nstruct Position {\n    x: u32,\n}",
+    right: "#[derive(IntrospectPacked)]\nstruct Position {\n    x: u32,\n}",
+};
+
+pub const UNSUPPORTED_VARIANT_TYPE: DiagnosticCode = DiagnosticCode {
+    code: "DOJO023",
+    title: "Unsupported type of enum variant",
+    explanation: "The data of an introspected enum variant must be a type path or a tuple.",
+    wrong: "#[derive(Introspect)]\nenum Item {\n    Sword: [u8; 2],\n}",
+    right: "#[derive(Introspect)]\nenum Item {\n    Sword: (u8, u8),\n}",
+};
+
+pub const OPTION_OF_TUPLE: DiagnosticCode = DiagnosticCode {
+    code: "DOJO024",
+    title: "Option of a tuple",
+    explanation: "`Option<T>` cannot be introspected when `T` is a tuple. Prefer using a \
+                  struct.",
+    wrong: "#[derive(Introspect)]\nstruct Player {\n    target: Option<(u32, u32)>,\n}",
+    right: "#[derive(Introspect)]\nstruct Vec2 {\n    x: u32,\n    y: u32,\n}\n\n\
+            #[derive(Introspect)]\nstruct Player {\n    target: Option<Vec2>,\n}",
+};
+
+pub const PACKED_ARRAY: DiagnosticCode = DiagnosticCode {
+    code: "DOJO025",
+    title: "Array in a packed layout",
+    explanation: "A packed layout has a fixed size. Arrays and byte arrays have a dynamic \
+                  size, they can't be packed.",
+    wrong: "#[derive(IntrospectPacked)]\nstruct Inventory {\n    items: Array<u32>,\n}",
+    right: "#[derive(Introspect)]\nstruct Inventory {\n    items: Array<u32>,\n}",
+};
+
+pub const PACKED_ENUM_LAYOUT: DiagnosticCode = DiagnosticCode {
+    code: "DOJO026",
+    title: "Packed enum with variants of different sizes",
+    explanation: "A packed enum has a fixed size. All the variants must have a fixed layout of \
+                  the same size.",
+    wrong: "#[derive(IntrospectPacked)]\nenum Action {\n    Idle,\n    Move: (u32, u32),\n}",
+    right: "#[derive(IntrospectPacked)]\nenum Action {\n    Idle: (u32, u32),\n    Move: (u32, \
+            u32),\n}",
+};
+
+pub const INVALID_MACRO_ARGUMENTS: DiagnosticCode = DiagnosticCode {
+    code: "DOJO027",
+    title: "Invalid arguments of a Dojo macro",
+    explanation: "The arguments of a Dojo inline macro are not the expected ones, in number or \
+                  in kind. The arguments are unnamed expressions: \
+                  `get!(world, keys, (models,))`, `set!(world, (models,))`, \
+                  `delete!(world, (models,))`, `emit!(world, (events,))`, \
+                  `selector_from_tag!(\"tag\")`, `spawn_test_world!([\"ns\"])` and \
+                  `get_models_test_class_hashes!([\"ns\"])`.",
+    wrong: "set!(world, position);",
+    right: "set!(world, (position,));",
+};
+
+pub const MISSING_MACRO_MODELS: DiagnosticCode = DiagnosticCode {
+    code: "DOJO028",
+    title: "Dojo macro without model",
+    explanation: "The `get!`, `set!`, `delete!` and `emit!` macros must be given at least one \
+                  model or event.",
+    wrong: "set!(world, ());",
+    right: "set!(world, (position, moves));",
+};
+
+pub const INVALID_TAG: DiagnosticCode = DiagnosticCode {
+    code: "DOJO029",
+    title: "Invalid tag",
+    explanation: "A tag identifies a resource by its namespace and its name, in the format of \
+                  `namespace-name`.",
+    wrong: "let selector = selector_from_tag!(\"Position\");",
+    right: "let selector = selector_from_tag!(\"dojo_examples-Position\");",
+};
+
+pub const INVALID_NAMESPACE_LIST: DiagnosticCode = DiagnosticCode {
+    code: "DOJO030",
+    title: "Invalid list of namespaces",
+    explanation: "The namespaces given to `spawn_test_world!` and \
+                  `get_models_test_class_hashes!` are a fixed size array of string literals.",
+    wrong: "let world = spawn_test_world!(\"ns1\");",
+    right: "let world = spawn_test_world!([\"ns1\"]);",
+};
+
+pub const MISSING_ANNOTATIONS: DiagnosticCode = DiagnosticCode {
+    code: "DOJO031",
+    title: "Models and namespaces not loaded",
+    explanation: "`spawn_test_world!` and `get_models_test_class_hashes!` read the models and \
+                  namespaces from the annotations written by the build. Build the project \
+                  before testing it.",
+    wrong: "$ sozo test",
+    right: "$ sozo build\n$ sozo test",
+};
+
+pub const COMMAND_FAILED: DiagnosticCode = DiagnosticCode {
+    code: "DOJO100",
+    title: "Command failed",
+    explanation: "A command of the compiler failed. The message gives the cause of the \
+                  failure.",
+    wrong: "$ sozo build --manifest-path missing/Scarb.toml",
+    right: "$ sozo build --manifest-path Scarb.toml",
+};
+
+pub const CONTRACT_NOT_FOUND: DiagnosticCode = DiagnosticCode {
+    code: "DOJO101",
+    title: "Contract selector matching no contract",
+    explanation: "A selector of `build-external-contracts` matched no contract of the \
+                  dependencies. Check the package and the path of the contract.",
+    wrong: "[[target.starknet-contract]]\nbuild-external-contracts = [\"dojo::world::wrld\"]",
+    right: "[[target.starknet-contract]]\nbuild-external-contracts = \
+            [\"dojo::world::world_contract::world\"]",
+};
+
+pub const UNSUPPORTED_LIBFUNC: DiagnosticCode = DiagnosticCode {
+    code: "DOJO102",
+    title: "Libfunc not allowed",
+    explanation: "A contract uses a libfunc which is not in the allowed libfuncs list of the \
+                  profile, and can't be declared on a public network. Remove the code using it, \
+                  or change the `[libfuncs]` list of the Dojo configuration.",
+    wrong: "[libfuncs]\nlist = \"audited\"",
+    right: "[libfuncs]\nlist = \"experimental\"",
+};
+
+pub const MISSING_WRITER_PERMISSION: DiagnosticCode = DiagnosticCode {
+    code: "DOJO103",
+    title: "Model written without writer permission",
+    explanation: "A contract writes a model, but isn't granted the writer permission on the \
+                  model or its namespace in the `[writers]` of the Dojo configuration. The \
+                  write fails at runtime.",
+    wrong: "[writers]\n\"ns-Position\" = []",
+    right: "[writers]\n\"ns-Position\" = [\"ns-actions\"]",
+};
+
+pub const BUDGET_EXCEEDED: DiagnosticCode = DiagnosticCode {
+    code: "DOJO104",
+    title: "Contract size budget exceeded",
+    explanation: "A contract exceeds a size budget of the Dojo configuration. Reduce the size \
+                  of the contract, by splitting it or removing unused code, or raise the \
+                  budget.",
+    wrong: "[budget]\nmax_casm_bytecode_size = 1000",
+    right: "[budget]\nmax_casm_bytecode_size = 81920",
+};
+
+pub const INCOMPATIBLE_DOJO_VERSION: DiagnosticCode = DiagnosticCode {
+    code: "DOJO105",
+    title: "Incompatible dojo version",
+    explanation: "The package depends on a version of the `dojo` library which is not \
+                  supported by the compiler. Use a version of `dojo` in the compatibility \
+                  range of the compiler.",
+    wrong: "[dependencies]\ndojo = { git = \"https://github.com/dojoengine/dojo\", tag = \
+            \"v0.1.0\" }",
+    right: "[dependencies]\ndojo = { path = \"../../crates/dojo\" }",
+};
+
+pub const LOCKED_CLASS_HASH_MISMATCH: DiagnosticCode = DiagnosticCode {
+    code: "DOJO106",
+    title: "Lock file generated by another compiler version",
+    explanation: "With `--locked`, the class hashes of the build are checked against the lock \
+                  file. The lock file was generated by another version of the compiler, and \
+                  the class hashes may change with the next build. Rebuild without `--locked` \
+                  to update the lock file.",
+    wrong: "$ sozo build --locked",
+    right: "$ sozo build",
+};
+
+pub const NESTED_CALLS_NOT_COVERED: DiagnosticCode = DiagnosticCode {
+    code: "DOJO107",
+    title: "Code of the called contracts not covered",
+    explanation: "The coverage only records the statements executed by the tests themselves. \
+                  The contracts called or deployed by a test, through a dispatcher or the \
+                  world, run in a nested entry point whose executed statements are not \
+                  reported by the test runner. Call the internal functions of the contract \
+                  from the tests to cover them.",
+    wrong: "let actions = IActionsDispatcher { contract_address };\nactions.spawn();",
+    right: "let mut state = actions::contract_state_for_testing();\n\
+            actions::InternalImpl::spawn(ref state);",
+};
+
+pub const GAS_REGRESSION: DiagnosticCode = DiagnosticCode {
+    code: "DOJO108",
+    title: "Gas regression",
+    explanation: "With `--check-gas`, a test uses more gas than in the gas snapshot of the \
+                  package, above the tolerance. Optimize the code, or update the snapshot with \
+                  `--gas-snapshot` if the increase is expected.",
+    wrong: "$ sozo test --check-gas",
+    right: "$ sozo test --gas-snapshot",
+};
+
+pub const MISSING_GAS_SNAPSHOT_TEST: DiagnosticCode = DiagnosticCode {
+    code: "DOJO109",
+    title: "Test missing from the gas snapshot",
+    explanation: "With `--check-gas`, a test has no gas in the gas snapshot of the package, \
+                  usually a new test. Update the snapshot with `--gas-snapshot` to check its \
+                  gas.",
+    wrong: "$ sozo test --check-gas",
+    right: "$ sozo test --gas-snapshot",
+};
+
+pub const TEST_FAILED: DiagnosticCode = DiagnosticCode {
+    code: "DOJO110",
+    title: "Test failed",
+    explanation: "A test panicked, or was expected to panic and passed. The message gives the \
+                  panic data of the test, decoded as short strings and byte arrays.",
+    wrong: "#[test]\nfn test_move() {\n    let x = 0;\n    assert!(x == 1, \"not moved\");\n}",
+    right: "#[test]\nfn test_move() {\n    let x = 1;\n    assert!(x == 1, \"not moved\");\n}",
+};
+
+pub const UNSUPPORTED_MACRO_ARGUMENTS: DiagnosticCode = DiagnosticCode {
+    code: "DOJO032",
+    title: "Named or bracketed arguments of a Dojo macro",
+    explanation: "The arguments of the Dojo inline macros are unnamed and given between \
+                  parentheses. The macros can't be called with brackets or braces, neither \
+                  with named arguments.",
+    wrong: "set![world, (position,)];",
+    right: "set!(world, (position,));",
+};
+
+pub const INVALID_MACRO_MODEL: DiagnosticCode = DiagnosticCode {
+    code: "DOJO033",
+    title: "Invalid model in a Dojo macro",
+    explanation: "The models given to `get!`, `set!`, `delete!` and `emit!` are variables, \
+                  paths or a tuple of them. A model with generic arguments must give them \
+                  unnamed, as types.",
+    wrong: "set!(world, (Position { player, x: 0 },));",
+    right: "let position = Position { player, x: 0 };\nset!(world, (position,));",
+};
+
+/// All the diagnostic codes, sorted by code.
+pub const REGISTRY: &[DiagnosticCode] = &[
+    INVALID_RESOURCE_NAME,
+    DUPLICATE_ATTRIBUTE_ARGUMENT,
+    UNEXPECTED_ATTRIBUTE_ARGUMENT,
+    INVALID_ATTRIBUTE_ARGUMENT,
+    UNSUPPORTED_VERSION,
+    MODEL_WITHOUT_KEY,
+    MODEL_WITHOUT_VALUE,
+    EVENT_WITHOUT_KEY,
+    EVENT_WITHOUT_VALUE,
+    UNSUPPORTED_KEY_TYPE,
+    PACKED_EVENT,
+    MODEL_AND_EVENT,
+    INTERFACE_NON_FUNCTION,
+    SELF_AND_WORLD_PARAMS,
+    MULTIPLE_WORLD_PARAMS,
+    WORLD_PARAM_NOT_FIRST,
+    WORLD_PARAM_NOT_SNAPSHOT,
+    INIT_RETURN_TYPE,
+    WORLD_IN_GENERATE_TRAIT,
+    UNSUPPORTED_DERIVE_ITEM,
+    MISSING_DERIVE_ARGS,
+    INTROSPECT_AND_PACKED,
+    UNSUPPORTED_VARIANT_TYPE,
+    OPTION_OF_TUPLE,
+    PACKED_ARRAY,
+    PACKED_ENUM_LAYOUT,
+    INVALID_MACRO_ARGUMENTS,
+    MISSING_MACRO_MODELS,
+    INVALID_TAG,
+    INVALID_NAMESPACE_LIST,
+    MISSING_ANNOTATIONS,
+    UNSUPPORTED_MACRO_ARGUMENTS,
+    INVALID_MACRO_MODEL,
+    COMMAND_FAILED,
+    CONTRACT_NOT_FOUND,
+    UNSUPPORTED_LIBFUNC,
+    MISSING_WRITER_PERMISSION,
+    BUDGET_EXCEEDED,
+    INCOMPATIBLE_DOJO_VERSION,
+    LOCKED_CLASS_HASH_MISMATCH,
+    NESTED_CALLS_NOT_COVERED,
+    GAS_REGRESSION,
+    MISSING_GAS_SNAPSHOT_TEST,
+    TEST_FAILED,
+];
+
+/// Finds a diagnostic code in the registry, ignoring the case.
+pub fn find(code: &str) -> Option<&'static DiagnosticCode> {
+    REGISTRY
+        .iter()
+        .find(|c| c.code.eq_ignore_ascii_case(code.trim()))
+}
+
+/// Extracts the code of a diagnostic message formatted with [`DiagnosticCode::message`].
+///
+/// The code may follow a prefix added by Cairo, like `Plugin diagnostic: `.
+pub fn code_of(message: &str) -> Option<&'static DiagnosticCode> {
+    let start = message.find("[DOJO")?;
+    let end = start + message[start..].find(']')?;
+
+    find(&message[start + 1..end])
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    #[test]
+    fn test_codes_are_unique() {
+        let mut codes = HashSet::new();
+
+        for code in REGISTRY {
+            assert!(
+                codes.insert(code.code),
+                "Duplicate diagnostic code {}.",
+                code.code
+            );
+        }
+    }
+
+    #[test]
+    fn test_codes_are_sorted_and_well_formed() {
+        for code in REGISTRY {
+            assert!(
+                code.code.len() == 7
+                    && code.code.starts_with("DOJO")
+                    && code.code[4..].chars().all(|c| c.is_ascii_digit()),
+                "Malformed diagnostic code {}.",
+                code.code
+            );
+        }
+
+        assert!(REGISTRY.windows(2).all(|w| w[0].code < w[1].code));
+    }
+
+    #[test]
+    fn test_codes_are_documented() {
+        for code in REGISTRY {
+            assert!(!code.title.is_empty(), "{} has no title.", code.code);
+            assert!(
+                !code.explanation.is_empty(),
+                "{} has no explanation.",
+                code.code
+            );
+            assert!(
+                !code.wrong.is_empty(),
+                "{} has no wrong example.",
+                code.code
+            );
+            assert!(
+                !code.right.is_empty(),
+                "{} has no right example.",
+                code.code
+            );
+            assert_ne!(
+                code.wrong, code.right,
+                "{} examples are identical.",
+                code.code
+            );
+        }
+    }
+
+    #[test]
+    fn test_code_of() {
+        let message = MODEL_WITHOUT_KEY.message("Model must define at least one #[key] attribute");
+        assert_eq!(
+            message,
+            "[DOJO006] Model must define at least one #[key] attribute"
+        );
+        assert_eq!(code_of(&message), Some(&MODEL_WITHOUT_KEY));
+
+        assert_eq!(
+            code_of("error: Plugin diagnostic: [DOJO029] Invalid tag."),
+            Some(&INVALID_TAG)
+        );
+        assert_eq!(code_of("[DOJO999] Unknown."), None);
+        assert_eq!(code_of("Identifier not found."), None);
+        assert_eq!(find("dojo012"), Some(&MODEL_AND_EVENT));
+    }
+}
@@ -4,6 +4,7 @@
 
 pub mod aux_data;
 pub mod compiler;
+pub mod diagnostic_codes;
 pub mod namespace_config;
 pub mod plugin;
 pub mod scarb_extensions;
@@ -15,6 +16,7 @@ pub const RESOURCE_METADATA_QUALIFIED_PATH: &str = "dojo::model::metadata::resou
 pub const CONTRACTS_DIR: &str = "contracts";
 pub const MODELS_DIR: &str = "models";
 pub const EVENTS_DIR: &str = "events";
+pub const STARKNET_CONTRACTS_DIR: &str = "starknet_contracts";
 pub const MANIFESTS_DIR: &str = "manifests";
 pub const MANIFESTS_BASE_DIR: &str = "base";
 
@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex, PoisonError};
 
 use anyhow::Result;
 use cairo_lang_filesystem::cfg::CfgSet;
@@ -17,8 +18,134 @@ pub fn is_name_valid(name: &str) -> bool {
     Regex::new(r"^[a-zA-Z0-9_]+$").unwrap().is_match(name)
 }
 
+/// Prefix of the mapping keys matching the Cairo path of the module declaring the resource.
+pub const PATH_PATTERN_PREFIX: &str = "path:";
+/// Prefix of the mapping keys matching the resource name.
+pub const NAME_PATTERN_PREFIX: &str = "name:";
+/// Prefix of the mapping keys matching the tag with a regular expression.
+pub const REGEX_PATTERN_PREFIX: &str = "re:";
+
+/// The rule of the namespace configuration that assigned the namespace of a resource.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MappingRule {
+    /// The namespace is explicitly set on the resource, and not mapped.
+    NoMapping,
+    /// The mapping of the exact tag or namespace.
+    Exact(String),
+    /// The mapping of a pattern key.
+    Pattern(String),
+    /// The mapping of the namespace of the tag.
+    Namespace(String),
+    /// No mapping, the default namespace is used.
+    Default,
+}
+
+impl std::fmt::Display for MappingRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MappingRule::NoMapping => write!(f, "explicit namespace (nomapping)"),
+            MappingRule::Exact(key) => write!(f, "exact mapping `{key}`"),
+            MappingRule::Pattern(key) => write!(f, "pattern mapping `{key}`"),
+            MappingRule::Namespace(key) => write!(f, "namespace mapping `{key}`"),
+            MappingRule::Default => write!(f, "default namespace"),
+        }
+    }
+}
+
+/// A pattern key of the namespace mappings.
+#[derive(Debug, Clone)]
+enum MappingPattern {
+    Path(Regex),
+    Name(Regex),
+    Tag(Regex),
+}
+
+impl MappingPattern {
+    /// Parses a mapping key, returning `None` if the key is an exact tag or namespace.
+    fn parse(key: &str) -> Result<Option<Self>> {
+        if let Some(glob) = key.strip_prefix(PATH_PATTERN_PREFIX) {
+            Ok(Some(Self::Path(glob_to_regex(glob)?)))
+        } else if let Some(glob) = key.strip_prefix(NAME_PATTERN_PREFIX) {
+            Ok(Some(Self::Name(glob_to_regex(glob)?)))
+        } else if let Some(regex) = key.strip_prefix(REGEX_PATTERN_PREFIX) {
+            if regex.is_empty() {
+                anyhow::bail!("empty regular expression");
+            }
+
+            Ok(Some(Self::Tag(Regex::new(regex)?)))
+        } else if key.contains(':') {
+            anyhow::bail!(
+                "unknown pattern kind, expected `{PATH_PATTERN_PREFIX}`, `{NAME_PATTERN_PREFIX}` or `{REGEX_PATTERN_PREFIX}`"
+            );
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Returns the pattern of a mapping key, `None` if the key is an exact tag or namespace,
+    /// or an invalid pattern, reported by [`NamespaceConfig::validate`].
+    ///
+    /// The configuration being built from the `CfgSet` on each plugin call, the patterns are
+    /// parsed once per key.
+    fn cached(key: &str) -> Option<Self> {
+        static PATTERNS: LazyLock<Mutex<HashMap<String, Option<MappingPattern>>>> =
+            LazyLock::new(Default::default);
+
+        if !key.contains(':') {
+            return None;
+        }
+
+        let mut patterns = PATTERNS.lock().unwrap_or_else(PoisonError::into_inner);
+
+        patterns
+            .entry(key.to_string())
+            .or_insert_with(|| Self::parse(key).ok().flatten())
+            .clone()
+    }
+
+    /// The precedence of the pattern kind, the lowest first.
+    fn precedence(&self) -> u8 {
+        match self {
+            Self::Path(_) => 0,
+            Self::Name(_) => 1,
+            Self::Tag(_) => 2,
+        }
+    }
+
+    fn matches(&self, tag: &str, name: &str, module_path: Option<&str>) -> bool {
+        match self {
+            Self::Path(re) => module_path.is_some_and(|path| re.is_match(path)),
+            Self::Name(re) => re.is_match(name),
+            Self::Tag(re) => re.is_match(tag),
+        }
+    }
+}
+
+/// Converts a glob pattern, where `*` matches any sequence of characters and `?` any
+/// single character, to an anchored regular expression.
+fn glob_to_regex(glob: &str) -> Result<Regex> {
+    if glob.is_empty() {
+        anyhow::bail!("empty pattern");
+    }
+
+    let mut regex = String::from("^");
+
+    for c in glob.chars() {
+        match c {
+            '*' => regex.push_str(".*"),
+            '?' => regex.push('.'),
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+
+    regex.push('$');
+
+    Ok(Regex::new(&regex)?)
+}
+
 /// Namespace configuration.
 #[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct NamespaceConfig {
     pub default: String,
     pub mappings: Option<HashMap<String, String>>,
@@ -48,6 +175,11 @@ impl NamespaceConfig {
         self
     }
 
+    /// Returns the mappings of tags, namespaces and patterns to namespaces.
+    pub fn mappings(&self) -> Option<&HashMap<String, String>> {
+        self.mappings.as_ref()
+    }
+
     /// Displays the namespace mappings as a string.
     pub fn display_mappings(&self) -> String {
         if let Some(mappings) = &self.mappings {
@@ -65,12 +197,13 @@ impl NamespaceConfig {
     /// namespace if no mapping was found.
     ///
     /// If the input is a tag, a first perfect match is checked. If no match
-    /// for the tag, then a check is done against the namespace of the tag.
+    /// for the tag, then the patterns are checked against the resource name and the
+    /// tag, and finally a check is done against the namespace of the tag.
     /// If the input is a namespace, a perfect match if checked.
     ///
     /// Examples:
-    /// - `get_mapping("armory-Flatbow")` first checks for `armory-Flatbow` tag, then for `armory`
-    ///   namespace in mapping keys.
+    /// - `get_mapping("armory-Flatbow")` first checks for `armory-Flatbow` tag, then for the
+    ///   `name:` and `re:` patterns, then for `armory` namespace in mapping keys.
     /// - `get_mapping("armory")` checks for `armory` namespace in mapping keys.
     ///
     /// # Arguments
@@ -81,23 +214,124 @@ impl NamespaceConfig {
     ///
     /// A [`String`] object containing the namespace.
     pub fn get_mapping(&self, tag_or_namespace: &str) -> String {
-        if let Some(namespace_from_tag) =
-            self.mappings.as_ref().and_then(|m| m.get(tag_or_namespace))
-        {
-            namespace_from_tag.clone()
-        } else if tag_or_namespace.contains('-') {
-            // TODO: we can't access the dojo-world/contracts from here as it belongs to a different
-            // feature. The naming module has to be relocated in more generic place,
-            // always available.
-            let (namespace, _) = tag_or_namespace.split_at(tag_or_namespace.rfind('-').unwrap());
-            self.mappings
-                .as_ref()
-                .and_then(|m| m.get(namespace))
-                .unwrap_or(&self.default)
-                .to_string()
-        } else {
-            self.default.clone()
+        self.resolve(tag_or_namespace, None).0
+    }
+
+    /// Gets the namespace for a given tag or namespace, along with the rule that assigned it.
+    ///
+    /// The rules are applied in this order, the first matching rule being used:
+    ///  1. the exact tag (or namespace if the input is a namespace),
+    ///  2. the `path:<glob>` patterns, matched against the Cairo path of the module declaring
+    ///     the resource,
+    ///  3. the `name:<glob>` patterns, matched against the resource name,
+    ///  4. the `re:<regex>` patterns, matched against the tag,
+    ///  5. the exact namespace of the tag,
+    ///  6. the default namespace.
+    ///
+    /// When several patterns of the same kind match, the longest pattern is used, the
+    /// patterns being compared alphabetically on equal length.
+    ///
+    /// # Arguments
+    ///
+    /// * `tag_or_namespace`: the tag or namespace to get the namespace for.
+    /// * `module_path`: the Cairo path of the module declaring the resource, if known.
+    pub fn resolve(
+        &self,
+        tag_or_namespace: &str,
+        module_path: Option<&str>,
+    ) -> (String, MappingRule) {
+        let default = || (self.default.clone(), MappingRule::Default);
+
+        let Some(mappings) = self.mappings.as_ref() else {
+            return default();
+        };
+
+        if let Some(namespace) = mappings.get(tag_or_namespace) {
+            return (
+                namespace.clone(),
+                MappingRule::Exact(tag_or_namespace.to_string()),
+            );
         }
+
+        // TODO: we can't access the dojo-world/contracts from here as it belongs to a different
+        // feature. The naming module has to be relocated in more generic place,
+        // always available.
+        let Some(separator) = tag_or_namespace.rfind('-') else {
+            return default();
+        };
+
+        let (namespace, name) = (
+            &tag_or_namespace[..separator],
+            &tag_or_namespace[separator + 1..],
+        );
+
+        if let Some(resolved) = self.match_patterns(tag_or_namespace, name, module_path, |_| true) {
+            return resolved;
+        }
+
+        if let Some(target) = mappings.get(namespace) {
+            return (
+                target.clone(),
+                MappingRule::Namespace(namespace.to_string()),
+            );
+        }
+
+        default()
+    }
+
+    /// Gets the namespace of a Starknet contract, along with the rule that assigned it.
+    ///
+    /// Starknet contracts are not dojo resources and are only namespaced when a `path:` or
+    /// `name:` pattern of the mappings matches them, the other mappings being ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `name`: the name of the contract module.
+    /// * `module_path`: the Cairo path of the module declaring the contract.
+    ///
+    /// # Returns
+    ///
+    /// The namespace and the rule, `None` if no pattern matches the contract.
+    pub fn resolve_starknet_contract(
+        &self,
+        name: &str,
+        module_path: &str,
+    ) -> Option<(String, MappingRule)> {
+        let tag = format!("{}-{}", self.default, name);
+
+        self.match_patterns(&tag, name, Some(module_path), |pattern| {
+            !matches!(pattern, MappingPattern::Tag(_))
+        })
+    }
+
+    /// Matches the pattern keys of the mappings, the most specific first: by kind, then by
+    /// length of the pattern.
+    fn match_patterns(
+        &self,
+        tag: &str,
+        name: &str,
+        module_path: Option<&str>,
+        is_applicable: impl Fn(&MappingPattern) -> bool,
+    ) -> Option<(String, MappingRule)> {
+        let mut patterns = self
+            .mappings
+            .as_ref()?
+            .iter()
+            .filter_map(|(key, target)| Some((MappingPattern::cached(key)?, key, target)))
+            .filter(|(pattern, _, _)| is_applicable(pattern))
+            .collect::<Vec<_>>();
+
+        patterns.sort_by(|(a, a_key, _), (b, b_key, _)| {
+            a.precedence()
+                .cmp(&b.precedence())
+                .then(b_key.len().cmp(&a_key.len()))
+                .then(a_key.cmp(b_key))
+        });
+
+        patterns
+            .into_iter()
+            .find(|(pattern, _, _)| pattern.matches(tag, name, module_path))
+            .map(|(_, key, target)| (target.clone(), MappingRule::Pattern(key.clone())))
     }
 
     /// Validates the namespace configuration and their names.
@@ -118,18 +352,29 @@ impl NamespaceConfig {
         }
 
         for (tag_or_namespace, namespace) in self.mappings.as_ref().unwrap_or(&HashMap::new()) {
-            if !Self::is_name_valid(namespace) {
-                return Err(anyhow::anyhow!(
-                    "Invalid namespace `{}` for tag or namespace `{}`",
-                    namespace,
-                    tag_or_namespace
-                ));
-            }
+            Self::validate_mapping(tag_or_namespace, namespace)?;
         }
 
         Ok(self)
     }
 
+    /// Validates a mapping: its key, if it's a pattern, and its target namespace.
+    pub fn validate_mapping(key: &str, namespace: &str) -> Result<()> {
+        if let Err(e) = MappingPattern::parse(key) {
+            anyhow::bail!("Invalid namespace mapping `{}`: {}", key, e);
+        }
+
+        if !Self::is_name_valid(namespace) {
+            return Err(anyhow::anyhow!(
+                "Invalid namespace `{}` for tag or namespace `{}`",
+                namespace,
+                key
+            ));
+        }
+
+        Ok(())
+    }
+
     /// Checks if the provided namespace follows the format rules.
     pub fn is_name_valid(namespace: &str) -> bool {
         Regex::new(r"^[a-zA-Z0-9_]+$").unwrap().is_match(namespace)
@@ -160,6 +405,8 @@ impl From<&CfgSet> for NamespaceConfig {
             Some(mappings)
         };
 
+        // The mappings are injected from the configuration of the root package, whose patterns
+        // are validated when it's loaded, an invalid pattern being ignored when resolving.
         NamespaceConfig {
             default: default.to_string(),
             mappings,
@@ -176,14 +423,11 @@ mod tests {
 
     #[test]
     fn test_namespace_config_get_mapping() {
-        let config = NamespaceConfig {
-            default: "nm".to_string(),
-            mappings: Some(HashMap::from([
-                ("tag1".to_string(), "namespace1".to_string()),
-                ("namespace2".to_string(), "namespace2".to_string()),
-                ("armory-Flatbow".to_string(), "weapons".to_string()),
-            ])),
-        };
+        let config = NamespaceConfig::new("nm").with_mappings(HashMap::from([
+            ("tag1".to_string(), "namespace1".to_string()),
+            ("namespace2".to_string(), "namespace2".to_string()),
+            ("armory-Flatbow".to_string(), "weapons".to_string()),
+        ]));
 
         assert_eq!(config.get_mapping("tag1"), "namespace1");
         assert_eq!(config.get_mapping("tag1-TestModel"), "namespace1");
@@ -193,36 +437,184 @@ mod tests {
         assert_eq!(config.get_mapping("unknown"), "nm");
     }
 
+    #[test]
+    fn test_namespace_config_resolve_patterns() {
+        let config = NamespaceConfig::new("nm").with_mappings(HashMap::from([
+            ("nm-ItemSword".to_string(), "weapons".to_string()),
+            ("name:Item*".to_string(), "items".to_string()),
+            ("name:ItemS*".to_string(), "specials".to_string()),
+            ("path:game::v2::*".to_string(), "world_v2".to_string()),
+            ("re:^nm-.*Event$".to_string(), "events".to_string()),
+            ("nm".to_string(), "mapped".to_string()),
+        ]));
+
+        let resolve = |tag: &str, path: Option<&str>| config.resolve(tag, path);
+
+        assert_eq!(
+            resolve("nm-ItemSword", None),
+            (
+                "weapons".to_string(),
+                MappingRule::Exact("nm-ItemSword".to_string())
+            )
+        );
+        assert_eq!(
+            resolve("nm-ItemShield", None),
+            (
+                "specials".to_string(),
+                MappingRule::Pattern("name:ItemS*".to_string())
+            )
+        );
+        assert_eq!(
+            resolve("nm-ItemBow", None),
+            (
+                "items".to_string(),
+                MappingRule::Pattern("name:Item*".to_string())
+            )
+        );
+        assert_eq!(
+            resolve("nm-ItemBow", Some("game::v2::models")),
+            (
+                "world_v2".to_string(),
+                MappingRule::Pattern("path:game::v2::*".to_string())
+            )
+        );
+        assert_eq!(
+            resolve("nm-MovedEvent", None),
+            (
+                "events".to_string(),
+                MappingRule::Pattern("re:^nm-.*Event$".to_string())
+            )
+        );
+        assert_eq!(
+            resolve("nm-Position", None),
+            (
+                "mapped".to_string(),
+                MappingRule::Namespace("nm".to_string())
+            )
+        );
+        assert_eq!(
+            resolve("other-Position", None),
+            ("nm".to_string(), MappingRule::Default)
+        );
+        assert_eq!(config.get_mapping("nm-ItemBow"), "items");
+    }
+
+    #[test]
+    fn test_namespace_config_resolve_starknet_contract() {
+        let config = NamespaceConfig::new("nm").with_mappings(HashMap::from([
+            ("nm-erc20".to_string(), "exact".to_string()),
+            ("nm".to_string(), "mapped".to_string()),
+            ("re:^nm-.*$".to_string(), "regex".to_string()),
+            ("name:erc*".to_string(), "tokens".to_string()),
+            ("path:game::presets".to_string(), "presets".to_string()),
+        ]));
+
+        assert_eq!(
+            config.resolve_starknet_contract("erc20", "game::tokens"),
+            Some((
+                "tokens".to_string(),
+                MappingRule::Pattern("name:erc*".to_string())
+            ))
+        );
+        assert_eq!(
+            config.resolve_starknet_contract("erc20", "game::presets"),
+            Some((
+                "presets".to_string(),
+                MappingRule::Pattern("path:game::presets".to_string())
+            ))
+        );
+        assert_eq!(
+            config.resolve_starknet_contract("account", "game::tokens"),
+            None
+        );
+        assert_eq!(
+            NamespaceConfig::new("nm").resolve_starknet_contract("erc20", "game::tokens"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_namespace_config_validate_patterns() {
+        for key in ["name:Item*", "path:game::*", "re:^ns-[A-Z]"] {
+            let config = NamespaceConfig::new("nm")
+                .with_mappings(HashMap::from([(key.to_string(), "valid".to_string())]));
+            assert!(config.validate().is_ok(), "{key}");
+        }
+
+        for key in ["name:", "re:(", "re:", "glob:Item*"] {
+            let config = NamespaceConfig::new("nm")
+                .with_mappings(HashMap::from([(key.to_string(), "valid".to_string())]));
+            let err = config.validate().unwrap_err();
+            assert!(err
+                .to_string()
+                .contains(&format!("Invalid namespace mapping `{key}`")));
+        }
+    }
+
+    #[test]
+    fn test_namespace_config_resolve_ignores_invalid_patterns() {
+        let config = NamespaceConfig::new("nm").with_mappings(HashMap::from([
+            ("re:(".to_string(), "invalid".to_string()),
+            ("name:Item*".to_string(), "items".to_string()),
+        ]));
+
+        assert_eq!(config.get_mapping("nm-ItemBow"), "items");
+        assert_eq!(config.get_mapping("nm-Position"), "nm");
+
+        // The configuration built from the plugin `CfgSet` doesn't panic either.
+        let mut cfg_set = CfgSet::new();
+        cfg_set.insert(Cfg::kv(DEFAULT_NAMESPACE_CFG_KEY, SmolStr::from("nm")));
+        cfg_set.insert(Cfg::kv(
+            format!("{}re:(", NAMESPACE_CFG_PREFIX),
+            SmolStr::from("invalid"),
+        ));
+
+        assert_eq!(
+            NamespaceConfig::from(&cfg_set).get_mapping("nm-Position"),
+            "nm"
+        );
+    }
+
+    #[test]
+    fn test_namespace_config_deserialize() {
+        let config: NamespaceConfig = toml::from_str(
+            "default = \"nm\"\nmappings = { \"name:Item*\" = \"items\", nm = \"mapped\" }",
+        )
+        .unwrap();
+
+        assert_eq!(config.default, "nm");
+        assert_eq!(config.get_mapping("nm-ItemBow"), "items");
+        assert_eq!(config.get_mapping("nm-Position"), "mapped");
+
+        // The patterns are checked by the validation, to report all the errors of the file.
+        let config = toml::from_str::<NamespaceConfig>(
+            "default = \"nm\"\nmappings = { \"re:(\" = \"items\" }",
+        )
+        .unwrap();
+        assert!(config.validate().is_err());
+
+        assert!(toml::from_str::<NamespaceConfig>("default = \"nm\"\nunknown = 1").is_err());
+    }
+
     #[test]
     fn test_namespace_config_validate() {
-        let valid_config = NamespaceConfig {
-            default: "valid_default".to_string(),
-            mappings: Some(HashMap::from([
-                ("tag1".to_string(), "valid_namespace1".to_string()),
-                ("tag2".to_string(), "valid_namespace2".to_string()),
-            ])),
-        };
+        let valid_config = NamespaceConfig::new("valid_default").with_mappings(HashMap::from([
+            ("tag1".to_string(), "valid_namespace1".to_string()),
+            ("tag2".to_string(), "valid_namespace2".to_string()),
+        ]));
         assert!(valid_config.validate().is_ok());
 
-        let empty_default_config = NamespaceConfig {
-            default: "".to_string(),
-            mappings: None,
-        };
+        let empty_default_config = NamespaceConfig::new("");
         assert!(empty_default_config.validate().is_err());
 
-        let invalid_default_config = NamespaceConfig {
-            default: "invalid-default".to_string(),
-            mappings: None,
-        };
+        let invalid_default_config = NamespaceConfig::new("invalid-default");
         assert!(invalid_default_config.validate().is_err());
 
-        let invalid_mapping_config = NamespaceConfig {
-            default: "valid_default".to_string(),
-            mappings: Some(HashMap::from([
+        let invalid_mapping_config =
+            NamespaceConfig::new("valid_default").with_mappings(HashMap::from([
                 ("tag1".to_string(), "valid_namespace".to_string()),
                 ("tag2".to_string(), "invalid-namespace".to_string()),
-            ])),
-        };
+            ]));
         assert!(invalid_mapping_config.validate().is_err());
     }
 
@@ -230,7 +622,7 @@ mod tests {
     fn test_namespace_config_new() {
         let config = NamespaceConfig::new("default_namespace");
         assert_eq!(config.default, "default_namespace");
-        assert_eq!(config.mappings, None);
+        assert_eq!(config.mappings(), None);
     }
 
     #[test]
@@ -241,7 +633,7 @@ mod tests {
 
         let config = NamespaceConfig::new("default_namespace").with_mappings(mappings.clone());
         assert_eq!(config.default, "default_namespace");
-        assert_eq!(config.mappings, Some(mappings));
+        assert_eq!(config.mappings(), Some(&mappings));
     }
 
     #[test]
@@ -282,8 +674,8 @@ mod tests {
 
         assert_eq!(namespace_config.default, "default_namespace");
         assert_eq!(
-            namespace_config.mappings,
-            Some(HashMap::from([
+            namespace_config.mappings(),
+            Some(&HashMap::from([
                 ("tag1".to_string(), "namespace1".to_string()),
                 ("tag2".to_string(), "namespace2".to_string()),
             ]))
@@ -294,6 +686,6 @@ mod tests {
         let empty_namespace_config = NamespaceConfig::from(&empty_cfg_set);
 
         assert_eq!(empty_namespace_config.default, "DEFAULT_NAMESPACE_CFG");
-        assert_eq!(empty_namespace_config.mappings, None);
+        assert_eq!(empty_namespace_config.mappings(), None);
     }
 }
@@ -17,7 +17,13 @@ use cairo_lang_utils::unordered_hash_map::UnorderedHashMap;
 use dojo_types::naming;
 
 use crate::aux_data::ContractAuxData;
-use crate::namespace_config::NamespaceConfig;
+use crate::diagnostic_codes::{
+    DUPLICATE_ATTRIBUTE_ARGUMENT, INIT_RETURN_TYPE, INVALID_ATTRIBUTE_ARGUMENT,
+    INVALID_RESOURCE_NAME, SELF_AND_WORLD_PARAMS, UNEXPECTED_ATTRIBUTE_ARGUMENT,
+    WORLD_IN_GENERATE_TRAIT,
+};
+use crate::namespace_config::{MappingRule, NamespaceConfig};
+use crate::plugin::syntax::utils::get_module_path;
 use crate::plugin::syntax::world_param::{self, WorldParamInjectionKind};
 use crate::plugin::syntax::{self_param, utils as syntax_utils};
 
@@ -62,12 +68,17 @@ impl DojoContract {
             .namespace
             .unwrap_or(namespace_config.default.clone());
 
-        let contract_namespace = if parameters.nomapping {
-            unmapped_namespace
+        let (contract_namespace, namespace_rule) = if parameters.nomapping {
+            (unmapped_namespace, MappingRule::NoMapping)
         } else {
+            let module_path = get_module_path(db, &module_ast.as_syntax_node());
+
             // Maps namespace from the tag to ensure higher precision on matching namespace
             // mappings.
-            namespace_config.get_mapping(&naming::get_tag(&unmapped_namespace, &name))
+            namespace_config.resolve(
+                &naming::get_tag(&unmapped_namespace, &name),
+                module_path.as_deref(),
+            )
         };
 
         for (id, value) in [
@@ -79,10 +90,10 @@ impl DojoContract {
                     code: None,
                     diagnostics: vec![PluginDiagnostic {
                         stable_ptr: module_ast.stable_ptr().0,
-                        message: format!(
+                        message: INVALID_RESOURCE_NAME.message(format!(
                             "The contract {id} '{value}' can only contain characters (a-z/A-Z), \
                              digits (0-9) and underscore (_)."
-                        ),
+                        )),
                         severity: Severity::Error,
                     }],
                     remove_original_item: false,
@@ -217,6 +228,7 @@ impl DojoContract {
                     aux_data: Some(DynGeneratedFileAuxData::new(ContractAuxData {
                         name: name.to_string(),
                         namespace: contract_namespace.to_string(),
+                        namespace_rule: namespace_rule.to_string(),
                         systems: contract.systems.clone(),
                     })),
                     code_mappings,
@@ -286,8 +298,10 @@ impl DojoContract {
         if let OptionReturnTypeClause::ReturnTypeClause(_) = fn_decl.signature(db).ret_ty(db) {
             self.diagnostics.push(PluginDiagnostic {
                 stable_ptr: fn_ast.stable_ptr().untyped(),
-                message: format!("The {} function cannot have a return type.", DOJO_INIT_FN)
-                    .to_string(),
+                message: INIT_RETURN_TYPE.message(format!(
+                    "The {} function cannot have a return type.",
+                    DOJO_INIT_FN
+                )),
                 severity: Severity::Error,
             });
         }
@@ -492,7 +506,8 @@ impl DojoContract {
         if is_self_used && world_injection != WorldParamInjectionKind::None {
             self.diagnostics.push(PluginDiagnostic {
                 stable_ptr: fn_diagnostic_item,
-                message: "You cannot use `self` and `world` parameters together.".to_string(),
+                message: SELF_AND_WORLD_PARAMS
+                    .message("You cannot use `self` and `world` parameters together."),
                 severity: Severity::Error,
             });
         }
@@ -596,9 +611,10 @@ impl DojoContract {
         if has_generate_trait && was_world_injected {
             self.diagnostics.push(PluginDiagnostic {
                 stable_ptr: fn_ast.stable_ptr().untyped(),
-                message: "You cannot use `world` and `#[generate_trait]` together. Use `self` \
-                          instead."
-                    .to_string(),
+                message: WORLD_IN_GENERATE_TRAIT.message(
+                    "You cannot use `world` and `#[generate_trait]` together. Use `self` \
+                     instead.",
+                ),
                 severity: Severity::Error,
             });
         }
@@ -671,10 +687,10 @@ fn get_contract_namespace(
         Expr::String(s) => Some(s.string_value(db).unwrap()),
         _ => {
             diagnostics.push(PluginDiagnostic {
-                message: format!(
+                message: INVALID_ATTRIBUTE_ARGUMENT.message(format!(
                     "The argument '{}' of dojo::contract must be a string",
                     CONTRACT_NAMESPACE
-                ),
+                )),
                 stable_ptr: arg_value.stable_ptr().untyped(),
                 severity: Severity::Error,
             });
@@ -719,10 +735,10 @@ fn get_parameters(
 
                     if processed_args.contains_key(&arg_name) {
                         diagnostics.push(PluginDiagnostic {
-                            message: format!(
+                            message: DUPLICATE_ATTRIBUTE_ARGUMENT.message(format!(
                                 "Too many '{}' attributes for dojo::contract",
                                 arg_name
-                            ),
+                            )),
                             stable_ptr: module_ast.stable_ptr().untyped(),
                             severity: Severity::Error,
                         });
@@ -737,10 +753,10 @@ fn get_parameters(
                             }
                             _ => {
                                 diagnostics.push(PluginDiagnostic {
-                                    message: format!(
+                                    message: UNEXPECTED_ATTRIBUTE_ARGUMENT.message(format!(
                                         "Unexpected argument '{}' for dojo::contract",
                                         arg_name
-                                    ),
+                                    )),
                                     stable_ptr: x.stable_ptr().untyped(),
                                     severity: Severity::Warning,
                                 });
@@ -752,17 +768,20 @@ fn get_parameters(
                     let arg_name = arg.value(db).as_syntax_node().get_text(db);
 
                     diagnostics.push(PluginDiagnostic {
-                        message: format!("Unexpected argument '{}' for dojo::contract", arg_name),
+                        message: UNEXPECTED_ATTRIBUTE_ARGUMENT.message(format!(
+                            "Unexpected argument '{}' for dojo::contract",
+                            arg_name
+                        )),
                         stable_ptr: arg.stable_ptr().untyped(),
                         severity: Severity::Warning,
                     });
                 }
                 ArgClause::FieldInitShorthand(x) => {
                     diagnostics.push(PluginDiagnostic {
-                        message: format!(
+                        message: UNEXPECTED_ATTRIBUTE_ARGUMENT.message(format!(
                             "Unexpected argument '{}' for dojo::contract",
                             x.name(db).name(db).text(db).to_string()
-                        ),
+                        )),
                         stable_ptr: x.stable_ptr().untyped(),
                         severity: Severity::Warning,
                     });
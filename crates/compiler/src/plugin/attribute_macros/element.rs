@@ -1,7 +1,11 @@
 use std::collections::HashMap;
 
 use crate::compiler::annotation::Member;
-use crate::namespace_config::NamespaceConfig;
+use crate::diagnostic_codes::{
+    DUPLICATE_ATTRIBUTE_ARGUMENT, INVALID_ATTRIBUTE_ARGUMENT, UNEXPECTED_ATTRIBUTE_ARGUMENT,
+    UNSUPPORTED_KEY_TYPE, UNSUPPORTED_VERSION,
+};
+use crate::namespace_config::{MappingRule, NamespaceConfig};
 use cairo_lang_defs::patcher::RewriteNode;
 use cairo_lang_defs::plugin::PluginDiagnostic;
 use cairo_lang_diagnostics::Severity;
@@ -50,10 +54,10 @@ pub trait StructParameterParser {
 
                         if processed_args.contains_key(&arg_name) {
                             diagnostics.push(PluginDiagnostic {
-                                message: format!(
+                                message: DUPLICATE_ATTRIBUTE_ARGUMENT.message(format!(
                                     "Too many '{}' attributes for {attribute_name}",
                                     arg_name
-                                ),
+                                )),
                                 stable_ptr: struct_ast.stable_ptr().untyped(),
                                 severity: Severity::Error,
                             });
@@ -64,20 +68,20 @@ pub trait StructParameterParser {
                     }
                     ArgClause::Unnamed(x) => {
                         diagnostics.push(PluginDiagnostic {
-                            message: format!(
+                            message: UNEXPECTED_ATTRIBUTE_ARGUMENT.message(format!(
                                 "Unexpected argument '{}' for {attribute_name}",
                                 x.as_syntax_node().get_text(db)
-                            ),
+                            )),
                             stable_ptr: x.stable_ptr().untyped(),
                             severity: Severity::Warning,
                         });
                     }
                     ArgClause::FieldInitShorthand(x) => {
                         diagnostics.push(PluginDiagnostic {
-                            message: format!(
+                            message: UNEXPECTED_ATTRIBUTE_ARGUMENT.message(format!(
                                 "Unexpected argument '{}' for {attribute_name}",
                                 x.name(db).name(db).text(db).to_string()
-                            ),
+                            )),
                             stable_ptr: x.stable_ptr().untyped(),
                             severity: Severity::Warning,
                         });
@@ -134,7 +138,10 @@ impl StructParameterParser for CommonStructParameters {
             }
             _ => {
                 diagnostics.push(PluginDiagnostic {
-                    message: format!("Unexpected argument '{}' for {attribute_name}", arg_name),
+                    message: UNEXPECTED_ATTRIBUTE_ARGUMENT.message(format!(
+                        "Unexpected argument '{}' for {attribute_name}",
+                        arg_name
+                    )),
                     stable_ptr: arg.stable_ptr().untyped(),
                     severity: Severity::Warning,
                 });
@@ -143,21 +150,34 @@ impl StructParameterParser for CommonStructParameters {
     }
 }
 
+/// Computes the namespace of a model or event, with the rule of the namespace configuration
+/// that assigned it.
+///
+/// # Arguments
+///
+/// * `element_name` - The name of the model or event.
+/// * `parameters` - The parameters of the attribute.
+/// * `namespace_config` - The namespace configuration.
+/// * `module_path` - The Cairo path of the module declaring the element, if known.
 pub fn compute_namespace(
     element_name: &str,
     parameters: &CommonStructParameters,
     namespace_config: &NamespaceConfig,
-) -> String {
+    module_path: Option<&str>,
+) -> (String, MappingRule) {
     let unmapped_namespace = parameters
         .namespace
         .clone()
         .unwrap_or(namespace_config.default.clone());
 
     if parameters.nomapping {
-        unmapped_namespace
+        (unmapped_namespace, MappingRule::NoMapping)
     } else {
         // Maps namespace from the tag to ensure higher precision on matching namespace mappings.
-        namespace_config.get_mapping(&naming::get_tag(&unmapped_namespace, element_name))
+        namespace_config.resolve(
+            &naming::get_tag(&unmapped_namespace, element_name),
+            module_path,
+        )
     }
 }
 
@@ -184,9 +204,10 @@ pub fn parse_members(
             // validate key member
             if member.key && member.ty == "u256" {
                 diagnostics.push(PluginDiagnostic {
-                    message: "Key is only supported for core types that are 1 felt long once \
-                              serialized. `u256` is a struct of 2 u128, hence not supported."
-                        .into(),
+                    message: UNSUPPORTED_KEY_TYPE.message(
+                        "Key is only supported for core types that are 1 felt long once \
+                         serialized. `u256` is a struct of 2 u128, hence not supported.",
+                    ),
                     stable_ptr: member_ast.name(db).stable_ptr().untyped(),
                     severity: Severity::Error,
                 });
@@ -262,7 +283,8 @@ fn get_version(
                     value
                 } else {
                     diagnostics.push(PluginDiagnostic {
-                        message: format!("{attribute_name} version {} not supported", value),
+                        message: UNSUPPORTED_VERSION
+                            .message(format!("{attribute_name} version {} not supported", value)),
                         stable_ptr: arg_value.stable_ptr().untyped(),
                         severity: Severity::Error,
                     });
@@ -270,10 +292,10 @@ fn get_version(
                 }
             } else {
                 diagnostics.push(PluginDiagnostic {
-                    message: format!(
+                    message: INVALID_ATTRIBUTE_ARGUMENT.message(format!(
                         "The argument '{}' of {attribute_name} must be an integer",
                         PARAMETER_VERSION_NAME
-                    ),
+                    )),
                     stable_ptr: arg_value.stable_ptr().untyped(),
                     severity: Severity::Error,
                 });
@@ -282,10 +304,10 @@ fn get_version(
         }
         _ => {
             diagnostics.push(PluginDiagnostic {
-                message: format!(
+                message: INVALID_ATTRIBUTE_ARGUMENT.message(format!(
                     "The argument '{}' of {attribute_name} must be an integer",
                     PARAMETER_VERSION_NAME
-                ),
+                )),
                 stable_ptr: arg_value.stable_ptr().untyped(),
                 severity: Severity::Error,
             });
@@ -306,10 +328,10 @@ fn get_namespace(
         Expr::String(s) => Some(s.string_value(db).unwrap()),
         _ => {
             diagnostics.push(PluginDiagnostic {
-                message: format!(
+                message: INVALID_ATTRIBUTE_ARGUMENT.message(format!(
                     "The argument '{}' of {attribute_name} must be a string",
                     PARAMETER_NAMESPACE
-                ),
+                )),
                 stable_ptr: arg_value.stable_ptr().untyped(),
                 severity: Severity::Error,
             });
@@ -20,10 +20,15 @@ use convert_case::{Case, Casing};
 use dojo_types::naming;
 
 use crate::aux_data::EventAuxData;
+use crate::diagnostic_codes::{
+    EVENT_WITHOUT_KEY, EVENT_WITHOUT_VALUE, INVALID_ATTRIBUTE_ARGUMENT, INVALID_RESOURCE_NAME,
+    PACKED_EVENT,
+};
 use crate::namespace_config::NamespaceConfig;
 use crate::plugin::derive_macros::{
     extract_derive_attr_names, handle_derive_attrs, DOJO_INTROSPECT_DERIVE, DOJO_PACKED_DERIVE,
 };
+use crate::plugin::syntax::utils::get_module_path;
 
 use super::element::{
     compute_namespace, parse_members, serialize_keys_and_values, CommonStructParameters,
@@ -103,7 +108,13 @@ impl DojoEvent {
             .get_text(db)
             .trim()
             .to_string();
-        let event_namespace = compute_namespace(&event_name, &parameters.common, namespace_config);
+        let module_path = get_module_path(db, &struct_ast.as_syntax_node());
+        let (event_namespace, namespace_rule) = compute_namespace(
+            &event_name,
+            &parameters.common,
+            namespace_config,
+            module_path.as_deref(),
+        );
 
         for (id, value) in [("name", &event_name), ("namespace", &event_namespace)] {
             if !NamespaceConfig::is_name_valid(value) {
@@ -111,10 +122,10 @@ impl DojoEvent {
                     code: None,
                     diagnostics: vec![PluginDiagnostic {
                         stable_ptr: struct_ast.stable_ptr().0,
-                        message: format!(
+                        message: INVALID_RESOURCE_NAME.message(format!(
                             "The event {id} '{value}' can only contain characters (a-z/A-Z), \
                              digits (0-9) and underscore (_)."
-                        ),
+                        )),
                         severity: Severity::Error,
                     }],
                     remove_original_item: false,
@@ -140,7 +151,8 @@ impl DojoEvent {
 
         if serialized_keys.is_empty() {
             diagnostics.push(PluginDiagnostic {
-                message: "Event must define at least one #[key] attribute".into(),
+                message: EVENT_WITHOUT_KEY
+                    .message("Event must define at least one #[key] attribute"),
                 stable_ptr: struct_ast.name(db).stable_ptr().untyped(),
                 severity: Severity::Error,
             });
@@ -148,7 +160,8 @@ impl DojoEvent {
 
         if serialized_values.is_empty() {
             diagnostics.push(PluginDiagnostic {
-                message: "Event must define at least one member that is not a key".into(),
+                message: EVENT_WITHOUT_VALUE
+                    .message("Event must define at least one member that is not a key"),
                 stable_ptr: struct_ast.name(db).stable_ptr().untyped(),
                 severity: Severity::Error,
             });
@@ -169,9 +182,9 @@ impl DojoEvent {
         // and do not derive IntrospectPacked.
         if derive_attr_names.contains(&DOJO_PACKED_DERIVE.to_string()) {
             diagnostics.push(PluginDiagnostic {
-                message: format!(
+                message: PACKED_EVENT.message(format!(
                     "Event should derive {DOJO_INTROSPECT_DERIVE} instead of {DOJO_PACKED_DERIVE}."
-                ),
+                )),
                 stable_ptr: struct_ast.name(db).stable_ptr().untyped(),
                 severity: Severity::Error,
             });
@@ -258,6 +271,8 @@ impl DojoEvent {
         let aux_data = EventAuxData {
             name: event_name.clone(),
             namespace: event_namespace.clone(),
+            namespace_rule: namespace_rule.to_string(),
+            version: parameters.common.version,
             members,
         };
 
@@ -285,9 +300,9 @@ fn get_historical(
         Expr::False(_) => false,
         _ => {
             diagnostics.push(PluginDiagnostic {
-                message: format!(
+                message: INVALID_ATTRIBUTE_ARGUMENT.message(format!(
                     "The argument '{PARAMETER_HISTORICAL}' of {attribute_name} must be a boolean",
-                ),
+                )),
                 stable_ptr: arg_value.stable_ptr().untyped(),
                 severity: Severity::Error,
             });
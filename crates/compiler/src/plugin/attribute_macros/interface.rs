@@ -1,6 +1,7 @@
 use cairo_lang_defs::patcher::{PatchBuilder, RewriteNode};
 use cairo_lang_defs::plugin::{
-    MacroPluginMetadata, PluginDiagnostic, PluginGeneratedFile, PluginResult,
+    DynGeneratedFileAuxData, MacroPluginMetadata, PluginDiagnostic, PluginGeneratedFile,
+    PluginResult,
 };
 use cairo_lang_diagnostics::Severity;
 use cairo_lang_plugins::plugins::HasItemsInCfgEx;
@@ -8,6 +9,8 @@ use cairo_lang_syntax::node::db::SyntaxGroup;
 use cairo_lang_syntax::node::{ast, ids, Terminal, TypedStablePtr, TypedSyntaxNode};
 use cairo_lang_utils::unordered_hash_map::UnorderedHashMap;
 
+use crate::aux_data::DojoGeneratedAuxData;
+use crate::diagnostic_codes::{INTERFACE_NON_FUNCTION, SELF_AND_WORLD_PARAMS};
 use crate::plugin::syntax::self_param;
 use crate::plugin::syntax::world_param::{self, WorldParamInjectionKind};
 
@@ -38,9 +41,10 @@ impl DojoInterface {
 
                     interface.diagnostics.push(PluginDiagnostic {
                         stable_ptr: el.stable_ptr().untyped(),
-                        message: "Anything other than functions is not supported in a \
-                                  dojo::interface"
-                            .to_string(),
+                        message: INTERFACE_NON_FUNCTION.message(
+                            "Anything other than functions is not supported in a \
+                             dojo::interface",
+                        ),
                         severity: Severity::Error,
                     });
 
@@ -86,7 +90,7 @@ impl DojoInterface {
             code: Some(PluginGeneratedFile {
                 name: name.clone(),
                 content: code,
-                aux_data: None,
+                aux_data: Some(DynGeneratedFileAuxData::new(DojoGeneratedAuxData)),
                 code_mappings,
             }),
             diagnostics: interface.diagnostics,
@@ -119,7 +123,8 @@ impl DojoInterface {
         if is_self_used && world_injection != WorldParamInjectionKind::None {
             self.diagnostics.push(PluginDiagnostic {
                 stable_ptr: diagnostic_item,
-                message: "You cannot use `self` and `world` parameters together.".to_string(),
+                message: SELF_AND_WORLD_PARAMS
+                    .message("You cannot use `self` and `world` parameters together."),
                 severity: Severity::Error,
             });
         }
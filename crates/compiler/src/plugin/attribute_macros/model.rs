@@ -16,10 +16,12 @@ use starknet::core::utils::get_selector_from_name;
 
 use crate::aux_data::ModelAuxData;
 use crate::compiler::annotation::Member;
+use crate::diagnostic_codes::{INVALID_RESOURCE_NAME, MODEL_WITHOUT_KEY, MODEL_WITHOUT_VALUE};
 use crate::namespace_config::NamespaceConfig;
 use crate::plugin::derive_macros::{
     extract_derive_attr_names, handle_derive_attrs, DOJO_INTROSPECT_DERIVE, DOJO_PACKED_DERIVE,
 };
+use crate::plugin::syntax::utils::get_module_path;
 
 use super::element::{
     compute_namespace, parse_members, serialize_member_ty, CommonStructParameters,
@@ -66,7 +68,13 @@ impl DojoModel {
             .to_string();
         let model_type_snake = model_type.to_case(Case::Snake);
 
-        let model_namespace = compute_namespace(&model_type, &parameters, namespace_config);
+        let module_path = get_module_path(db, &struct_ast.as_syntax_node());
+        let (model_namespace, namespace_rule) = compute_namespace(
+            &model_type,
+            &parameters,
+            namespace_config,
+            module_path.as_deref(),
+        );
 
         for (id, value) in [("name", &model_type), ("namespace", &model_namespace)] {
             if !NamespaceConfig::is_name_valid(value) {
@@ -74,10 +82,10 @@ impl DojoModel {
                     code: None,
                     diagnostics: vec![PluginDiagnostic {
                         stable_ptr: struct_ast.stable_ptr().0,
-                        message: format!(
+                        message: INVALID_RESOURCE_NAME.message(format!(
                             "The model {id} '{value}' can only contain characters (a-z/A-Z), \
                              digits (0-9) and underscore (_)."
-                        ),
+                        )),
                         severity: Severity::Error,
                     }],
                     remove_original_item: false,
@@ -133,7 +141,8 @@ impl DojoModel {
         });
         if keys.is_empty() {
             diagnostics.push(PluginDiagnostic {
-                message: "Model must define at least one #[key] attribute".into(),
+                message: MODEL_WITHOUT_KEY
+                    .message("Model must define at least one #[key] attribute"),
                 stable_ptr: struct_ast.name(db).stable_ptr().untyped(),
                 severity: Severity::Error,
             });
@@ -141,7 +150,8 @@ impl DojoModel {
 
         if values.is_empty() {
             diagnostics.push(PluginDiagnostic {
-                message: "Model must define at least one member that is not a key".into(),
+                message: MODEL_WITHOUT_VALUE
+                    .message("Model must define at least one member that is not a key"),
                 stable_ptr: struct_ast.name(db).stable_ptr().untyped(),
                 severity: Severity::Error,
             });
@@ -269,6 +279,8 @@ impl DojoModel {
         let aux_data = ModelAuxData {
             name: model_type.clone(),
             namespace: model_namespace.clone(),
+            namespace_rule: namespace_rule.to_string(),
+            version: parameters.version,
             members,
         };
 
@@ -14,6 +14,8 @@ use scarb::core::{PackageId, PackageName, SourceId};
 use semver::Version;
 use url::Url;
 
+use crate::diagnostic_codes::MODEL_AND_EVENT;
+
 use super::attribute_macros::{
     DojoContract, DojoEvent, DojoInterface, DojoModel, DOJO_CONTRACT_ATTR, DOJO_EVENT_ATTR,
     DOJO_INTERFACE_ATTR, DOJO_MODEL_ATTR,
@@ -132,10 +134,10 @@ impl MacroPlugin for BuiltinDojoPlugin {
                 if n_model_attrs > 0 && n_event_attrs > 0 {
                     return PluginResult::diagnostic_only(PluginDiagnostic {
                         stable_ptr: struct_ast.stable_ptr().0,
-                        message: format!(
+                        message: MODEL_AND_EVENT.message(format!(
                             "The struct {} can only have one of the dojo::model or one dojo::event attribute.",
                             struct_ast.name(db).text(db)
-                        ),
+                        )),
                         severity: Severity::Error,
                     });
                 } else if n_model_attrs == 1 {
@@ -6,6 +6,8 @@ use cairo_lang_syntax::node::helpers::QueryAttrs;
 use cairo_lang_syntax::node::{ids, Terminal, TypedSyntaxNode};
 use starknet::core::utils::get_selector_from_name;
 
+use crate::diagnostic_codes::{OPTION_OF_TUPLE, PACKED_ARRAY, UNSUPPORTED_VARIANT_TYPE};
+
 use super::utils::{
     get_array_item_type, get_tuple_item_types, is_array, is_byte_array, is_tuple,
     is_unsupported_option_type, primitive_type_introspection,
@@ -93,7 +95,8 @@ pub fn get_layout_from_type_clause(
         _ => {
             diagnostics.push(PluginDiagnostic {
                 stable_ptr: type_clause.stable_ptr().0,
-                message: "Unexpected expression for variant data type.".to_string(),
+                message: UNSUPPORTED_VARIANT_TYPE
+                    .message("Unexpected expression for variant data type."),
                 severity: Severity::Error,
             });
             "ERROR".to_string()
@@ -174,7 +177,8 @@ pub fn build_item_layout_from_type(
         if is_unsupported_option_type(item_type) {
             diagnostics.push(PluginDiagnostic {
                 stable_ptr: diagnostic_item,
-                message: "Option<T> cannot be used with tuples. Prefer using a struct.".into(),
+                message: OPTION_OF_TUPLE
+                    .message("Option<T> cannot be used with tuples. Prefer using a struct."),
                 severity: Severity::Error,
             });
         }
@@ -330,7 +334,8 @@ pub fn get_packed_field_layout_from_type_clause(
         _ => {
             diagnostics.push(PluginDiagnostic {
                 stable_ptr: type_clause.stable_ptr().0,
-                message: "Unexpected expression for variant data type.".to_string(),
+                message: UNSUPPORTED_VARIANT_TYPE
+                    .message("Unexpected expression for variant data type."),
                 severity: Severity::Error,
             });
             vec!["ERROR".to_string()]
@@ -347,7 +352,7 @@ pub fn get_packed_item_layout_from_type(
     if is_array(item_type) || is_byte_array(item_type) {
         diagnostics.push(PluginDiagnostic {
             stable_ptr: diagnostic_item,
-            message: "Array field cannot be packed.".into(),
+            message: PACKED_ARRAY.message("Array field cannot be packed."),
             severity: Severity::Error,
         });
         vec!["ERROR".to_string()]
@@ -8,6 +8,8 @@ use cairo_lang_syntax::node::db::SyntaxGroup;
 use cairo_lang_syntax::node::{Terminal, TypedSyntaxNode};
 use cairo_lang_utils::unordered_hash_map::UnorderedHashMap;
 
+use crate::diagnostic_codes::PACKED_ENUM_LAYOUT;
+
 mod layout;
 mod size;
 mod ty;
@@ -65,8 +67,8 @@ pub fn handle_introspect_enum(
         } else {
             diagnostics.push(PluginDiagnostic {
                 stable_ptr: enum_ast.name(db).stable_ptr().0,
-                message: "To be packed, all variants must have fixed layout of same size."
-                    .to_string(),
+                message: PACKED_ENUM_LAYOUT
+                    .message("To be packed, all variants must have fixed layout of same size."),
                 severity: Severity::Error,
             });
             "ERROR".to_string()
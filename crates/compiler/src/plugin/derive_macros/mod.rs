@@ -4,7 +4,9 @@
 //! The input of the macro consists of the AST of the struct or enum and the attributes of the derive macro.
 
 use cairo_lang_defs::patcher::{PatchBuilder, RewriteNode};
-use cairo_lang_defs::plugin::{PluginDiagnostic, PluginGeneratedFile, PluginResult};
+use cairo_lang_defs::plugin::{
+    DynGeneratedFileAuxData, PluginDiagnostic, PluginGeneratedFile, PluginResult,
+};
 use cairo_lang_diagnostics::Severity;
 use cairo_lang_syntax::attribute::structured::{AttributeArgVariant, AttributeStructurize};
 use cairo_lang_syntax::node::ast::Attribute;
@@ -15,6 +17,11 @@ use cairo_lang_syntax::node::{ast, Terminal, TypedSyntaxNode};
 use introspect::{handle_introspect_enum, handle_introspect_struct};
 use print::{handle_print_enum, handle_print_struct};
 
+use crate::aux_data::DojoGeneratedAuxData;
+use crate::diagnostic_codes::{
+    INTROSPECT_AND_PACKED, MISSING_DERIVE_ARGS, UNSUPPORTED_DERIVE_ITEM,
+};
+
 pub mod introspect;
 pub mod print;
 
@@ -53,7 +60,7 @@ pub fn dojo_derive_all(
         code: Some(PluginGeneratedFile {
             name: item_name,
             content: code,
-            aux_data: None,
+            aux_data: Some(DynGeneratedFileAuxData::new(DojoGeneratedAuxData)),
             code_mappings,
         }),
         diagnostics,
@@ -129,9 +136,10 @@ pub fn handle_derive_attrs(
             // Currently Dojo plugin doesn't support derive macros on other items than struct and enum.
             diagnostics.push(PluginDiagnostic {
                 stable_ptr: item_ast.stable_ptr().0,
-                message:
-                    "Dojo plugin doesn't support derive macros on other items than struct and enum."
-                        .to_string(),
+                message: UNSUPPORTED_DERIVE_ITEM.message(
+                    "Dojo plugin doesn't support derive macros on other items than struct and \
+                     enum.",
+                ),
                 severity: Severity::Error,
             });
         }
@@ -164,7 +172,7 @@ pub fn extract_derive_attr_names(
             if args.is_empty() {
                 diagnostics.push(PluginDiagnostic {
                     stable_ptr: attr.stable_ptr().0,
-                    message: "Expected args.".into(),
+                    message: MISSING_DERIVE_ARGS.message("Expected args."),
                     severity: Severity::Error,
                 });
                 None
@@ -199,10 +207,10 @@ fn check_for_derive_attr_conflicts(
     {
         diagnostics.push(PluginDiagnostic {
             stable_ptr: diagnostic_item,
-            message: format!(
+            message: INTROSPECT_AND_PACKED.message(format!(
                 "{} and {} attributes cannot be used at a same time.",
                 DOJO_INTROSPECT_DERIVE, DOJO_PACKED_DERIVE
-            ),
+            )),
             severity: Severity::Error,
         });
     }
@@ -7,8 +7,13 @@ use cairo_lang_defs::plugin_utils::unsupported_bracket_diagnostic;
 use cairo_lang_diagnostics::Severity;
 use cairo_lang_syntax::node::{ast, TypedStablePtr, TypedSyntaxNode};
 
+use crate::diagnostic_codes::{INVALID_MACRO_ARGUMENTS, MISSING_MACRO_MODELS};
+
 use super::unsupported_arg_diagnostic;
 
+/// The name of the file generated by the macro.
+pub const GENERATED_FILE_NAME: &str = "delete_inline_macro";
+
 #[derive(Debug, Default)]
 pub struct DeleteMacro;
 
@@ -36,7 +41,8 @@ impl InlineMacroExprPlugin for DeleteMacro {
                 code: None,
                 diagnostics: vec![PluginDiagnostic {
                     stable_ptr: arg_list.arguments(db).stable_ptr().untyped(),
-                    message: "Invalid arguments. Expected \"(world, (models,))\"".to_string(),
+                    message: INVALID_MACRO_ARGUMENTS
+                        .message("Invalid arguments. Expected \"(world, (models,))\""),
                     severity: Severity::Error,
                 }],
             };
@@ -72,7 +78,8 @@ impl InlineMacroExprPlugin for DeleteMacro {
                 return InlinePluginResult {
                     code: None,
                     diagnostics: vec![PluginDiagnostic {
-                        message: "Invalid arguments. Expected \"(world, (models,))\"".to_string(),
+                        message: INVALID_MACRO_ARGUMENTS
+                            .message("Invalid arguments. Expected \"(world, (models,))\""),
                         stable_ptr: arg_list.arguments(db).stable_ptr().untyped(),
                         severity: Severity::Error,
                     }],
@@ -84,7 +91,7 @@ impl InlineMacroExprPlugin for DeleteMacro {
             return InlinePluginResult {
                 code: None,
                 diagnostics: vec![PluginDiagnostic {
-                    message: "Invalid arguments: No models provided.".to_string(),
+                    message: MISSING_MACRO_MODELS.message("Invalid arguments: No models provided."),
                     stable_ptr: arg_list.arguments(db).stable_ptr().untyped(),
                     severity: Severity::Error,
                 }],
@@ -104,7 +111,7 @@ impl InlineMacroExprPlugin for DeleteMacro {
 
         InlinePluginResult {
             code: Some(PluginGeneratedFile {
-                name: "delete_inline_macro".into(),
+                name: GENERATED_FILE_NAME.into(),
                 content: code,
                 code_mappings,
                 aux_data: None,
@@ -7,8 +7,13 @@ use cairo_lang_defs::plugin_utils::unsupported_bracket_diagnostic;
 use cairo_lang_diagnostics::Severity;
 use cairo_lang_syntax::node::{ast, TypedStablePtr, TypedSyntaxNode};
 
+use crate::diagnostic_codes::{INVALID_MACRO_ARGUMENTS, MISSING_MACRO_MODELS};
+
 use super::unsupported_arg_diagnostic;
 
+/// The name of the file generated by the macro.
+pub const GENERATED_FILE_NAME: &str = "emit_inline_macro";
+
 #[derive(Debug, Default)]
 pub struct EmitMacro;
 
@@ -36,7 +41,8 @@ impl InlineMacroExprPlugin for EmitMacro {
                 code: None,
                 diagnostics: vec![PluginDiagnostic {
                     stable_ptr: arg_list.arguments(db).stable_ptr().untyped(),
-                    message: "Invalid arguments. Expected \"emit!(world, (events,))\"".to_string(),
+                    message: INVALID_MACRO_ARGUMENTS
+                        .message("Invalid arguments. Expected \"emit!(world, (events,))\""),
                     severity: Severity::Error,
                 }],
             };
@@ -72,7 +78,8 @@ impl InlineMacroExprPlugin for EmitMacro {
                 return InlinePluginResult {
                     code: None,
                     diagnostics: vec![PluginDiagnostic {
-                        message: "Invalid arguments. Expected \"(world, (events,))\"".to_string(),
+                        message: INVALID_MACRO_ARGUMENTS
+                            .message("Invalid arguments. Expected \"(world, (events,))\""),
                         stable_ptr: arg_list.arguments(db).stable_ptr().untyped(),
                         severity: Severity::Error,
                     }],
@@ -84,7 +91,7 @@ impl InlineMacroExprPlugin for EmitMacro {
             return InlinePluginResult {
                 code: None,
                 diagnostics: vec![PluginDiagnostic {
-                    message: "Invalid arguments: No models provided.".to_string(),
+                    message: MISSING_MACRO_MODELS.message("Invalid arguments: No models provided."),
                     stable_ptr: arg_list.arguments(db).stable_ptr().untyped(),
                     severity: Severity::Error,
                 }],
@@ -108,7 +115,7 @@ impl InlineMacroExprPlugin for EmitMacro {
 
         InlinePluginResult {
             code: Some(PluginGeneratedFile {
-                name: "emit_inline_macro".into(),
+                name: GENERATED_FILE_NAME.into(),
                 content: code,
                 code_mappings,
                 aux_data: None,
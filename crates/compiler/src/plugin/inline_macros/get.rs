@@ -9,8 +9,13 @@ use cairo_lang_syntax::node::ast::Expr;
 use cairo_lang_syntax::node::{ast, TypedStablePtr, TypedSyntaxNode};
 use itertools::Itertools;
 
+use crate::diagnostic_codes::{INVALID_MACRO_ARGUMENTS, MISSING_MACRO_MODELS};
+
 use super::{extract_models, unsupported_arg_diagnostic, CAIRO_ERR_MSG_LEN};
 
+/// The name of the file generated by the macro.
+pub const GENERATED_FILE_NAME: &str = "get_inline_macro";
+
 #[derive(Debug, Default)]
 pub struct GetMacro;
 
@@ -38,8 +43,8 @@ impl InlineMacroExprPlugin for GetMacro {
                 code: None,
                 diagnostics: vec![PluginDiagnostic {
                     stable_ptr: syntax.stable_ptr().untyped(),
-                    message: "Invalid arguments. Expected \"get!(world, keys, (models,))\""
-                        .to_string(),
+                    message: INVALID_MACRO_ARGUMENTS
+                        .message("Invalid arguments. Expected \"get!(world, keys, (models,))\""),
                     severity: Severity::Error,
                 }],
             };
@@ -69,7 +74,7 @@ impl InlineMacroExprPlugin for GetMacro {
                 code: None,
                 diagnostics: vec![PluginDiagnostic {
                     stable_ptr: syntax.stable_ptr().untyped(),
-                    message: "Model types cannot be empty".to_string(),
+                    message: MISSING_MACRO_MODELS.message("Model types cannot be empty"),
                     severity: Severity::Error,
                 }],
             };
@@ -101,7 +106,7 @@ impl InlineMacroExprPlugin for GetMacro {
 
         InlinePluginResult {
             code: Some(PluginGeneratedFile {
-                name: "get_inline_macro".into(),
+                name: GENERATED_FILE_NAME.into(),
                 content: code,
                 code_mappings,
                 aux_data: None,
@@ -7,9 +7,14 @@ use cairo_lang_defs::plugin_utils::unsupported_bracket_diagnostic;
 use cairo_lang_diagnostics::Severity;
 use cairo_lang_syntax::node::{ast, TypedStablePtr, TypedSyntaxNode};
 
+use crate::diagnostic_codes::{INVALID_MACRO_ARGUMENTS, MISSING_ANNOTATIONS};
+
 use super::unsupported_arg_diagnostic;
 use super::utils::{extract_namespaces, load_manifest_models_and_namespaces};
 
+/// The name of the file generated by the macro.
+pub const GENERATED_FILE_NAME: &str = "get_models_test_class_hashes_macro";
+
 #[derive(Debug, Default)]
 pub struct GetModelsTestClassHashes;
 
@@ -35,10 +40,11 @@ impl InlineMacroExprPlugin for GetModelsTestClassHashes {
                 code: None,
                 diagnostics: vec![PluginDiagnostic {
                     stable_ptr: syntax.stable_ptr().untyped(),
-                    message: "Invalid arguments. Expected \
-                              \"get_models_test_class_hashes!([\"ns1\", \"ns2\")]\" or \
-                              \"get_models_test_class_hashes!()\"."
-                        .to_string(),
+                    message: INVALID_MACRO_ARGUMENTS.message(
+                        "Invalid arguments. Expected \
+                         \"get_models_test_class_hashes!([\"ns1\", \"ns2\")]\" or \
+                         \"get_models_test_class_hashes!()\".",
+                    ),
                     severity: Severity::Error,
                 }],
             };
@@ -70,9 +76,10 @@ impl InlineMacroExprPlugin for GetModelsTestClassHashes {
                         code: None,
                         diagnostics: vec![PluginDiagnostic {
                             stable_ptr: syntax.stable_ptr().untyped(),
-                            message: "Failed to load models and namespaces, ensure you have run \
-                                             `sozo build` first."
-                                .to_string(),
+                            message: MISSING_ANNOTATIONS.message(
+                                "Failed to load models and namespaces, ensure you have run \
+                                 `sozo build` first.",
+                            ),
                             severity: Severity::Error,
                         }],
                     };
@@ -96,7 +103,7 @@ impl InlineMacroExprPlugin for GetModelsTestClassHashes {
 
         InlinePluginResult {
             code: Some(PluginGeneratedFile {
-                name: "get_models_test_class_hashes_macro".into(),
+                name: GENERATED_FILE_NAME.into(),
                 content: code,
                 code_mappings,
                 aux_data: None,
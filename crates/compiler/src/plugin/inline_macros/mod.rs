@@ -8,6 +8,8 @@ use cairo_lang_syntax::node::db::SyntaxGroup;
 use cairo_lang_syntax::node::{ast, Terminal, TypedStablePtr, TypedSyntaxNode};
 use smol_str::SmolStr;
 
+use crate::diagnostic_codes::{INVALID_MACRO_MODEL, UNSUPPORTED_MACRO_ARGUMENTS};
+
 pub mod delete;
 pub mod emit;
 pub mod get;
@@ -27,6 +29,18 @@ pub use spawn_test_world::SpawnTestWorld;
 
 const CAIRO_ERR_MSG_LEN: usize = 31;
 
+/// The names of the files generated by the inline macros, which have no aux data to identify
+/// the code generated by the plugin.
+pub const GENERATED_FILE_NAMES: &[&str] = &[
+    delete::GENERATED_FILE_NAME,
+    emit::GENERATED_FILE_NAME,
+    get::GENERATED_FILE_NAME,
+    get_models_test_class_hashes::GENERATED_FILE_NAME,
+    selector_from_tag::GENERATED_FILE_NAME,
+    set::GENERATED_FILE_NAME,
+    spawn_test_world::GENERATED_FILE_NAME,
+];
+
 pub fn extract_models(
     db: &dyn SyntaxGroup,
     expression: &ast::Expr,
@@ -55,7 +69,7 @@ pub fn extract_models(
                     let ast::GenericArg::Unnamed(unnamed) = param else {
                         return Err(PluginDiagnostic {
                             stable_ptr: param.stable_ptr().untyped(),
-                            message: "Should be an unnamed argument".to_string(),
+                            message: INVALID_MACRO_MODEL.message("Should be an unnamed argument"),
                             severity: Severity::Error,
                         });
                     };
@@ -63,7 +77,7 @@ pub fn extract_models(
                     let ast::GenericArgValue::Expr(expr) = unnamed.value(db) else {
                         return Err(PluginDiagnostic {
                             stable_ptr: unnamed.stable_ptr().untyped(),
-                            message: "Should be an expression".to_string(),
+                            message: INVALID_MACRO_MODEL.message("Should be an expression"),
                             severity: Severity::Error,
                         });
                     };
@@ -81,10 +95,10 @@ pub fn extract_models(
         _ => {
             return Err(PluginDiagnostic {
                 stable_ptr: expression.stable_ptr().untyped(),
-                message: format!(
+                message: INVALID_MACRO_MODEL.message(format!(
                     "Unsupported expression type: {}",
                     expression.as_syntax_node().get_text(db)
-                ),
+                )),
                 severity: Severity::Error,
             });
         }
@@ -101,10 +115,10 @@ pub fn unsupported_arg_diagnostic(
         code: None,
         diagnostics: vec![PluginDiagnostic {
             stable_ptr: macro_ast.stable_ptr().untyped(),
-            message: format!(
+            message: UNSUPPORTED_MACRO_ARGUMENTS.message(format!(
                 "Macro {} does not support this arg type",
                 macro_ast.path(db).as_syntax_node().get_text(db)
-            ),
+            )),
             severity: Severity::Error,
         }],
     }
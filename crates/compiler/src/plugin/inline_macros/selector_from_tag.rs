@@ -8,6 +8,11 @@ use cairo_lang_diagnostics::Severity;
 use cairo_lang_syntax::node::{ast, TypedStablePtr, TypedSyntaxNode};
 use dojo_types::naming;
 
+use crate::diagnostic_codes::{INVALID_MACRO_ARGUMENTS, INVALID_TAG};
+
+/// The name of the file generated by the macro.
+pub const GENERATED_FILE_NAME: &str = "selector_from_tag_macro";
+
 #[derive(Debug, Default)]
 pub struct SelectorFromTagMacro;
 
@@ -33,8 +38,8 @@ impl InlineMacroExprPlugin for SelectorFromTagMacro {
                 code: None,
                 diagnostics: vec![PluginDiagnostic {
                     stable_ptr: syntax.stable_ptr().untyped(),
-                    message: "Invalid arguments. Expected \"selector_from_tag!(\"tag\")\""
-                        .to_string(),
+                    message: INVALID_MACRO_ARGUMENTS
+                        .message("Invalid arguments. Expected \"selector_from_tag!(\"tag\")\""),
                     severity: Severity::Error,
                 }],
             };
@@ -47,8 +52,8 @@ impl InlineMacroExprPlugin for SelectorFromTagMacro {
                 code: None,
                 diagnostics: vec![PluginDiagnostic {
                     stable_ptr: syntax.stable_ptr().untyped(),
-                    message: "Invalid tag. Tag must be in the format of `namespace-name`."
-                        .to_string(),
+                    message: INVALID_TAG
+                        .message("Invalid tag. Tag must be in the format of `namespace-name`."),
                     severity: Severity::Error,
                 }],
             };
@@ -63,7 +68,7 @@ impl InlineMacroExprPlugin for SelectorFromTagMacro {
 
         InlinePluginResult {
             code: Some(PluginGeneratedFile {
-                name: "selector_from_tag_macro".into(),
+                name: GENERATED_FILE_NAME.into(),
                 content: code,
                 code_mappings,
                 aux_data: None,
@@ -7,8 +7,13 @@ use cairo_lang_defs::plugin_utils::unsupported_bracket_diagnostic;
 use cairo_lang_diagnostics::Severity;
 use cairo_lang_syntax::node::{ast, TypedStablePtr, TypedSyntaxNode};
 
+use crate::diagnostic_codes::{INVALID_MACRO_ARGUMENTS, MISSING_MACRO_MODELS};
+
 use super::unsupported_arg_diagnostic;
 
+/// The name of the file generated by the macro.
+pub const GENERATED_FILE_NAME: &str = "set_inline_macro";
+
 #[derive(Debug, Default)]
 pub struct SetMacro;
 
@@ -50,7 +55,8 @@ impl InlineMacroExprPlugin for SetMacro {
                 code: None,
                 diagnostics: vec![PluginDiagnostic {
                     stable_ptr: arg_list.arguments(db).stable_ptr().untyped(),
-                    message: "Invalid arguments. Expected \"(world, (models,))\"".to_string(),
+                    message: INVALID_MACRO_ARGUMENTS
+                        .message("Invalid arguments. Expected \"(world, (models,))\""),
                     severity: Severity::Error,
                 }],
             };
@@ -86,7 +92,8 @@ impl InlineMacroExprPlugin for SetMacro {
                 return InlinePluginResult {
                     code: None,
                     diagnostics: vec![PluginDiagnostic {
-                        message: "Invalid arguments. Expected \"(world, (models,))\"".to_string(),
+                        message: INVALID_MACRO_ARGUMENTS
+                            .message("Invalid arguments. Expected \"(world, (models,))\""),
                         stable_ptr: arg_list.arguments(db).stable_ptr().untyped(),
                         severity: Severity::Error,
                     }],
@@ -98,7 +105,7 @@ impl InlineMacroExprPlugin for SetMacro {
             return InlinePluginResult {
                 code: None,
                 diagnostics: vec![PluginDiagnostic {
-                    message: "Invalid arguments: No models provided.".to_string(),
+                    message: MISSING_MACRO_MODELS.message("Invalid arguments: No models provided."),
                     stable_ptr: arg_list.arguments(db).stable_ptr().untyped(),
                     severity: Severity::Error,
                 }],
@@ -120,7 +127,7 @@ impl InlineMacroExprPlugin for SetMacro {
 
         InlinePluginResult {
             code: Some(PluginGeneratedFile {
-                name: "set_inline_macro".into(),
+                name: GENERATED_FILE_NAME.into(),
                 content: code,
                 code_mappings,
                 aux_data: None,
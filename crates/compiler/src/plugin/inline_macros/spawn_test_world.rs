@@ -8,9 +8,14 @@ use cairo_lang_diagnostics::Severity;
 use cairo_lang_syntax::node::{ast, TypedStablePtr, TypedSyntaxNode};
 use tracing::trace;
 
+use crate::diagnostic_codes::{INVALID_MACRO_ARGUMENTS, MISSING_ANNOTATIONS};
+
 use super::unsupported_arg_diagnostic;
 use super::utils::{extract_namespaces, load_manifest_models_and_namespaces};
 
+/// The name of the file generated by the macro.
+pub const GENERATED_FILE_NAME: &str = "spawn_test_world_macro";
+
 #[derive(Debug, Default)]
 pub struct SpawnTestWorld;
 
@@ -36,9 +41,10 @@ impl InlineMacroExprPlugin for SpawnTestWorld {
                 code: None,
                 diagnostics: vec![PluginDiagnostic {
                     stable_ptr: syntax.stable_ptr().untyped(),
-                    message: "Invalid arguments. Expected \"spawn_test_world!()\" or \
-                              \"spawn_test_world!([\"ns1\"])"
-                        .to_string(),
+                    message: INVALID_MACRO_ARGUMENTS.message(
+                        "Invalid arguments. Expected \"spawn_test_world!()\" or \
+                         \"spawn_test_world!([\"ns1\"])",
+                    ),
                     severity: Severity::Error,
                 }],
             };
@@ -70,9 +76,10 @@ impl InlineMacroExprPlugin for SpawnTestWorld {
                         code: None,
                         diagnostics: vec![PluginDiagnostic {
                             stable_ptr: syntax.stable_ptr().untyped(),
-                            message: "failed to load models and namespaces, ensure you have run \
-                                  `sozo build` first."
-                                .to_string(),
+                            message: MISSING_ANNOTATIONS.message(
+                                "failed to load models and namespaces, ensure you have run \
+                                 `sozo build` first.",
+                            ),
                             severity: Severity::Error,
                         }],
                     };
@@ -101,7 +108,7 @@ impl InlineMacroExprPlugin for SpawnTestWorld {
 
         InlinePluginResult {
             code: Some(PluginGeneratedFile {
-                name: "spawn_test_world_macro".into(),
+                name: GENERATED_FILE_NAME.into(),
                 content: code,
                 code_mappings,
                 aux_data: None,
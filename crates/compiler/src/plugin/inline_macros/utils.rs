@@ -9,10 +9,9 @@ use cairo_lang_syntax::node::kind::SyntaxKind;
 use cairo_lang_syntax::node::{SyntaxNode, TypedStablePtr, TypedSyntaxNode};
 use camino::Utf8PathBuf;
 use dojo_types::naming;
-use scarb::compiler::Profile;
-use scarb::core::Config;
 
 use crate::compiler::annotation::DojoAnnotation;
+use crate::diagnostic_codes::INVALID_NAMESPACE_LIST;
 use crate::namespace_config::{DOJO_ANNOTATIONS_DIR_CFG_KEY, WORKSPACE_CURRENT_PROFILE_CFG_KEY};
 
 #[derive(Debug)]
@@ -42,20 +41,9 @@ pub fn load_manifest_models_and_namespaces(
     whitelisted_namespaces: &[String],
 ) -> anyhow::Result<(Vec<String>, Vec<String>)> {
     let dojo_manifests_dir = get_dojo_manifests_dir(cfg_set.clone())?;
-    let scarb_toml = dojo_manifests_dir
-        .parent()
-        .expect("Profile dir should have parent")
-        .parent()
-        .expect("Manifests dir should have parent")
-        .join("Scarb.toml");
 
-    let config = Config::builder(scarb_toml.clone())
-        .profile(Profile::new(get_current_profile(cfg_set.clone())?.into())?)
-        .build()?;
-
-    let ws = scarb::ops::read_workspace(config.manifest_path(), &config)?;
-
-    let annotations = DojoAnnotation::read(&ws)?;
+    // The directory is the target directory of the dojo package being compiled.
+    let annotations = DojoAnnotation::read_from_dir(&dojo_manifests_dir)?;
 
     let mut models = HashSet::<String>::new();
     let mut namespaces = HashSet::<String>::new();
@@ -120,7 +108,7 @@ pub fn extract_namespaces(
                 } else {
                     return Err(PluginDiagnostic {
                         stable_ptr: element.stable_ptr().untyped(),
-                        message: "Expected a string literal".to_string(),
+                        message: INVALID_NAMESPACE_LIST.message("Expected a string literal"),
                         severity: Severity::Error,
                     });
                 }
@@ -129,8 +117,8 @@ pub fn extract_namespaces(
         _ => {
             return Err(PluginDiagnostic {
                 stable_ptr: expression.stable_ptr().untyped(),
-                message: "The list of namespaces should be a fixed size array of strings."
-                    .to_string(),
+                message: INVALID_NAMESPACE_LIST
+                    .message("The list of namespaces should be a fixed size array of strings."),
                 severity: Severity::Error,
             });
         }
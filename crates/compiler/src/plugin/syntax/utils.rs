@@ -1,6 +1,10 @@
+use cairo_lang_filesystem::ids::{Directory, FileLongId};
 use cairo_lang_syntax::node::ast::OptionTypeClause;
 use cairo_lang_syntax::node::db::SyntaxGroup;
-use cairo_lang_syntax::node::{ast, Terminal, TypedSyntaxNode};
+use cairo_lang_syntax::node::kind::SyntaxKind;
+use cairo_lang_syntax::node::{ast, SyntaxNode, Terminal, TypedSyntaxNode};
+
+use crate::CAIRO_PATH_SEPARATOR;
 
 /// Gets the name, modifiers and type of a function parameter.
 ///
@@ -29,3 +33,63 @@ pub fn get_parameter_info(db: &dyn SyntaxGroup, param: ast::Param) -> (String, S
 
     (name, modifiers, param_type)
 }
+
+/// Gets the Cairo path of the module containing a syntax node, like `my_crate::models`.
+///
+/// The path is computed from the location of the file in its crate, and from the inline
+/// modules enclosing the node.
+///
+/// # Arguments
+///
+/// * `db` - The syntax group.
+/// * `node` - The syntax node.
+///
+/// # Returns
+///
+/// * The module path, `None` if the node is not in a file of a crate on disk.
+pub fn get_module_path(db: &dyn SyntaxGroup, node: &SyntaxNode) -> Option<String> {
+    let FileLongId::OnDisk(file_path) = db.lookup_intern_file(node.stable_ptr().file_id(db)) else {
+        return None;
+    };
+
+    // The most nested crate root containing the file.
+    let (crate_name, relative_path) = db
+        .crates()
+        .into_iter()
+        .filter_map(|crate_id| {
+            let Directory::Real(root) = db.crate_config(crate_id)?.root else {
+                return None;
+            };
+
+            let relative_path = file_path.strip_prefix(&root).ok()?.to_path_buf();
+            Some((crate_id.name(db.upcast()), relative_path))
+        })
+        .min_by_key(|(_, relative_path)| relative_path.components().count())?;
+
+    let mut segments = vec![crate_name.to_string()];
+
+    for component in relative_path.with_extension("").components() {
+        segments.push(component.as_os_str().to_str()?.to_string());
+    }
+
+    // The crate root file doesn't add a module to the path.
+    if relative_path.components().count() == 1 && segments.last()? == "lib" {
+        segments.pop();
+    }
+
+    let mut inline_modules = vec![];
+    let mut current = node.parent();
+
+    while let Some(parent) = current {
+        if parent.kind(db) == SyntaxKind::ItemModule {
+            let module_ast = ast::ItemModule::from_syntax_node(db, parent.clone());
+            inline_modules.push(module_ast.name(db).text(db).to_string());
+        }
+
+        current = parent.parent();
+    }
+
+    segments.extend(inline_modules.into_iter().rev());
+
+    Some(segments.join(CAIRO_PATH_SEPARATOR))
+}
@@ -3,6 +3,10 @@ use cairo_lang_diagnostics::Severity;
 use cairo_lang_syntax::node::db::SyntaxGroup;
 use cairo_lang_syntax::node::{ast, ids};
 
+use crate::diagnostic_codes::{
+    MULTIPLE_WORLD_PARAMS, SELF_AND_WORLD_PARAMS, WORLD_PARAM_NOT_FIRST, WORLD_PARAM_NOT_SNAPSHOT,
+};
+
 use super::utils as syntax_utils;
 
 const WORLD_PARAM_NAME: &str = "world";
@@ -55,8 +59,8 @@ pub fn parse_world_injection(
                 if name.eq(super::self_param::SELF_PARAM_NAME) && has_world_injected {
                     diagnostics.push(PluginDiagnostic {
                         stable_ptr: fn_diagnostic_item,
-                        message: "You cannot use `self` and `world` parameters together."
-                            .to_string(),
+                        message: SELF_AND_WORLD_PARAMS
+                            .message("You cannot use `self` and `world` parameters together."),
                         severity: Severity::Error,
                     });
                 }
@@ -67,7 +71,7 @@ pub fn parse_world_injection(
             if has_world_injected {
                 diagnostics.push(PluginDiagnostic {
                     stable_ptr: fn_diagnostic_item,
-                    message: "Only one world parameter is allowed".to_string(),
+                    message: MULTIPLE_WORLD_PARAMS.message("Only one world parameter is allowed"),
                     severity: Severity::Error,
                 });
 
@@ -79,7 +83,8 @@ pub fn parse_world_injection(
             if idx != 0 {
                 diagnostics.push(PluginDiagnostic {
                     stable_ptr: fn_diagnostic_item,
-                    message: "World parameter must be the first parameter.".to_string(),
+                    message: WORLD_PARAM_NOT_FIRST
+                        .message("World parameter must be the first parameter."),
                     severity: Severity::Error,
                 });
 
@@ -94,8 +99,8 @@ pub fn parse_world_injection(
                 if param_type == WORLD_PARAM_TYPE {
                     diagnostics.push(PluginDiagnostic {
                         stable_ptr: fn_diagnostic_item,
-                        message: "World parameter must be a snapshot if `ref` is not used."
-                            .to_string(),
+                        message: WORLD_PARAM_NOT_SNAPSHOT
+                            .message("World parameter must be a snapshot if `ref` is not used."),
                         severity: Severity::Error,
                     });
                 }
@@ -1,6 +1,7 @@
 use anyhow::Result;
 use camino::Utf8Path;
-use scarb::{core::Workspace, flock::Filesystem};
+use scarb::core::{Package, Workspace};
+use scarb::flock::Filesystem;
 
 /// Handy enum for selecting the current profile or all profiles.
 #[derive(Debug)]
@@ -55,6 +56,11 @@ impl FilesystemExt for Filesystem {
 pub trait WorkspaceExt {
     /// Returns the target directory for the current profile.
     fn target_dir_profile(&self) -> Filesystem;
+    /// Returns the target directory of the artifacts of a dojo package for the current profile.
+    ///
+    /// The artifacts are always written in a subdirectory of the profile directory named after
+    /// the package, so their paths don't depend on the other members of the workspace.
+    fn dojo_target_dir(&self, package: &Package) -> Filesystem;
     /// Checks if the current profile is valid for the workspace.
     fn profile_check(&self) -> Result<()>;
 }
@@ -68,6 +74,10 @@ impl WorkspaceExt for Workspace<'_> {
         )
     }
 
+    fn dojo_target_dir(&self, package: &Package) -> Filesystem {
+        self.target_dir_profile().child(package.id.name.as_str())
+    }
+
     fn profile_check(&self) -> Result<()> {
         if let Err(e) = self.current_profile() {
             if e.to_string().contains("has no profile") {
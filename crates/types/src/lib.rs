@@ -7,6 +7,10 @@ pub mod model;
 
 pub mod naming;
 pub mod packing;
+pub mod primitive;
+pub mod primitive_conversion;
+pub mod schema;
+pub mod system;
 
 #[allow(rust_2018_idioms)]
 #[allow(unused)]